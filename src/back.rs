@@ -1 +1,2 @@
 pub mod code_generator;
+pub mod linker;
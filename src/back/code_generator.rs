@@ -1,4 +1,4 @@
-use crate::back::code_generator::generator::CodeGenerator;
+use crate::back::code_generator::generator::{generate_load_function, CodeGenerator};
 use crate::middle::format::ir_types::{fn_name_from_block, FunctionName};
 use crate::middle::format::types::Program;
 
@@ -11,6 +11,7 @@ pub struct MFunction {
 
 pub struct GeneratedCode {
     pub functions: Vec<MFunction>,
+    pub load_functions: Vec<String>,
 }
 
 impl GeneratedCode {
@@ -33,11 +34,22 @@ impl Context {
 }
 
 pub fn generate_code(program: &Program) -> GeneratedCode {
-    let mut generated_code = GeneratedCode { functions: vec![] };
+    let mut generated_code = GeneratedCode {
+        functions: vec![],
+        load_functions: vec![],
+    };
+
+    let load_function = generate_load_function(program, &mut generated_code);
+    generated_code
+        .load_functions
+        .push(load_function.name.clone());
+    generated_code.add_function(load_function);
+
     for (name, def) in &program.function_definitions {
+        // blocks that the IR already named come first, new blocks are numbered after them
         let mut context = Context {
             fn_name: name.clone(),
-            block_count: 0,
+            block_count: def.block_count,
         };
 
         let mut body = vec![];
@@ -85,7 +97,7 @@ mod tests {
         );
 
         let mut program_merger = ProgramMerger::new("test");
-        program_merger.read_package("test", mock_fs);
+        program_merger.read_package("test", mock_fs).unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -103,7 +115,7 @@ mod tests {
         );
 
         let mut program_merger = ProgramMerger::new("test");
-        program_merger.read_package("test", mock_fs);
+        program_merger.read_package("test", mock_fs).unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -122,7 +134,7 @@ mod tests {
         );
 
         let mut program_merger = ProgramMerger::new("test");
-        program_merger.read_package("test", mock_fs);
+        program_merger.read_package("test", mock_fs).unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -131,4 +143,247 @@ mod tests {
 
         println!("{}", hmasm);
     }
+
+    #[test]
+    fn test_generate_increment_code() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 0; a++; --a; a += 3; a *= a; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm.contains("scoreboard players add test/root/0_a blst 1\n"));
+        assert!(hmasm.contains("scoreboard players remove test/root/0_a blst 1\n"));
+        assert!(hmasm.contains("scoreboard players add test/root/0_a blst 3\n"));
+        assert!(hmasm
+            .contains("scoreboard players operation test/root/0_a blst *= test/root/0_a blst\n"));
+    }
+
+    #[test]
+    fn test_generate_chained_conditions() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 0; let b: int = 0; if (a > 1 && b < 3) { a = 2; } for (let i: int = 0; i < 9; i += 1) { a = 5; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm.contains(
+            "execute if score test/root/0_a blst matches 2.. if score test/root/0_b blst matches ..2 run scoreboard players set test/root/0_a blst 2\n"
+        ));
+        assert!(hmasm.contains("execute if score test/root/0_i blst matches 9.. run return 0\n"));
+    }
+
+    #[test]
+    fn test_generate_returns() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 0; if (a > 1) { return; } while (a < 5) { a++; if (a == 3) { break; } } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        // since 1.20.3 a bare `return` is not a command, so every exit returns a value
+        assert!(hmasm.contains("    return 0\n"));
+        assert!(hmasm.contains("execute if score test/root/0_a blst matches 5.. run return 0\n"));
+        assert!(
+            hmasm.contains("execute unless score test/root/0_main_1 blst matches 0 run return 0\n")
+        );
+        assert!(hmasm
+            .lines()
+            .all(|line| !line.trim_end().ends_with("return")));
+    }
+
+    #[test]
+    fn test_generate_recursive_call_frames() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "rec fn count(n: int) -> int { if (n == 0) { return 0; } return count(n - 1) + 1; } pub fn main() { let r: int = count(3); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm.contains(
+            "data modify storage blst:stack frames append value {}\n    execute store result storage blst:stack frames[-1].v0 int 1 run scoreboard players get test/root/0_n blst\n"
+        ));
+        assert!(hmasm.contains(
+            "function test:root/0_count\n    execute store result score test/root/0_n blst run data get storage blst:stack frames[-1].v0\n"
+        ));
+        assert!(hmasm.contains("data remove storage blst:stack frames[-1]\n"));
+    }
+
+    #[test]
+    fn test_generate_struct_fields() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "struct Point { x: int, y: int } pub fn main() { let p: Point; p.x = 1; p.y = 2; let q: Point = p; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm.contains("scoreboard players set test/root/0_p blst 1\n"));
+        assert!(hmasm.contains("scoreboard players set test/root/0_p.1 blst 2\n"));
+        assert!(hmasm.contains(
+            "scoreboard players operation test/root/0_q.1 blst = test/root/0_p.1 blst\n"
+        ));
+    }
+
+    #[test]
+    fn test_generate_arrays() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let xs: [int; 3]; let i: int = 2; xs[0] = 7; xs[i] = xs[0]; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm
+            .contains("data modify storage blst:vars \"test/root/0_xs\" set value [0, 0, 0]\n"));
+        assert!(hmasm.contains("data modify storage blst:vars \"test/root/0_xs\"[0] set value 7\n"));
+        assert!(hmasm.contains(
+            "execute store result storage blst:args i int 1 run scoreboard players get test/root/0_i blst\n"
+        ));
+        assert!(hmasm.contains(
+            "    $execute store result storage blst:vars \"test/root/0_xs\"[$(i)] int 1 run scoreboard players get"
+        ));
+    }
+
+    #[test]
+    fn test_generate_strings() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn echo(s: string) -> string { return s; } \
+            pub fn main() { let a: string = \"hello\"; let b = echo(a); println!(b, 1); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(
+            hmasm.contains("data modify storage blst:vars \"test/root/0_a\" set value \"hello\"\n")
+        );
+        assert!(hmasm.contains(
+            "data modify storage blst:vars \"test/root/0_s\" set from storage blst:vars \"test/root/0_a\"\n"
+        ));
+        assert!(hmasm.contains(
+            "data modify storage blst:vars \"!return\" set from storage blst:vars \"test/root/0_s\"\n"
+        ));
+        assert!(hmasm.contains(
+            "data modify storage blst:vars \"test/root/0_b\" set from storage blst:vars \"!return\"\n"
+        ));
+        assert!(hmasm.contains(
+            "tellraw @a [\"\",{\"nbt\":\"\\\"test/root/0_b\\\"\",\"storage\":\"blst:vars\"},\" \",{\"text\":\"1\"}]\n"
+        ));
+    }
+
+    #[test]
+    fn test_generate_compounds() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let x: int = 3; let c = { hp: 20, name: \"Bob\", pos: { x: x, y: 2 } }; \
+            let y = c.pos.y; c.hp = c.hp + x; let d: { hp: int } = { hp: 1 }; d = { hp: d.hp }; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm.contains(
+            "data modify storage blst:vars \"test/root/0_c\" set value {hp: 20, name: \"Bob\", pos: {y: 2}}\n"
+        ));
+        assert!(hmasm.contains(
+            "execute store result storage blst:vars \"test/root/0_c\".pos.x int 1 run scoreboard players get test/root/0_x blst\n"
+        ));
+        assert!(hmasm.contains(
+            "execute store result score test/root/0_y blst run data get storage blst:vars \"test/root/0_c\".pos.y\n"
+        ));
+        assert!(hmasm.contains(
+            "execute store result storage blst:vars \"test/root/0_c\".hp int 1 run scoreboard players get test/root/0_main_0 blst\n"
+        ));
+        // the new value of `d` reads `d`, so it is built in a temporary first
+        assert!(hmasm.contains(
+            "data modify storage blst:vars \"test/root/0_d\" set from storage blst:vars \"test/root/0_main_0\"\n"
+        ));
+    }
+
+    #[test]
+    fn test_generate_bools() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 3; let b: bool = a > 2; let c = !b; if (b) { a = 1; } let d = a as bool; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let hmasm = flatten_to_hmasm(&generate_code(&program));
+
+        assert!(hmasm.contains(
+            "execute store result score test/root/0_c blst if score test/root/0_c blst matches 0\n"
+        ));
+        assert!(hmasm.contains(
+            "execute if score test/root/0_b blst matches 1 run scoreboard players set test/root/0_a blst 1\n"
+        ));
+        assert!(hmasm.contains(
+            "execute store result score test/root/0_d blst unless score test/root/0_d blst matches 0\n"
+        ));
+    }
 }
@@ -1,32 +1,134 @@
 use crate::back::code_generator::{Context, GeneratedCode, MFunction};
+use crate::back::linker::resource_location;
 use crate::middle::format::ir_types::CompareOp;
 use crate::middle::format::ir_types::Cond;
-use crate::middle::format::ir_types::{Address, IrBlock, IrIf, IrStatement};
-use crate::middle::format::ir_types::{AddressOrigin, IrScoreOperation, IrScoreOperationType};
+use crate::middle::format::ir_types::{
+    Address, ArrayIndex, IrArrayElement, IrBlock, IrCompoundField, IrIf, IrStatement,
+};
+use crate::middle::format::ir_types::{
+    AddressKind, AddressOrigin, IrScoreOperation, IrScoreOperationType,
+};
+use crate::middle::format::types::{Program, LOAD_FN_NAME};
 
 static BLASTFURNACE_OBJECTIVE: &str = "blst";
 static BLASTFURNACE_CONST: &str = "blst";
+static BLASTFURNACE_STACK: &str = "blst:stack";
+static BLASTFURNACE_VARS: &str = "blst:vars";
+static BLASTFURNACE_MACRO_ARGS: &str = "blst:args";
 
 pub trait CodeGenerator {
     fn generate(&self, generated_code: &mut GeneratedCode, context: &mut Context) -> Vec<String>;
 }
 
 impl Address {
-    fn to_score(&self) -> String {
+    // the fields of a struct after the first one get the offset appended to the name
+    fn offset_suffix(&self) -> String {
+        if self.offset == 0 {
+            String::new()
+        } else {
+            format!(".{}", self.offset)
+        }
+    }
+
+    // the score holder and the objective of the score
+    fn score_holder(&self) -> (String, &str) {
+        if self.kind != AddressKind::Score {
+            panic!("{:?} is held in storage, not in a score", self.name);
+        }
+        let offset = self.offset_suffix();
+
         match &self.name {
-            AddressOrigin::User(x) => format!("{x} {BLASTFURNACE_OBJECTIVE}"),
-            AddressOrigin::CtxGenerated(x, y) => format!("{x}_{y} {BLASTFURNACE_OBJECTIVE}"),
-            AddressOrigin::If => format!("!if {BLASTFURNACE_OBJECTIVE}"),
-            AddressOrigin::Return => format!("!return {BLASTFURNACE_OBJECTIVE}"),
+            AddressOrigin::User(x) => (format!("{x}{offset}"), BLASTFURNACE_OBJECTIVE),
+            AddressOrigin::CtxGenerated(x, y) => {
+                (format!("{x}_{y}{offset}"), BLASTFURNACE_OBJECTIVE)
+            }
+            AddressOrigin::Return => (format!("!return{offset}"), BLASTFURNACE_OBJECTIVE),
             AddressOrigin::Const(x) => {
                 if *x >= 0 {
-                    format!("c{x} {BLASTFURNACE_CONST}")
+                    (format!("c{x}"), BLASTFURNACE_CONST)
                 } else {
-                    format!("cn{x} {BLASTFURNACE_CONST}")
+                    (format!("cn{x}"), BLASTFURNACE_CONST)
                 }
             }
         }
     }
+
+    fn to_score(&self) -> String {
+        let (holder, objective) = self.score_holder();
+        format!("{holder} {objective}")
+    }
+
+    // storage addresses are kept in the variables storage under the name their score would have
+    fn to_storage_path(&self) -> String {
+        if self.kind != AddressKind::Storage {
+            panic!("{:?} is held in a score, not in storage", self.name);
+        }
+        let offset = self.offset_suffix();
+
+        match &self.name {
+            AddressOrigin::User(x) => format!("\"{x}{offset}\""),
+            AddressOrigin::CtxGenerated(x, y) => format!("\"{x}_{y}{offset}\""),
+            AddressOrigin::Return => format!("\"!return{offset}\""),
+            AddressOrigin::Const(_) => panic!("Constants are only held in scores"),
+        }
+    }
+
+    // a text component that shows the value
+    fn to_text_component(&self) -> String {
+        match (&self.kind, &self.name) {
+            (_, AddressOrigin::Const(x)) => format!("{{\"text\":\"{x}\"}}"),
+            (AddressKind::Score, _) => {
+                let (holder, objective) = self.score_holder();
+                format!(
+                    "{{\"score\":{{\"name\":\"{}\",\"objective\":\"{objective}\"}}}}",
+                    escape_json(&holder)
+                )
+            }
+            (AddressKind::Storage, _) => format!(
+                "{{\"nbt\":\"{}\",\"storage\":\"{BLASTFURNACE_VARS}\"}}",
+                escape_json(&self.to_storage_path())
+            ),
+        }
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn generate_load_function(program: &Program, generated_code: &mut GeneratedCode) -> MFunction {
+    let mut body = vec![format!(
+        "scoreboard objectives add {BLASTFURNACE_OBJECTIVE} dummy"
+    )];
+
+    let mut constants = program.constants.iter().collect::<Vec<_>>();
+    constants.sort();
+
+    for constant in constants {
+        let address = Address {
+            name: AddressOrigin::Const(*constant),
+            offset: 0,
+            kind: AddressKind::Score,
+        };
+        body.push(format!(
+            "scoreboard players set {} {constant}",
+            address.to_score()
+        ));
+    }
+
+    // the globals are initialised once the constants they may use are set
+    let mut context = Context {
+        fn_name: LOAD_FN_NAME.to_string(),
+        block_count: program.load_function.block_count,
+    };
+    for statement in &program.load_function.statements {
+        body.extend(statement.generate(generated_code, &mut context));
+    }
+
+    MFunction {
+        name: LOAD_FN_NAME.to_string(),
+        body,
+    }
 }
 
 impl CodeGenerator for IrScoreOperation {
@@ -39,7 +141,7 @@ impl CodeGenerator for IrScoreOperation {
             IrScoreOperationType::Mod => "%=",
             IrScoreOperationType::Assign => "=",
             IrScoreOperationType::Leq => "<=",
-            IrScoreOperationType::Geq => "=>",
+            IrScoreOperationType::Geq => ">=",
             IrScoreOperationType::Lt => "<",
             IrScoreOperationType::Gt => ">",
             IrScoreOperationType::Eq => "=",
@@ -75,6 +177,19 @@ impl CodeGenerator for IrScoreOperation {
                         x
                     )];
                 }
+                // a constant is matched directly instead of being compared to its score
+                IrScoreOperationType::Eq | IrScoreOperationType::Neq => {
+                    let type_ = if self.op == IrScoreOperationType::Eq {
+                        "if"
+                    } else {
+                        "unless"
+                    };
+                    return vec![format!(
+                        "execute store result score {} {type_} score {} matches {x}",
+                        self.left.to_score(),
+                        self.left.to_score()
+                    )];
+                }
                 _ => {}
             },
             _ => {}
@@ -87,7 +202,8 @@ impl CodeGenerator for IrScoreOperation {
             | IrScoreOperationType::Div
             | IrScoreOperationType::Mod
             | IrScoreOperationType::Assign => {
-                if self.left != self.right {
+                // assigning a score to itself does nothing, but `a += a` still has to run
+                if self.left != self.right || self.op != IrScoreOperationType::Assign {
                     vec![format!(
                         "scoreboard players operation {} {} {}",
                         self.left.to_score(),
@@ -118,27 +234,141 @@ impl CodeGenerator for IrScoreOperation {
                     self.right.to_score()
                 )]
             }
-            IrScoreOperationType::And => {
-                vec![format!(
-                    "execute store result score {} unless score {} matches 0 unless score {} matches 0",
-                    self.left.to_score(),
-                    self.left.to_score(),
-                    self.right.to_score()
-                )]
+        }
+    }
+}
+
+/*
+Converts a condition to the subcommands of an execute command, or None if the condition can never hold.
+ */
+fn condition_subcommands(cond: &Cond, invert: bool) -> Option<Vec<String>> {
+    match cond {
+        Cond::CheckVal(x) => {
+            let type_ = if invert { "unless" } else { "if" };
+            if x.min == x.max {
+                Some(vec![format!(
+                    "{type_} score {} matches {}",
+                    x.var_name.to_score(),
+                    x.min
+                )])
+            } else {
+                Some(vec![format!(
+                    "{type_} score {} matches {}..{}",
+                    x.var_name.to_score(),
+                    x.min,
+                    x.max
+                )])
             }
-            IrScoreOperationType::Or => {
-                vec![
-                    format!(
-                        "scoreboard players set {} 1",
-                        self.left.to_score(),
-                    ),
-                    format!(
-                        "execute if score {} matches 0 if score {} matches 0 scoreboard players set {} 0",
-                        self.left.to_score(),
-                        self.right.to_score(),
-                        self.left.to_score(),
-                    )]
+        }
+        Cond::CompareVal(x) => {
+            let mut type_ = "if";
+
+            let op = match x.op {
+                CompareOp::Eq => {
+                    if invert {
+                        type_ = "unless";
+                    }
+                    "="
+                }
+                CompareOp::Neq => {
+                    if !invert {
+                        type_ = "unless";
+                    }
+                    "="
+                }
+                CompareOp::Lt => {
+                    if invert {
+                        ">="
+                    } else {
+                        "<"
+                    }
+                }
+                CompareOp::Gt => {
+                    if invert {
+                        "<="
+                    } else {
+                        ">"
+                    }
+                }
+                CompareOp::Leq => {
+                    if invert {
+                        ">"
+                    } else {
+                        "<="
+                    }
+                }
+                CompareOp::Geq => {
+                    if invert {
+                        "<"
+                    } else {
+                        ">="
+                    }
+                }
+            };
+
+            // should be type_ var0 op var1
+
+            if let AddressOrigin::Const(c1) = x.var_1.name {
+                if let AddressOrigin::Const(c0) = x.var_0.name {
+                    return if match op {
+                        "=" => (c0 == c1 && type_ == "if") || c0 != c1 && type_ == "unless",
+                        "<" => c0 < c1,
+                        ">" => c0 > c1,
+                        "<=" => c0 <= c1,
+                        ">=" => c0 >= c1,
+                        _ => {
+                            panic!("Invalid op, match arms must be insufficient")
+                        }
+                    } {
+                        Some(vec![])
+                    } else {
+                        None
+                    };
+                }
+
+                let range = match op {
+                    "=" => c1.to_string(),
+                    "<" => {
+                        format!("..{}", c1 - 1)
+                    }
+                    ">" => {
+                        format!("{}..", c1 + 1)
+                    }
+                    "<=" => {
+                        format!("..{}", c1)
+                    }
+                    ">=" => {
+                        format!("{}..", c1)
+                    }
+                    _ => {
+                        panic!("Invalid op, match arms must be insufficient")
+                    }
+                };
+                Some(vec![format!(
+                    "{type_} score {} matches {}",
+                    x.var_0.to_score(),
+                    range
+                )])
+            } else {
+                Some(vec![format!(
+                    "{type_} score {} {} {}",
+                    x.var_0.to_score(),
+                    op,
+                    x.var_1.to_score()
+                )])
+            }
+        }
+        Cond::Not(x) => condition_subcommands(x, !invert),
+        Cond::And(conds) => {
+            if invert {
+                panic!("An inverted And can not be checked in a single execute command");
+            }
+
+            let mut subcommands = vec![];
+            for cond in conds {
+                subcommands.append(&mut condition_subcommands(cond, false)?);
             }
+            Some(subcommands)
         }
     }
 }
@@ -158,127 +388,15 @@ fn if_unless_helper(
         wrap_in_function(statements, generated_code, context)
     };
 
-    vec![format!(
-        "{} {}",
-        match &cond {
-            Cond::CheckVal(x) => {
-                let type_ = if invert { "unless" } else { "if" };
-                if x.min == x.max {
-                    format!(
-                        "execute {type_} score {} matches {} run",
-                        x.var_name.to_score(),
-                        x.min
-                    )
-                } else {
-                    format!(
-                        "execute {type_} score {} matches {}..{} run",
-                        x.var_name.to_score(),
-                        x.min,
-                        x.max
-                    )
-                }
-            }
-            Cond::CompareVal(x) => {
-                let mut type_ = "if";
-
-                let op = match x.op {
-                    CompareOp::Eq => {
-                        if invert {
-                            type_ = "unless";
-                        }
-                        "="
-                    }
-                    CompareOp::Neq => {
-                        if !invert {
-                            type_ = "unless";
-                        }
-                        "="
-                    }
-                    CompareOp::Lt => {
-                        if invert {
-                            ">"
-                        } else {
-                            "<"
-                        }
-                    }
-                    CompareOp::Gt => {
-                        if invert {
-                            "<"
-                        } else {
-                            ">"
-                        }
-                    }
-                    CompareOp::Leq => {
-                        if invert {
-                            ">="
-                        } else {
-                            "<="
-                        }
-                    }
-                    CompareOp::Geq => {
-                        if invert {
-                            "<="
-                        } else {
-                            ">="
-                        }
-                    }
-                };
-
-                // should be type_ var0 op var1
-
-                if let AddressOrigin::Const(c1) = x.var_1.name {
-                    if let AddressOrigin::Const(c0) = x.var_0.name {
-                        return if match op {
-                            "=" => (c0 == c1 && type_ == "if") || c0 != c1 && type_ == "unless",
-                            "<" => c0 < c1,
-                            ">" => c0 > c1,
-                            "<=" => c0 <= c1,
-                            ">=" => c0 >= c1,
-                            _ => {
-                                panic!("Invalid op, match arms must be insufficient")
-                            }
-                        } {
-                            vec![statement]
-                        } else {
-                            vec![]
-                        };
-                    }
-
-                    let range = match op {
-                        "=" => c1.to_string(),
-                        "<" => {
-                            format!("..{}", c1 - 1)
-                        }
-                        ">" => {
-                            format!("{}..", c1 + 1)
-                        }
-                        "<=" => {
-                            format!("..{}", c1)
-                        }
-                        ">=" => {
-                            format!("{}..", c1)
-                        }
-                        _ => {
-                            panic!("Invalid op, match arms must be insufficient")
-                        }
-                    };
-                    format!(
-                        "execute {type_} score {} matches {} run",
-                        x.var_0.to_score(),
-                        range
-                    )
-                } else {
-                    format!(
-                        "execute {type_} score {} {} {} run",
-                        x.var_0.to_score(),
-                        op,
-                        x.var_1.to_score()
-                    )
-                }
-            }
-        },
-        statement
-    )]
+    match condition_subcommands(cond, invert) {
+        None => vec![],
+        Some(subcommands) if subcommands.is_empty() => vec![statement],
+        Some(subcommands) => vec![format!(
+            "execute {} run {}",
+            subcommands.join(" "),
+            statement
+        )],
+    }
 }
 
 impl CodeGenerator for IrIf {
@@ -292,13 +410,188 @@ impl CodeGenerator for IrStatement {
         match self {
             IrStatement::ScoreOperation(x) => x.generate(generated_code, context),
             IrStatement::If(x) => x.generate(generated_code, context),
-            IrStatement::FnCall(x) => vec![format!("function {}", x.fn_name)],
-            IrStatement::Return => vec!["return".to_string()],
+            IrStatement::FnCall(x) => vec![format!("function {}", resource_location(&x.fn_name))],
+            IrStatement::Return => vec!["return 0".to_string()],
             IrStatement::Block(x) => x.generate(generated_code, context),
+            IrStatement::PushFrame(x) => push_frame(x),
+            IrStatement::PopFrame(x) => pop_frame(x),
+            IrStatement::ArrayInit(array, len) => vec![format!(
+                "data modify storage {BLASTFURNACE_VARS} {} set value [{}]",
+                array.to_storage_path(),
+                vec!["0"; *len as usize].join(", ")
+            )],
+            IrStatement::ArrayGet(element, address) => element_command(
+                element,
+                |path| {
+                    format!(
+                        "execute store result score {} run data get storage {BLASTFURNACE_VARS} {path}",
+                        address.to_score()
+                    )
+                },
+                generated_code,
+                context,
+            ),
+            IrStatement::ArraySet(element, address) => element_command(
+                element,
+                |path| {
+                    match address.name {
+                    AddressOrigin::Const(x) => {
+                        format!("data modify storage {BLASTFURNACE_VARS} {path} set value {x}")
+                    }
+                    _ => format!(
+                        "execute store result storage {BLASTFURNACE_VARS} {path} int 1 run scoreboard players get {}",
+                        address.to_score()
+                    ),
+                }
+                },
+                generated_code,
+                context,
+            ),
+            IrStatement::StorageSet(address, value) => vec![format!(
+                "data modify storage {BLASTFURNACE_VARS} {} set value {value}",
+                address.to_storage_path()
+            )],
+            IrStatement::StorageCopy(address, source) => {
+                if address == source {
+                    vec![]
+                } else {
+                    vec![format!(
+                        "data modify storage {BLASTFURNACE_VARS} {} set from storage {BLASTFURNACE_VARS} {}",
+                        address.to_storage_path(),
+                        source.to_storage_path()
+                    )]
+                }
+            }
+            IrStatement::FieldGet(field, address) => vec![match address.kind {
+                AddressKind::Score => format!(
+                    "execute store result score {} run data get storage {BLASTFURNACE_VARS} {}",
+                    address.to_score(),
+                    field_path(field)
+                ),
+                AddressKind::Storage => format!(
+                    "data modify storage {BLASTFURNACE_VARS} {} set from storage {BLASTFURNACE_VARS} {}",
+                    address.to_storage_path(),
+                    field_path(field)
+                ),
+            }],
+            IrStatement::FieldSet(field, address) => vec![match (&address.kind, &address.name) {
+                (_, AddressOrigin::Const(x)) => format!(
+                    "data modify storage {BLASTFURNACE_VARS} {} set value {x}",
+                    field_path(field)
+                ),
+                (AddressKind::Score, _) => format!(
+                    "execute store result storage {BLASTFURNACE_VARS} {} int 1 run scoreboard players get {}",
+                    field_path(field),
+                    address.to_score()
+                ),
+                (AddressKind::Storage, _) => format!(
+                    "data modify storage {BLASTFURNACE_VARS} {} set from storage {BLASTFURNACE_VARS} {}",
+                    field_path(field),
+                    address.to_storage_path()
+                ),
+            }],
+            IrStatement::Print(addresses) => {
+                let components = addresses
+                    .iter()
+                    .map(|address| address.to_text_component())
+                    .collect::<Vec<_>>()
+                    .join(",\" \",");
+                vec![format!("tellraw @a [\"\",{components}]")]
+            }
         }
     }
 }
 
+fn field_path(field: &IrCompoundField) -> String {
+    let mut path = field.compound.to_storage_path();
+    for name in &field.path {
+        path.push('.');
+        path.push_str(name);
+    }
+    path
+}
+
+/*
+A constant index is part of the path. A dynamic index is stored in the macro arguments and the command runs in
+a macro function, which puts it into the path.
+ */
+fn element_command(
+    element: &IrArrayElement,
+    command: impl Fn(&str) -> String,
+    generated_code: &mut GeneratedCode,
+    context: &mut Context,
+) -> Vec<String> {
+    let array = element.array.to_storage_path();
+    match &element.index {
+        ArrayIndex::Const(i) => vec![command(&format!("{array}[{i}]"))],
+        ArrayIndex::Score(index) => {
+            let macro_function = wrap_in_function(
+                vec![format!("${}", command(&format!("{array}[$(i)]")))],
+                generated_code,
+                context,
+            );
+            vec![
+                format!(
+                    "execute store result storage {BLASTFURNACE_MACRO_ARGS} i int 1 run scoreboard players get {}",
+                    index.to_score()
+                ),
+                format!("{macro_function} with storage {BLASTFURNACE_MACRO_ARGS}"),
+            ]
+        }
+    }
+}
+
+/*
+Every frame is a compound appended to the frames list of the stack storage, with the scores and storage values
+stored as v0, v1, ... in the order of the addresses.
+ */
+fn push_frame(addresses: &[Address]) -> Vec<String> {
+    if addresses.is_empty() {
+        return vec![];
+    }
+
+    let mut commands = vec![format!(
+        "data modify storage {BLASTFURNACE_STACK} frames append value {{}}"
+    )];
+    for (i, address) in addresses.iter().enumerate() {
+        commands.push(match address.kind {
+            AddressKind::Score => format!(
+                "execute store result storage {BLASTFURNACE_STACK} frames[-1].v{i} int 1 run scoreboard players get {}",
+                address.to_score()
+            ),
+            AddressKind::Storage => format!(
+                "data modify storage {BLASTFURNACE_STACK} frames[-1].v{i} set from storage {BLASTFURNACE_VARS} {}",
+                address.to_storage_path()
+            ),
+        });
+    }
+    commands
+}
+
+fn pop_frame(addresses: &[Address]) -> Vec<String> {
+    if addresses.is_empty() {
+        return vec![];
+    }
+
+    let mut commands = vec![];
+    for (i, address) in addresses.iter().enumerate() {
+        commands.push(match address.kind {
+            AddressKind::Score => format!(
+                "execute store result score {} run data get storage {BLASTFURNACE_STACK} frames[-1].v{i}",
+                address.to_score()
+            ),
+            AddressKind::Storage => format!(
+                "data modify storage {BLASTFURNACE_VARS} {} set from storage {BLASTFURNACE_STACK} frames[-1].v{i}",
+                address.to_storage_path()
+            ),
+        });
+    }
+    commands.push(format!(
+        "data remove storage {BLASTFURNACE_STACK} frames[-1]"
+    ));
+    commands
+}
+
 impl CodeGenerator for IrBlock {
     fn generate(&self, generated_code: &mut GeneratedCode, context: &mut Context) -> Vec<String> {
         let mut result = vec![];
@@ -306,25 +599,48 @@ impl CodeGenerator for IrBlock {
             result.append(&mut statement.generate(generated_code, context));
         }
 
-        if result.len() == 1 && self.can_embed {
+        // a return inside the block must only exit the block, which an embedded command would not do
+        if result.len() == 1 && self.can_embed && !self.statements.iter().any(exits_function) {
             result
         } else {
-            vec![wrap_in_function(result, generated_code, context)]
+            // the block keeps its own name, since it can be called by name (loops call themselves)
+            vec![add_block_function(
+                self.get_fn_name(),
+                result,
+                generated_code,
+            )]
         }
     }
 }
 
+fn exits_function(statement: &IrStatement) -> bool {
+    match statement {
+        IrStatement::Return => true,
+        IrStatement::If(x) => exits_function(&x.body),
+        _ => false,
+    }
+}
+
 fn wrap_in_function(
     statements: Vec<String>,
     generated_code: &mut GeneratedCode,
     context: &mut Context,
 ) -> String {
     let block_name = context.new_block();
+    add_block_function(block_name, statements, generated_code)
+}
+
+fn add_block_function(
+    block_name: String,
+    statements: Vec<String>,
+    generated_code: &mut GeneratedCode,
+) -> String {
+    let call = format!("function {}", resource_location(&block_name));
 
     generated_code.add_function(MFunction {
-        name: block_name.clone(),
+        name: block_name,
         body: statements,
     });
 
-    format!("function {}", block_name)
+    call
 }
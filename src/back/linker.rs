@@ -0,0 +1,189 @@
+use crate::back::code_generator::GeneratedCode;
+use crate::middle::format::types::LOAD_FN_NAME;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+static PACK_FORMAT: u32 = 26;
+
+pub struct Datapack {
+    pub files: BTreeMap<Utf8PathBuf, String>,
+}
+
+impl Datapack {
+    pub fn write(&self, root: &Utf8Path) -> io::Result<()> {
+        for (path, content) in &self.files {
+            let path = root.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+}
+
+/*
+Resource locations may only contain [a-z0-9_.-] (and '/' in the path), but our names can contain
+anything an identifier can. Upper case letters become '-' followed by the lower case letter, '-' becomes
+"--" and anything else becomes "-_<hex>_", so no two names map to the same location. Every '-' written here is
+followed by another character, so a name ending in a single '-' is never produced.
+ */
+fn sanitize(name: &str, allow_slash: bool) -> String {
+    let mut sanitized = String::new();
+    for c in name.chars() {
+        match c {
+            'a'..='z' | '0'..='9' | '_' | '.' => sanitized.push(c),
+            '/' if allow_slash => sanitized.push(c),
+            '-' => sanitized.push_str("--"),
+            'A'..='Z' => {
+                sanitized.push('-');
+                sanitized.push(c.to_ascii_lowercase());
+            }
+            _ => sanitized.push_str(&format!("-_{:x}_", c as u32)),
+        }
+    }
+    sanitized
+}
+
+fn split_fn_name(fn_name: &str) -> (String, String) {
+    // the load function and its blocks keep the namespace `blst-`, which no package name is sanitized to
+    if fn_name == LOAD_FN_NAME || fn_name.starts_with(&format!("{LOAD_FN_NAME}/")) {
+        let (namespace, path) = fn_name.split_once('/').unwrap();
+        return (namespace.to_string(), path.to_string());
+    }
+    let (namespace, path) = fn_name.split_once('/').unwrap_or(("blst", fn_name));
+    (sanitize(namespace, false), sanitize(path, true))
+}
+
+/// Converts a function name such as `pkg/root/0_main/3` into the resource location `pkg:root/0_main/3`.
+pub fn resource_location(fn_name: &str) -> String {
+    let (namespace, path) = split_fn_name(fn_name);
+    format!("{namespace}:{path}")
+}
+
+fn escape_json(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn link(generated_code: &GeneratedCode, description: &str) -> Datapack {
+    let mut files = BTreeMap::new();
+
+    files.insert(
+        Utf8PathBuf::from("pack.mcmeta"),
+        format!(
+            "{{\n  \"pack\": {{\n    \"pack_format\": {PACK_FORMAT},\n    \"description\": \"{}\"\n  }}\n}}\n",
+            escape_json(description)
+        ),
+    );
+
+    if !generated_code.load_functions.is_empty() {
+        let values = generated_code
+            .load_functions
+            .iter()
+            .map(|name| format!("    \"{}\"", resource_location(name)))
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        files.insert(
+            Utf8PathBuf::from("data/minecraft/tags/functions/load.json"),
+            format!("{{\n  \"values\": [\n{values}\n  ]\n}}\n"),
+        );
+    }
+
+    for function in &generated_code.functions {
+        let (namespace, path) = split_fn_name(&function.name);
+
+        let mut body = function.body.join("\n");
+        body.push('\n');
+
+        files.insert(
+            Utf8PathBuf::from(format!("data/{namespace}/functions/{path}.mcfunction")),
+            body,
+        );
+    }
+
+    Datapack { files }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::back::code_generator::generate_code;
+    use crate::back::linker::{link, resource_location};
+    use crate::front::file_system::fs::FileSystem;
+    use crate::front::file_system::mock_fs::MockFileSystem;
+    use crate::front::mergers::program::ProgramMerger;
+    use crate::middle::format::types::LOAD_FN_NAME;
+    use camino::Utf8PathBuf;
+
+    #[test]
+    fn test_resource_location() {
+        assert_eq!(resource_location("pkg/root/0_main/3"), "pkg:root/0_main/3");
+        assert_eq!(
+            resource_location("Pkg/root/0_doThing"),
+            "-pkg:root/0_do-thing"
+        );
+        assert_eq!(resource_location("pkg/root/0_a-b"), "pkg:root/0_a--b");
+
+        // a package can not share the namespace of the load function
+        assert_eq!(resource_location(LOAD_FN_NAME), "blst-:load");
+        assert_eq!(resource_location("blst-/load/0"), "blst-:load/0");
+        assert_eq!(resource_location("blst-/root/0_load"), "blst--:root/0_load");
+    }
+
+    #[test]
+    fn test_link_datapack() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "let g: int = 7; pub fn main() { let a: int = 0; while (a < 10) { a += g; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let datapack = link(&generate_code(&program), "test");
+
+        assert!(datapack
+            .files
+            .get(&Utf8PathBuf::from("pack.mcmeta"))
+            .unwrap()
+            .contains("\"pack_format\""));
+
+        let main = datapack
+            .files
+            .get(&Utf8PathBuf::from(
+                "data/test/functions/root/0_main.mcfunction",
+            ))
+            .unwrap();
+        assert!(main.contains("function test:root/0_main/0"));
+
+        let block = datapack
+            .files
+            .get(&Utf8PathBuf::from(
+                "data/test/functions/root/0_main/0.mcfunction",
+            ))
+            .unwrap();
+        assert!(block.ends_with("function test:root/0_main/0\n"));
+
+        assert!(datapack
+            .files
+            .get(&Utf8PathBuf::from(
+                "data/minecraft/tags/functions/load.json"
+            ))
+            .unwrap()
+            .contains("\"blst-:load\""));
+
+        let load = datapack
+            .files
+            .get(&Utf8PathBuf::from("data/blst-/functions/load.mcfunction"))
+            .unwrap();
+        assert!(load.starts_with("scoreboard objectives add blst dummy\n"));
+        assert!(load.contains("scoreboard players set c10 blst 10\n"));
+        assert!(load.ends_with("scoreboard players set test/root/0_g blst 7\n"));
+    }
+}
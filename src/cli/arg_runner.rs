@@ -4,6 +4,8 @@ pub enum CliMessage {
     Message(String),
     Warning(String),
     Error(String),
+    // errors and warnings about the program, already rendered with their source
+    Diagnostic(String),
 }
 
 impl Display for CliMessage {
@@ -12,6 +14,7 @@ impl Display for CliMessage {
             CliMessage::Message(m) => write!(f, "{}", m),
             CliMessage::Warning(w) => write!(f, "[WARNING]: {}", w),
             CliMessage::Error(e) => write!(f, "[ERROR]: {}", e),
+            CliMessage::Diagnostic(d) => write!(f, "{}", d),
         }
     }
 }
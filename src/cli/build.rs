@@ -1,25 +1,40 @@
 use crate::back::code_generator::flatten_to_hmasm;
 use crate::back::code_generator::generate_code;
+use crate::back::linker::link;
 use crate::cli::arg_runner::{ArgRunner, CliMessage};
+use crate::front::diagnostics::Diagnostic;
 use crate::front::file_system::fs::FileSystem;
 use crate::front::file_system::system_fs::SystemFs;
 use crate::front::mergers::program::ProgramMerger;
+use crate::front::passes::check_assignment::DisallowNullAssignment;
+use crate::front::passes::check_loops::CheckLoopExits;
+use crate::front::passes::fold_constants::FoldConstants;
+use crate::front::passes::pass;
+use crate::front::passes::types::{AnnotateTypes, CheckTypes};
 use crate::middle::passes::delete_unused::DeleteUnused;
 use crate::middle::passes::optimize;
-use camino::Utf8PathBuf;
-use clap::Args;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Args, ValueEnum};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::{fs, io};
 use toml::{Table, Value};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputMode {
+    /// A single HMASM file listing every generated function.
+    Hmasm,
+    /// A datapack directory that can be dropped into a world.
+    Datapack,
+}
+
 #[derive(Debug, Args)]
 pub struct BuildArgs {
     /// The path to the project.
     path: Option<Utf8PathBuf>,
-    /// Should output HMASM instead
-    #[clap(long, short = 'o')]
-    hmasm: bool,
+    /// The kind of output to write into the target directory.
+    #[clap(long, short = 'o', value_enum, default_value_t = OutputMode::Datapack)]
+    output: OutputMode,
 }
 
 impl ArgRunner for BuildArgs {
@@ -63,36 +78,82 @@ impl ArgRunner for BuildArgs {
             );
         }
 
-        let package_name = if let Ok(toml) = buf.parse::<Table>() {
-            if let Some(package) = toml.get("package") {
-                if let Some(name) = package.get("name") {
-                    if let Value::String(name) = name {
-                        name.clone()
-                    } else {
-                        return CliMessage::Error("The name field in the package section of the blastf.toml file is not a string.".to_string());
-                    }
+        let toml = if let Ok(toml) = buf.parse::<Table>() {
+            toml
+        } else {
+            return CliMessage::Error("The blastf.toml file is not a valid toml file.".to_string());
+        };
+
+        let package_name = if let Some(package) = toml.get("package") {
+            if let Some(name) = package.get("name") {
+                if let Value::String(name) = name {
+                    name.clone()
                 } else {
-                    return CliMessage::Error(
-                        "The package section of the blastf.toml file does not have a name field."
-                            .to_string(),
-                    );
+                    return CliMessage::Error("The name field in the package section of the blastf.toml file is not a string.".to_string());
                 }
             } else {
                 return CliMessage::Error(
-                    "The blastf.toml file does not have a package section.".to_string(),
+                    "The package section of the blastf.toml file does not have a name field."
+                        .to_string(),
                 );
             }
         } else {
-            return CliMessage::Error("The blastf.toml file is not a valid toml file.".to_string());
+            return CliMessage::Error(
+                "The blastf.toml file does not have a package section.".to_string(),
+            );
+        };
+
+        // the name is the namespace of the datapack and the name of its directory in target
+        if !is_valid_namespace(&package_name) {
+            return CliMessage::Error(format!(
+                "The package name {:?} in the blastf.toml file is not a valid namespace, which only has the characters a-z, 0-9, _, . and -.",
+                package_name
+            ));
+        }
+
+        // a float is stored in a score as its value times the scale, which is 1000 unless it is set here
+        let float_scale = match toml.get("build").and_then(|build| build.get("float_scale")) {
+            None => None,
+            Some(Value::Integer(scale)) if *scale > 0 && *scale <= i32::MAX as i64 => {
+                Some(*scale as i32)
+            }
+            Some(_) => {
+                return CliMessage::Error("The float_scale field in the build section of the blastf.toml file is not a positive integer.".to_string());
+            }
         };
 
         let real_fs = SystemFs::new(abs_path.join("src")).unwrap();
 
         let mut program_merger = ProgramMerger::new(&package_name);
 
-        program_merger.read_package(&package_name, real_fs);
+        if let Err(diagnostic) = program_merger.read_package(&package_name, real_fs) {
+            return render_diagnostic(diagnostic, &abs_path.join("src"));
+        }
+
+        let mut front_program = program_merger.return_merged();
+        if let Some(float_scale) = float_scale {
+            front_program.float_scale = float_scale;
+        }
+
+        if let Err(e) = pass(
+            &mut front_program,
+            &mut vec![
+                Box::new(FoldConstants),
+                Box::new(DisallowNullAssignment),
+                Box::new(CheckLoopExits),
+                Box::new(AnnotateTypes),
+                Box::new(CheckTypes),
+            ],
+        ) {
+            return render_diagnostic(e.into(), &abs_path.join("src"));
+        }
+
+        // only the public functions are kept in the datapack, so without them it has nothing to call
+        let warning = front_program.public_functions.is_empty().then(|| {
+            Diagnostic::warning("The package has no public functions")
+                .help("mark the functions to call from the game with `pub`")
+        });
 
-        let front_program = program_merger.return_merged();
         let mut program = front_program.export_program();
 
         optimize(&mut program, &mut vec![Box::new(DeleteUnused)]);
@@ -109,16 +170,89 @@ impl ArgRunner for BuildArgs {
             }
         }
 
-        let target = abs_path.join(format!("target/{package_name}.hmasm"));
+        let generated_code = generate_code(&program);
 
-        return if let Ok(mut fs) = File::create(&target) {
-            if let Ok(_) = fs.write_all(flatten_to_hmasm(&generate_code(&program)).as_ref()) {
-                CliMessage::Message(format!("Wrote the HMASM file to: {:?}", target))
-            } else {
-                CliMessage::Error("Could not write the HMASM file.".to_string())
+        let message = match self.output {
+            OutputMode::Hmasm => {
+                let target = abs_path.join(format!("target/{package_name}.hmasm"));
+
+                if let Ok(mut fs) = File::create(&target) {
+                    if fs
+                        .write_all(flatten_to_hmasm(&generated_code).as_ref())
+                        .is_ok()
+                    {
+                        CliMessage::Message(format!("Wrote the HMASM file to: {:?}", target))
+                    } else {
+                        CliMessage::Error("Could not write the HMASM file.".to_string())
+                    }
+                } else {
+                    CliMessage::Error("Could not find the target directory.".to_string())
+                }
+            }
+            OutputMode::Datapack => {
+                let target = abs_path.join(format!("target/{package_name}"));
+
+                // stale functions from a previous build would otherwise stay in the datapack
+                if target.exists() {
+                    if !target.join("pack.mcmeta").is_file() {
+                        return CliMessage::Error(format!(
+                            "{:?} is not a datapack, so it is not replaced.",
+                            target
+                        ));
+                    }
+                    if let Err(e) = fs::remove_dir_all(&target) {
+                        return CliMessage::Error(format!(
+                            "Could not clear the previous datapack. Error: {}",
+                            e
+                        ));
+                    }
+                }
+
+                if let Err(e) = link(&generated_code, &package_name).write(&target) {
+                    CliMessage::Error(format!("Could not write the datapack. Error: {}", e))
+                } else {
+                    CliMessage::Message(format!("Wrote the datapack to: {:?}", target))
+                }
             }
-        } else {
-            CliMessage::Error("Could not find the target directory.".to_string())
         };
+
+        match (warning, message) {
+            (Some(warning), CliMessage::Message(m)) => {
+                CliMessage::Diagnostic(format!("{}\n{}", warning.render(None), m))
+            }
+            (_, message) => message,
+        }
+    }
+}
+
+// the file a diagnostic points at is read again to show the line it is on
+fn render_diagnostic(diagnostic: Diagnostic, src: &Utf8Path) -> CliMessage {
+    let source = diagnostic
+        .span
+        .as_ref()
+        .and_then(|span| fs::read_to_string(src.join(&*span.file)).ok());
+    CliMessage::Diagnostic(diagnostic.render(source.as_deref()))
+}
+
+fn is_valid_namespace(name: &str) -> bool {
+    name != "."
+        && name != ".."
+        && !name.is_empty()
+        && name
+            .chars()
+            .all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_' | '.' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::build::is_valid_namespace;
+
+    #[test]
+    fn test_namespace() {
+        assert!(is_valid_namespace("my_pack"));
+        assert!(is_valid_namespace("pack-1.2"));
+        for name in ["", ".", "..", "../src", "/tmp", "Pack", "a b"] {
+            assert!(!is_valid_namespace(name), "{name}");
+        }
     }
 }
@@ -1,9 +1,10 @@
 pub mod ast_retriever;
 mod ast_types;
+pub mod diagnostics;
 mod exporter;
 pub mod file_system;
 pub mod mergers;
-mod passes;
+pub mod passes;
 
 #[cfg(test)]
 mod tests {
@@ -24,8 +25,8 @@ mod tests {
         mock_fs_1.insert_file(Utf8PathBuf::from("main.ing"), "pub fn libfunc() {}");
 
         let mut program_merger = ProgramMerger::new("test");
-        program_merger.read_package("test", mock_fs_0);
-        program_merger.read_package("library", mock_fs_1);
+        program_merger.read_package("test", mock_fs_0).unwrap();
+        program_merger.read_package("library", mock_fs_1).unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -1,3 +1,4 @@
+use crate::front::ast_retriever::name_resolution::resolver::ResolverError;
 use crate::front::ast_retriever::name_resolution::scope_table::ScopeTable;
 use crate::front::ast_types::visitor::Visitable;
 use crate::front::ast_types::Module;
@@ -6,7 +7,8 @@ mod lib;
 mod resolver;
 mod scope_table;
 
-pub fn resolve_module(module: &mut Module) {
+pub fn resolve_module(module: &mut Module) -> Result<(), ResolverError> {
     let mut scope_table = ScopeTable::new();
-    module.visit(&mut scope_table).unwrap();
+    module.visit(&mut scope_table)?;
+    Ok(())
 }
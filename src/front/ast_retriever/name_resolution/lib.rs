@@ -7,9 +7,19 @@ mod tests {
     use crate::front::ast_retriever::string_to_module;
     use crate::front::ast_types::visitor::Visitable;
     use crate::front::ast_types::ExpressionEnum;
-    use crate::front::ast_types::{AtomicExpression, Definition, Reference, Statement, Type};
+    use crate::front::ast_types::{AtomicExpression, Definition, Reference, Span, Statement, Type};
     use std::rc::Rc;
 
+    fn span(line: u32, column: u32, end_line: u32, end_column: u32) -> Span {
+        Span {
+            file: Rc::from(""),
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+
     #[test]
     fn simple_global() {
         let mut scope_table = ScopeTable::new();
@@ -322,7 +332,7 @@ mod tests {
 
         assert_eq!(
             module.visit(&mut scope_table),
-            Err(UndefinedVariable("b".to_string()))
+            Err(UndefinedVariable("b".to_string(), span(1, 24, 1, 29)))
         );
     }
 
@@ -505,7 +515,7 @@ mod tests {
         let mut block = string_to_module(statement).unwrap().block;
 
         if let Err(error) = block.visit(&mut scope_table) {
-            assert_eq!(error, Redefinition("A".to_string()));
+            assert_eq!(error, Redefinition("A".to_string(), span(1, 21, 1, 40)));
         } else {
             panic!("Expected error");
         }
@@ -646,4 +656,151 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn fn_call_before_def() {
+        let mut scope_table = ScopeTable::new();
+
+        let statement = "pub fn main() { helper(); } fn helper() { }";
+        let mut module = string_to_module(statement).unwrap();
+
+        module.visit(&mut scope_table).unwrap();
+
+        match &module.public_definitions[0] {
+            Definition::FnDef(fn_def) => match &fn_def.body.statements[0] {
+                Statement::Expression(expr) => match &expr.expr {
+                    ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(fn_call)) => {
+                        assert_eq!(
+                            fn_call.name.module_resolved,
+                            Some(Rc::new("0_helper".to_string()))
+                        );
+                    }
+                    _ => {
+                        panic!("Expected FnCall");
+                    }
+                },
+                _ => {
+                    panic!("Expected Expression");
+                }
+            },
+            _ => {
+                panic!("Expected FnDef");
+            }
+        }
+
+        match &module.block.definitions[0] {
+            Definition::FnDef(fn_def) => {
+                assert_eq!(
+                    fn_def.name.module_resolved,
+                    Some(Rc::new("0_helper".to_string()))
+                );
+            }
+            _ => {
+                panic!("Expected FnDef");
+            }
+        }
+    }
+
+    #[test]
+    fn struct_used_before_def() {
+        let mut scope_table = ScopeTable::new();
+
+        let statement = "pub fn main() { let p: Point; } struct Point { x: int }";
+        let mut module = string_to_module(statement).unwrap();
+
+        module.visit(&mut scope_table).unwrap();
+
+        match &module.public_definitions[0] {
+            Definition::FnDef(fn_def) => match &fn_def.body.statements[0] {
+                Statement::VarDecl(var_decl) => match &var_decl.var_def.type_ {
+                    Some(Type::Struct(struct_name)) => {
+                        assert_eq!(
+                            struct_name.module_resolved,
+                            Some(Rc::new("0_Point".to_string()))
+                        );
+                    }
+                    _ => {
+                        panic!("Expected Struct");
+                    }
+                },
+                _ => {
+                    panic!("Expected VarDecl");
+                }
+            },
+            _ => {
+                panic!("Expected FnDef");
+            }
+        }
+
+        match &module.block.definitions[0] {
+            Definition::StructDef(struct_def) => {
+                assert_eq!(
+                    struct_def.type_name.module_resolved,
+                    Some(Rc::new("0_Point".to_string()))
+                );
+            }
+            _ => {
+                panic!("Expected StructDef");
+            }
+        }
+    }
+
+    #[test]
+    fn impl_methods() {
+        let mut scope_table = ScopeTable::new();
+
+        let statement =
+            "impl Point { fn len(self) -> int { return self.x; } } struct Point { x: int } fn len() {}";
+        let mut module = string_to_module(statement).unwrap();
+
+        module.visit(&mut scope_table).unwrap();
+
+        match &module.block.definitions[1] {
+            Definition::Impl(impl_) => {
+                assert_eq!(
+                    impl_.type_name.module_resolved,
+                    Some(Rc::new("0_Point".to_string()))
+                );
+
+                // the method does not clash with the function of the same name
+                let method = &impl_.methods[0];
+                assert_eq!(
+                    method.name.module_resolved,
+                    Some(Rc::new("0_Point.len".to_string()))
+                );
+                assert_eq!(
+                    method.args[0].name.module_resolved,
+                    Some(Rc::new("0_self".to_string()))
+                );
+            }
+            _ => {
+                panic!("Expected Impl");
+            }
+        }
+
+        match &module.block.definitions[2] {
+            Definition::FnDef(fn_def) => {
+                assert_eq!(
+                    fn_def.name.module_resolved,
+                    Some(Rc::new("0_len".to_string()))
+                );
+            }
+            _ => {
+                panic!("Expected FnDef");
+            }
+        }
+    }
+
+    #[test]
+    fn impl_duplicate_method() {
+        let mut scope_table = ScopeTable::new();
+
+        let statement = "struct Point { x: int } impl Point { fn a(self) {} fn a(self) {} }";
+        let mut module = string_to_module(statement).unwrap();
+
+        assert_eq!(
+            module.visit(&mut scope_table),
+            Err(Redefinition("a".to_string(), span(1, 52, 1, 65)))
+        );
+    }
 }
@@ -1,11 +1,50 @@
 use crate::front::ast_retriever::name_resolution::scope_table::{ScopeTable, SymbolType};
 use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
-use crate::front::ast_types::{AtomicExpression, NamePath, Type};
+use crate::front::ast_types::{AtomicExpression, Definition, ExpressionEnum, NamePath, Span, Type};
+use crate::front::diagnostics::Diagnostic;
+use std::collections::HashSet;
+use std::rc::Rc;
 
 #[derive(Debug, PartialEq)]
 pub enum ResolverError {
-    UndefinedVariable(String),
-    Redefinition(String),
+    UndefinedVariable(String, Span),
+    Redefinition(String, Span),
+}
+
+impl ResolverError {
+    // names do not know where they are, so the node they are found in gives the span, unless a node in it already has
+    pub fn at(self, span: &Span) -> ResolverError {
+        let locate = |found: Span| {
+            if found.is_default() {
+                span.clone()
+            } else {
+                found
+            }
+        };
+        match self {
+            ResolverError::UndefinedVariable(name, found) => {
+                ResolverError::UndefinedVariable(name, locate(found))
+            }
+            ResolverError::Redefinition(name, found) => {
+                ResolverError::Redefinition(name, locate(found))
+            }
+        }
+    }
+}
+
+impl From<ResolverError> for Diagnostic {
+    fn from(error: ResolverError) -> Diagnostic {
+        match error {
+            ResolverError::UndefinedVariable(name, span) => {
+                Diagnostic::error(format!("Cannot find `{name}` in this scope")).at(&span)
+            }
+            ResolverError::Redefinition(name, span) => {
+                Diagnostic::error(format!("`{name}` is defined more than once in this scope"))
+                    .at(&span)
+                    .help("give one of them another name")
+            }
+        }
+    }
 }
 
 pub type ResolveResult<T> = GenericResolveResult<T, ResolverError>;
@@ -17,8 +56,37 @@ fn name_path_lookup(scope_table: &ScopeTable, name_path: &mut NamePath) -> Resol
             name_path.name.module_resolved = Some(name.clone());
             Ok((true, None))
         }
-        None => Err(ResolverError::UndefinedVariable(name_path.name.raw.clone())),
+        None => Err(ResolverError::UndefinedVariable(
+            name_path.name.raw.clone(),
+            Span::default(),
+        )),
+    }
+}
+
+// struct types can be used before the struct is defined
+fn struct_type_lookup(scope_table: &mut ScopeTable, type_: &mut Type) {
+    if let Type::Struct(struct_name) = type_ {
+        struct_name.module_resolved =
+            Some(scope_table.scope_lookup_force(&struct_name.raw, SymbolType::Struct));
+    }
+}
+
+/*
+Functions can be called before they are defined, so their names are bound at the start of the scope
+they are defined in, before any of the bodies are visited.
+ */
+fn bind_fn_names<'a>(
+    scope_table: &mut ScopeTable,
+    definitions: impl Iterator<Item = &'a Definition>,
+) -> InternalResolveResult<()> {
+    for definition in definitions {
+        if let Definition::FnDef(fn_def) = definition {
+            scope_table
+                .scope_bind(&fn_def.name.raw, SymbolType::Fn)
+                .map_err(|error| error.at(&fn_def.span))?;
+        }
     }
+    Ok(())
 }
 
 impl Visitor<(), ResolverError> for ScopeTable {
@@ -36,7 +104,10 @@ impl Visitor<(), ResolverError> for ScopeTable {
                         Some(name) => {
                             x.name.module_resolved = Some(name.clone());
                         }
-                        None => Err(ResolverError::UndefinedVariable(x.name.raw.clone()))?,
+                        None => Err(ResolverError::UndefinedVariable(
+                            x.name.raw.clone(),
+                            Span::default(),
+                        ))?,
                     }
                 }
                 _ => {
@@ -45,36 +116,77 @@ impl Visitor<(), ResolverError> for ScopeTable {
             },
 
             ASTNodeEnum::VarDef(var_def) => {
-                if let Some(Type::Struct(struct_name)) = &mut var_def.type_ {
-                    struct_name.module_resolved =
-                        Some(self.scope_lookup_force(&struct_name.raw, SymbolType::Struct));
+                if let Some(type_) = &mut var_def.type_ {
+                    struct_type_lookup(self, type_);
                 }
 
                 var_def.name.module_resolved =
                     Some(self.scope_bind(&var_def.name.raw, SymbolType::Var)?);
             }
+            ASTNodeEnum::Expression(expression) => {
+                let result = match &mut expression.expr {
+                    ExpressionEnum::AtomicExpression(x) => x.visit(self),
+                    ExpressionEnum::Unary(_, x) => x.visit(self),
+                    ExpressionEnum::Binary(x, _, y) => x.visit(self).and_then(|_| y.visit(self)),
+                };
+                result.map_err(|error| error.at(&expression.span))?;
+            }
+            ASTNodeEnum::VarDecl(var_decl) => {
+                if let Some(expr) = &mut var_decl.expr {
+                    expr.visit(self)?;
+                }
+                var_decl
+                    .var_def
+                    .visit(self)
+                    .map_err(|error| error.at(&var_decl.span))?;
+            }
             ASTNodeEnum::VarAssign(var_assign) => {
                 var_assign.expr.visit(self)?;
-                name_path_lookup(&self, &mut var_assign.name_path)?;
+                name_path_lookup(&self, &mut var_assign.name_path)
+                    .map_err(|error| error.at(&var_assign.span))?;
+            }
+            ASTNodeEnum::IndexAssign(index_assign) => {
+                index_assign.expr.visit(self)?;
+                index_assign
+                    .element
+                    .visit(self)
+                    .map_err(|error| error.at(&index_assign.span))?;
             }
             ASTNodeEnum::FnDef(fn_def) => {
-                self.scope_enter();
+                fn_def.name.module_resolved = Some(
+                    match self.scope_lookup_current(&fn_def.name.raw, SymbolType::Fn) {
+                        Some(name) => name,
+                        None => self
+                            .scope_bind(&fn_def.name.raw, SymbolType::Fn)
+                            .map_err(|error| error.at(&fn_def.span))?,
+                    },
+                );
+                struct_type_lookup(self, &mut fn_def.return_type);
 
-                fn_def.name.module_resolved =
-                    Some(self.scope_bind(&fn_def.name.raw, SymbolType::Fn)?);
+                self.scope_enter();
                 for arg in &mut fn_def.args {
-                    arg.visit(self)?;
+                    arg.visit(self).map_err(|error| error.at(&fn_def.span))?;
                 }
 
                 fn_def.body.visit(self)?;
                 self.scope_exit();
             }
             ASTNodeEnum::FnCall(fn_call) => {
-                match self.scope_lookup(&fn_call.name.raw, SymbolType::Fn) {
-                    Some(name) => {
-                        fn_call.name.module_resolved = Some(name.clone());
+                if let Some(receiver) = &mut fn_call.receiver {
+                    // the method itself is looked up in the type of the receiver once it is known
+                    name_path_lookup(self, receiver)?;
+                } else {
+                    match self.scope_lookup(&fn_call.name.raw, SymbolType::Fn) {
+                        Some(name) => {
+                            fn_call.name.module_resolved = Some(name.clone());
+                        }
+                        None => {
+                            return Err(ResolverError::UndefinedVariable(
+                                fn_call.name.raw.clone(),
+                                Span::default(),
+                            ))
+                        }
                     }
-                    None => return Err(ResolverError::UndefinedVariable(fn_call.name.raw.clone())),
                 }
 
                 for arg in &mut fn_call.args {
@@ -83,6 +195,7 @@ impl Visitor<(), ResolverError> for ScopeTable {
             }
             ASTNodeEnum::Block(block) => {
                 self.scope_enter();
+                bind_fn_names(self, block.definitions.iter())?;
                 for definitions in &mut block.definitions {
                     definitions.visit(self)?;
                 }
@@ -97,50 +210,110 @@ impl Visitor<(), ResolverError> for ScopeTable {
                     use_.visit(self)?;
                 }
 
-                for definitions in &mut module.public_definitions {
-                    definitions.visit(self)?;
-                }
+                // private definitions share the module scope so public functions can call them
+                bind_fn_names(
+                    self,
+                    module
+                        .public_definitions
+                        .iter()
+                        .chain(module.block.definitions.iter()),
+                )?;
 
-                module.block.visit(self)?;
+                // structs come first so any type can refer to them, then globals so functions defined
+                // before them can use them
+                let mut definitions: Vec<_> = module
+                    .public_definitions
+                    .iter_mut()
+                    .chain(module.block.definitions.iter_mut())
+                    .collect();
+                definitions.sort_by_key(|definition| match definition {
+                    Definition::StructDef(_) => 0,
+                    Definition::VarDecl(_) => 1,
+                    Definition::FnDef(_) | Definition::Impl(_) => 2,
+                });
+                for definition in definitions {
+                    definition.visit(self)?;
+                }
+                for statement in &mut module.block.statements {
+                    statement.visit(self)?;
+                }
                 self.scope_exit();
             }
+            ASTNodeEnum::Index(index) => {
+                name_path_lookup(self, &mut index.array)?;
+                index.index.visit(self)?;
+            }
+            ASTNodeEnum::StructLiteral(struct_literal) => {
+                struct_literal.type_name.module_resolved = Some(
+                    self.scope_lookup_force(&struct_literal.type_name.raw, SymbolType::Struct),
+                );
+                for field in struct_literal.fields.values_mut() {
+                    field.visit(self)?;
+                }
+            }
+            ASTNodeEnum::Impl(impl_) => {
+                let type_name = self.scope_lookup_force(&impl_.type_name.raw, SymbolType::Struct);
+
+                // methods are not bound in the scope, they are named after the struct they belong to
+                let mut method_names = HashSet::new();
+                for method in &mut impl_.methods {
+                    if !method_names.insert(method.name.raw.clone()) {
+                        return Err(ResolverError::Redefinition(
+                            method.name.raw.clone(),
+                            method.span.clone(),
+                        ));
+                    }
+                    method.name.module_resolved =
+                        Some(Rc::new(format!("{type_name}.{}", method.name.raw)));
+                    struct_type_lookup(self, &mut method.return_type);
+
+                    self.scope_enter();
+                    for arg in &mut method.args {
+                        arg.visit(self).map_err(|error| error.at(&method.span))?;
+                    }
+
+                    method.body.visit(self)?;
+                    self.scope_exit();
+                }
+                impl_.type_name.module_resolved = Some(type_name);
+            }
             ASTNodeEnum::StructDef(struct_def) => {
-                struct_def.type_name.module_resolved =
-                    Some(self.scope_bind(&struct_def.type_name.raw, SymbolType::Struct)?);
+                struct_def.type_name.module_resolved = Some(
+                    self.scope_bind(&struct_def.type_name.raw, SymbolType::Struct)
+                        .map_err(|error| error.at(&struct_def.span))?,
+                );
 
                 for v in &mut struct_def.map.values_mut() {
-                    if let Type::Struct(struct_name) = v {
-                        struct_name.module_resolved =
-                            Some(self.scope_lookup_force(&struct_name.raw, SymbolType::Struct));
-                    }
+                    struct_type_lookup(self, v);
                 }
             }
             ASTNodeEnum::Use(use_) => {
                 for element in &mut use_.elements {
-                    let struct_name =
-                        self.scope_bind(&element.imported_name.raw, SymbolType::Struct)?;
-                    let fn_name = self.scope_bind(&element.imported_name.raw, SymbolType::Fn)?;
-                    let var_name = self.scope_bind(&element.imported_name.raw, SymbolType::Var)?;
+                    let name = &element.imported_name.raw;
+                    let bind = |scope_table: &mut ScopeTable, symbol_type| {
+                        scope_table
+                            .scope_bind(name, symbol_type)
+                            .map_err(|error| error.at(&use_.span))
+                    };
+                    let struct_name = bind(self, SymbolType::Struct)?;
+                    let fn_name = bind(self, SymbolType::Fn)?;
+                    let var_name = bind(self, SymbolType::Var)?;
 
                     if struct_name == fn_name && fn_name == var_name {
                         element.imported_name.module_resolved = Some(struct_name);
                     } else {
                         return Err(ResolverError::Redefinition(
                             element.imported_name.raw.clone(),
+                            use_.span.clone(),
                         ));
                     }
                 }
             }
 
-            ASTNodeEnum::VarDecl(_)
-            | ASTNodeEnum::LiteralValue(_)
-            | ASTNodeEnum::Expression(_)
-            | ASTNodeEnum::If(_)
-            | ASTNodeEnum::Else(_)
+            ASTNodeEnum::If(_)
             | ASTNodeEnum::While(_)
             | ASTNodeEnum::For(_)
-            | ASTNodeEnum::Statement(_)
-            | ASTNodeEnum::Definition(_) => return Ok((true, None)),
+            | ASTNodeEnum::Statement(_) => return Ok((true, None)),
         };
         return Ok((false, None));
     }
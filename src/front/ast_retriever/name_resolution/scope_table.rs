@@ -1,6 +1,6 @@
 use crate::front::ast_retriever::name_resolution::resolver::InternalResolveResult;
 use crate::front::ast_retriever::name_resolution::resolver::ResolverError::Redefinition;
-use crate::front::ast_types::{RawName, ResolvedName};
+use crate::front::ast_types::{RawName, ResolvedName, Span};
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -86,7 +86,7 @@ impl ScopeTable {
 
             match node.symbols.get_mut(&key) {
                 Some(_) => {
-                    return Err(Redefinition(name.clone()));
+                    return Err(Redefinition(name.clone(), Span::default()));
                 }
                 None => {
                     node.symbols.insert(key, resolved.clone());
@@ -1,12 +1,19 @@
 use crate::front::ast_retriever::reader::lexical::token_types::Token;
 use crate::front::ast_retriever::reader::syntax::parser::TokenStream;
+use crate::front::ast_types::Span;
 use crate::front::file_system::byte_stream::ByteStream;
+use std::rc::Rc;
 
 pub struct Lexer {
     reader: ByteStream,
+    file: Rc<str>,
     curr: char,
-    index: u64,
-    return_index: u64,
+    // where curr is
+    line: u32,
+    column: u32,
+    // where the token being read started
+    start_line: u32,
+    start_column: u32,
 }
 
 #[derive(Debug, PartialEq)]
@@ -15,39 +22,110 @@ pub enum TokenError {
     MultipleDecimals,
 }
 
-pub type TokenInfo = (Token, u64);
+pub type TokenInfo = (Token, Span);
+
+// an invalid token is reported where it was read
+pub type TokenResult = Result<TokenInfo, (TokenError, Span)>;
 
 impl Lexer {
+    // tests read sources that are not in any file
+    #[cfg(test)]
     pub fn new(reader: ByteStream) -> Lexer {
+        Lexer::new_with_file(reader, Rc::from(""))
+    }
+
+    // the file is only used to say where tokens are
+    pub fn new_with_file(reader: ByteStream, file: Rc<str>) -> Lexer {
         let mut lexer = Lexer {
             reader,
+            file,
             curr: '\0',
-            index: 0,
-            return_index: 0,
+            line: 1,
+            column: 0,
+            start_line: 1,
+            start_column: 1,
         };
         lexer.eat();
-        lexer.index = 0;
         lexer
     }
+
+    // the rest of a number that starts with the given digits
+    fn read_number(&mut self, mut number: String) -> Result<Token, TokenError> {
+        let mut dec = number.contains('.');
+
+        while self.curr.is_ascii_digit() || (self.curr == '.') {
+            if self.curr == '.' {
+                if dec {
+                    return Err(TokenError::MultipleDecimals);
+                } else {
+                    dec = true;
+                }
+            }
+
+            number.push(self.curr);
+            self.eat();
+        }
+
+        Ok(if dec {
+            match number.parse() {
+                Ok(n) => {
+                    if self.curr == 'd' {
+                        self.eat();
+                        Token::Double(n)
+                    } else {
+                        Token::Float(n as f32)
+                    }
+                }
+                Err(_) => return Err(TokenError::InvalidToken(number)),
+            }
+        } else {
+            match number.parse() {
+                Ok(n) => Token::Int(n),
+                Err(_) => return Err(TokenError::InvalidToken(number)),
+            }
+        })
+    }
+
     fn read_char(&mut self) -> char {
-        self.index += 1;
         self.reader.next()
     }
 
     fn eat(&mut self) -> char {
         let prev = self.curr;
         self.curr = self.read_char();
+        if prev == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
         prev
     }
 
-    fn get_token(&mut self) -> Result<TokenInfo, TokenError> {
-        return Ok((self.parse_token()?, self.return_index));
+    fn mark_start(&mut self) {
+        self.start_line = self.line;
+        self.start_column = self.column;
+    }
+
+    fn get_token(&mut self) -> TokenResult {
+        let token = self.parse_token();
+        let span = Span {
+            file: Rc::clone(&self.file),
+            line: self.start_line,
+            column: self.start_column,
+            end_line: self.line,
+            end_column: self.column,
+        };
+        match token {
+            Ok(token) => Ok((token, span)),
+            Err(error) => Err((error, span)),
+        }
     }
 
     fn parse_token(&mut self) -> Result<Token, TokenError> {
         // check for EOF
         if self.curr == '\0' {
-            self.return_index = self.index;
+            self.mark_start();
             return Ok(Token::Eof);
         }
 
@@ -59,6 +137,7 @@ impl Lexer {
 
             // skip comments
             if self.curr == '/' {
+                self.mark_start();
                 self.eat();
                 match self.curr {
                     '/' => {
@@ -82,7 +161,7 @@ impl Lexer {
                 }
             }
         }
-        self.return_index = self.index;
+        self.mark_start();
 
         // read string
         if self.curr == '"' {
@@ -151,44 +230,16 @@ impl Lexer {
         }
 
         // numbers
-        if self.curr.is_ascii_digit() || self.curr == '.' {
-            let mut number = String::new();
-            let mut dec = false;
-
-            while self.curr.is_ascii_digit() || (self.curr == '.') {
-                if self.curr == '.' {
-                    if dec {
-                        return Err(TokenError::MultipleDecimals);
-                    } else {
-                        dec = true;
-                    }
-                }
-
-                number.push(self.curr);
-                self.eat();
-            }
-
-            return Ok(if dec {
-                match number.parse() {
-                    Ok(n) => {
-                        if self.curr == 'd' {
-                            self.eat();
-                            Token::Double(n)
-                        } else {
-                            Token::Float(n as f32)
-                        }
-                    }
-                    Err(_) => return Err(TokenError::InvalidToken(number)),
-                }
-            } else {
-                match number.parse() {
-                    Ok(n) => Token::Int(n),
-                    Err(_) => return Err(TokenError::InvalidToken(number)),
-                }
-            });
+        if self.curr.is_ascii_digit() {
+            return self.read_number(String::new());
         }
 
         let prev = self.eat();
+
+        // `.5` is a number, any other `.` is a field access
+        if prev == '.' && self.curr.is_ascii_digit() {
+            return self.read_number(".".to_string());
+        }
         if self.curr == '=' {
             let assign = match prev {
                 '<' => Token::Leq,
@@ -260,7 +311,7 @@ impl Lexer {
 }
 
 impl TokenStream for Lexer {
-    fn next(&mut self) -> Result<TokenInfo, TokenError> {
+    fn next(&mut self) -> TokenResult {
         self.get_token()
     }
 }
@@ -270,6 +321,16 @@ mod tests {
     use super::*;
     use crate::front::file_system::byte_stream::StringReader;
 
+    // the line, column and end column of the next token
+    fn assert_next(lexer: &mut Lexer, token: Token, line: u32, column: u32, end_column: u32) {
+        let (next, span) = lexer.next().unwrap();
+        assert_eq!(next, token);
+        assert_eq!(
+            (span.line, span.column, span.end_line, span.end_column),
+            (line, column, line, end_column)
+        );
+    }
+
     #[test]
     fn simple_test() {
         let statement = "fn main() { return 0; }";
@@ -277,16 +338,37 @@ mod tests {
             statement.to_string(),
         ))));
 
-        assert_eq!(lexer.next().unwrap(), (Token::Fn, 0));
-        assert_eq!(lexer.next().unwrap(), (Token::Ident("main".to_string()), 3));
-        assert_eq!(lexer.next().unwrap(), (Token::LParen, 7));
-        assert_eq!(lexer.next().unwrap(), (Token::RParen, 8));
-        assert_eq!(lexer.next().unwrap(), (Token::LBrace, 10));
-        assert_eq!(lexer.next().unwrap(), (Token::Return, 12));
-        assert_eq!(lexer.next().unwrap(), (Token::Int(0), 19));
-        assert_eq!(lexer.next().unwrap(), (Token::Semicolon, 20));
-        assert_eq!(lexer.next().unwrap(), (Token::RBrace, 22));
-        assert_eq!(lexer.next().unwrap(), (Token::Eof, 23));
+        assert_next(&mut lexer, Token::Fn, 1, 1, 3);
+        assert_next(&mut lexer, Token::Ident("main".to_string()), 1, 4, 8);
+        assert_next(&mut lexer, Token::LParen, 1, 8, 9);
+        assert_next(&mut lexer, Token::RParen, 1, 9, 10);
+        assert_next(&mut lexer, Token::LBrace, 1, 11, 12);
+        assert_next(&mut lexer, Token::Return, 1, 13, 19);
+        assert_next(&mut lexer, Token::Int(0), 1, 20, 21);
+        assert_next(&mut lexer, Token::Semicolon, 1, 21, 22);
+        assert_next(&mut lexer, Token::RBrace, 1, 23, 24);
+        assert_next(&mut lexer, Token::Eof, 1, 24, 24);
+    }
+
+    #[test]
+    fn span_test() {
+        let statement = "fn main() {\n    // a / b\n    a /= 2;\n}";
+        let mut lexer = Lexer::new_with_file(
+            ByteStream::new(Box::from(StringReader::new(statement.to_string()))),
+            Rc::from("main.ing"),
+        );
+
+        for _ in 0..5 {
+            lexer.next().unwrap();
+        }
+        assert_next(&mut lexer, Token::Ident("a".to_string()), 3, 5, 6);
+        assert_next(&mut lexer, Token::SlashAssign, 3, 7, 9);
+        assert_next(&mut lexer, Token::Int(2), 3, 10, 11);
+        assert_next(&mut lexer, Token::Semicolon, 3, 11, 12);
+
+        let (token, span) = lexer.next().unwrap();
+        assert_eq!(token, Token::RBrace);
+        assert_eq!(span.to_string(), "main.ing:4:1");
     }
 
     #[test]
@@ -299,7 +381,24 @@ mod tests {
         assert_eq!(lexer.next().unwrap().0, Token::Int(643214));
         assert_eq!(lexer.next().unwrap().0, Token::Float(3243.24321));
         assert_eq!(lexer.next().unwrap().0, Token::Float(0.432432));
-        assert_eq!(lexer.next().err().unwrap(), TokenError::MultipleDecimals);
+        assert_eq!(lexer.next().err().unwrap().0, TokenError::MultipleDecimals);
+    }
+
+    #[test]
+    fn field_access_test() {
+        let statement = "a.b . c = 1.5;";
+        let mut lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+
+        assert_eq!(lexer.next().unwrap().0, Token::Ident("a".to_string()));
+        assert_eq!(lexer.next().unwrap().0, Token::Dot);
+        assert_eq!(lexer.next().unwrap().0, Token::Ident("b".to_string()));
+        assert_eq!(lexer.next().unwrap().0, Token::Dot);
+        assert_eq!(lexer.next().unwrap().0, Token::Ident("c".to_string()));
+        assert_eq!(lexer.next().unwrap().0, Token::Assign);
+        assert_eq!(lexer.next().unwrap().0, Token::Float(1.5));
+        assert_eq!(lexer.next().unwrap().0, Token::Semicolon);
     }
 
     #[test]
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Any,
@@ -90,3 +92,93 @@ pub enum Token {
     // Misc
     Eof,
 }
+
+// how a token is written, for error messages
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Token::Any => return write!(f, "a token"),
+            Token::Eof => return write!(f, "the end of the file"),
+
+            Token::Ident(s) => s,
+            Token::Null => "null",
+            Token::Bool(b) => return write!(f, "`{b}`"),
+            Token::Int(i) => return write!(f, "`{i}`"),
+            Token::Float(x) => return write!(f, "`{x}`"),
+            Token::Double(x) => return write!(f, "`{x}d`"),
+            Token::String(s) => return write!(f, "`\"{s}\"`"),
+
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Slash => "/",
+            Token::Percent => "%",
+            Token::Exclamation => "!",
+            Token::Ampersand => "&",
+            Token::And => "&&",
+            Token::Or => "||",
+            Token::PlusPlus => "++",
+            Token::MinusMinus => "--",
+
+            Token::Equal => "==",
+            Token::NotEqual => "!=",
+            Token::Leq => "<=",
+            Token::Geq => ">=",
+
+            Token::Assign => "=",
+            Token::PlusAssign => "+=",
+            Token::MinusAssign => "-=",
+            Token::StarAssign => "*=",
+            Token::SlashAssign => "/=",
+            Token::PercentAssign => "%=",
+
+            Token::Comma => ",",
+            Token::Semicolon => ";",
+            Token::Colon => ":",
+            Token::Dot => ".",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::LBracket => "[",
+            Token::RBracket => "]",
+            Token::LAngle => "<",
+            Token::RAngle => ">",
+
+            Token::Arrow => "->",
+
+            Token::Const => "const",
+
+            Token::VoidType => "void",
+            Token::IntType => "int",
+            Token::FloatType => "float",
+            Token::DoubleType => "double",
+            Token::BoolType => "bool",
+            Token::StringType => "string",
+            Token::StructType => "struct",
+
+            Token::Impl => "impl",
+
+            Token::Let => "let",
+
+            Token::Fn => "fn",
+            Token::Rec => "rec",
+            Token::Inline => "inline",
+
+            Token::If => "if",
+            Token::Else => "else",
+            Token::While => "while",
+            Token::For => "for",
+
+            Token::Return => "return",
+            Token::Break => "break",
+            Token::Continue => "continue",
+
+            Token::Use => "use",
+            Token::As => "as",
+            Token::Mod => "mod",
+            Token::Pub => "pub",
+        };
+        write!(f, "`{text}`")
+    }
+}
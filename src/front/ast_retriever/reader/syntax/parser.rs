@@ -1,12 +1,13 @@
-use crate::front::ast_retriever::reader::lexical::lexer::{TokenError, TokenInfo};
+use crate::front::ast_retriever::reader::lexical::lexer::{TokenError, TokenInfo, TokenResult};
 use crate::front::ast_retriever::reader::lexical::token_types::Token;
 use crate::front::ast_retriever::reader::lexical::token_types::Token::Any;
 use crate::front::ast_types::{
     AtomicExpression, BinOp, Block, Compound, CompoundValue, Definition, Else, Expression,
-    ExpressionEnum, FnCall, FnDef, FnMod, For, If, LiteralValue, Module, ModuleImport, NamePath,
-    Reference, Statement, StructDef, Type, UnOp, Use, UseElement, VarAssign, VarDecl, VarDef,
-    VarMod, While,
+    ExpressionEnum, FnCall, FnDef, FnMod, For, If, Impl, Index, IndexAssign, LiteralValue, Module,
+    ModuleImport, NamePath, Reference, Span, Statement, StructDef, StructLiteral, Type, UnOp, Use,
+    UseElement, VarAssign, VarDecl, VarDef, VarMod, While,
 };
+use crate::front::diagnostics::Diagnostic;
 use std::collections::{HashMap, VecDeque};
 use std::mem;
 use std::rc::Rc;
@@ -15,41 +16,71 @@ use std::rc::Rc;
 pub enum ParseError {
     Unknown,
     Unexpected(TokenInfo, String),
+    Token(TokenError, Span),
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Diagnostic {
+        match error {
+            ParseError::Unknown => Diagnostic::error("Could not parse the file"),
+            ParseError::Unexpected((_, span), message) => Diagnostic::error(message).at(&span),
+            ParseError::Token(TokenError::InvalidToken(token), span) => {
+                Diagnostic::error(format!("Invalid token `{token}`")).at(&span)
+            }
+            ParseError::Token(TokenError::MultipleDecimals, span) => {
+                Diagnostic::error("A number can only have one decimal point").at(&span)
+            }
+        }
+    }
 }
 
 pub type ParseResult<T> = Result<T, ParseError>;
 
 pub trait TokenStream {
-    fn next(&mut self) -> Result<TokenInfo, TokenError>;
+    fn next(&mut self) -> TokenResult;
 }
 
 pub struct Parser<T: TokenStream> {
     lexer: T,
-    token_index: u64,
+    token_span: Span,
+    // the span of the token eaten last, where the node being parsed ends
+    last_span: Span,
     curr_token: Token,
     future_tokens: VecDeque<TokenInfo>,
 }
 
 impl<T: TokenStream> Parser<T> {
-    pub fn new(lexer: T) -> Parser<T> {
+    pub fn new(lexer: T) -> ParseResult<Parser<T>> {
         let mut parser = Parser {
             lexer,
-            token_index: 0,
+            token_span: Span::default(),
+            last_span: Span::default(),
             curr_token: Token::Eof,
             future_tokens: VecDeque::new(),
         };
-        parser.eat(&Token::Eof).unwrap();
-        parser
+        parser.eat(&Token::Eof)?;
+        Ok(parser)
     }
 
     fn curr_token_info(&self) -> TokenInfo {
-        (self.curr_token.clone(), self.token_index)
+        (self.curr_token.clone(), self.token_span.clone())
+    }
+
+    // from the start of a node to the last token eaten
+    fn span_from(&self, start: &Span) -> Span {
+        start.to(&self.last_span)
     }
 
-    fn next(&mut self) -> TokenInfo {
+    fn read_token(&mut self) -> ParseResult<TokenInfo> {
+        self.lexer
+            .next()
+            .map_err(|(error, span)| ParseError::Token(error, span))
+    }
+
+    fn next(&mut self) -> ParseResult<TokenInfo> {
         match self.future_tokens.pop_front() {
-            None => self.lexer.next().unwrap(),
-            Some(front) => front,
+            None => self.read_token(),
+            Some(front) => Ok(front),
         }
     }
 
@@ -57,31 +88,35 @@ impl<T: TokenStream> Parser<T> {
         // return old lexical, set new lexical, set one buffer of next lexical
         if mem::discriminant(&self.curr_token) == mem::discriminant(type_) || matches!(type_, Any) {
             let old_curr = self.curr_token.clone();
-            let old_index = self.token_index;
-            (self.curr_token, self.token_index) = self.next();
+            let old_span = self.token_span.clone();
+            (self.curr_token, self.token_span) = self.next()?;
+            self.last_span = old_span.clone();
 
-            Ok((old_curr, old_index))
+            Ok((old_curr, old_span))
         } else {
             Err(ParseError::Unexpected(
-                (self.curr_token.clone(), self.token_index),
-                format!("Tried to eat {:?}, ate {:?}", type_, self.curr_token),
+                self.curr_token_info(),
+                format!("Expected {type_}, found {}", self.curr_token),
             ))
         }
     }
 
-    fn peek(&mut self, count: i32) -> &Token {
+    fn peek(&mut self, count: i32) -> ParseResult<&Token> {
         if count == 0 {
-            return &self.curr_token;
+            return Ok(&self.curr_token);
         }
 
+        // the tokens already peeked at are kept, so more are read from the lexer
         while self.future_tokens.len() < count as usize {
-            let next = self.next();
+            let next = self.read_token()?;
             self.future_tokens.push_back(next);
         }
 
-        &self.future_tokens[count as usize - 1].0
+        Ok(&self.future_tokens[count as usize - 1].0)
     }
 
+    // tests write the name paths they expect as `a.b.c`
+    #[cfg(test)]
     pub fn string_to_namepath(s: &str) -> NamePath {
         let mut path = Vec::new();
         let mut curr = String::new();
@@ -99,11 +134,40 @@ impl<T: TokenStream> Parser<T> {
         NamePath { name, path }
     }
 
+    // `.b.c` after the name `a`
+    fn parse_field_path(&mut self) -> ParseResult<Vec<String>> {
+        let mut path = Vec::new();
+        while matches!(self.curr_token, Token::Dot) {
+            self.eat(&Token::Dot)?;
+            match self.eat(&Any)? {
+                (Token::Ident(field), _) => path.push(field),
+                tok => Err(ParseError::Unexpected(
+                    tok,
+                    "Expected a field name".to_string(),
+                ))?,
+            }
+        }
+        Ok(path)
+    }
+
+    // how far ahead the token after the name at the current token and its fields is
+    fn peek_past_field_path(&mut self) -> ParseResult<i32> {
+        let mut count = 1;
+        while matches!(self.peek(count)?, Token::Dot)
+            && matches!(self.peek(count + 1)?, Token::Ident(_))
+        {
+            count += 2;
+        }
+        Ok(count)
+    }
+
     fn parse_atomic_expression(&mut self) -> ParseResult<Expression> {
+        let start = self.token_span.clone();
         if matches!(self.curr_token, Token::LBrace) {
             let compound = self.parse_compound()?;
             return Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                     LiteralValue::Compound(compound),
                 )),
@@ -113,45 +177,64 @@ impl<T: TokenStream> Parser<T> {
         match self.eat(&Any)? {
             (Token::Null, _) => Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                     LiteralValue::Null,
                 )),
             }),
             (Token::Bool(b), _) => Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
-                    LiteralValue::Int(b as i32),
+                    LiteralValue::Bool(b),
                 )),
             }),
             (Token::Int(i), _) => Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                     LiteralValue::Int(i),
                 )),
             }),
             (Token::Float(f), _) => Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                     LiteralValue::Float(f),
                 )),
             }),
             (Token::Double(f), _) => Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                     LiteralValue::Double(f),
                 )),
             }),
             (Token::String(s), _) => Ok(Expression {
                 type_: None,
+                span: self.span_from(&start),
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                     LiteralValue::String(s),
                 )),
             }),
             (Token::Ident(s), _) => {
+                let mut path = self.parse_field_path()?;
                 if matches!(self.curr_token, Token::LParen) {
-                    let mut fn_call = Box::from(FnCall {
-                        name: Reference::new(s),
-                        args: Vec::new(),
+                    // `a.b.len(` calls the method len on a.b
+                    let mut fn_call = Box::from(match path.pop() {
+                        Some(method) => FnCall {
+                            name: Reference::new(method),
+                            receiver: Some(NamePath {
+                                name: Reference::new(s),
+                                path,
+                            }),
+                            args: Vec::new(),
+                        },
+                        None => FnCall {
+                            name: Reference::new(s),
+                            receiver: None,
+                            args: Vec::new(),
+                        },
                     });
                     self.eat(&Token::LParen)?;
 
@@ -172,13 +255,44 @@ impl<T: TokenStream> Parser<T> {
                     Ok(Expression {
                         expr: ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(fn_call)),
                         type_: None,
+                        span: self.span_from(&start),
+                    })
+                } else if matches!(self.curr_token, Token::LBracket) {
+                    self.eat(&Token::LBracket)?;
+                    let index = self.parse_expression()?;
+                    self.eat(&Token::RBracket)?;
+
+                    Ok(Expression {
+                        expr: ExpressionEnum::AtomicExpression(AtomicExpression::Index(Index {
+                            array: NamePath {
+                                name: Reference::new(s),
+                                path,
+                            },
+                            index,
+                        })),
+                        type_: None,
+                        span: self.span_from(&start),
+                    })
+                } else if path.is_empty() && matches!(self.curr_token, Token::LBrace) {
+                    // conditions are always in brackets, so a brace after a name starts a struct literal
+                    let struct_literal = self.parse_struct_literal(s)?;
+                    Ok(Expression {
+                        expr: ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(
+                            struct_literal,
+                        )),
+                        type_: None,
+                        span: self.span_from(&start),
                     })
                 } else {
                     let var = Expression {
                         expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
-                            Parser::<T>::string_to_namepath(&s),
+                            NamePath {
+                                name: Reference::new(s),
+                                path,
+                            },
                         )),
                         type_: None,
+                        span: self.span_from(&start),
                     };
                     match self.curr_token {
                         Token::PlusPlus => {
@@ -186,6 +300,7 @@ impl<T: TokenStream> Parser<T> {
                             Ok(Expression {
                                 expr: ExpressionEnum::Unary(UnOp::PostInc, Box::from(var)),
                                 type_: None,
+                                span: self.span_from(&start),
                             })
                         }
                         Token::MinusMinus => {
@@ -193,6 +308,7 @@ impl<T: TokenStream> Parser<T> {
                             Ok(Expression {
                                 expr: ExpressionEnum::Unary(UnOp::PostDec, Box::from(var)),
                                 type_: None,
+                                span: self.span_from(&start),
                             })
                         }
                         _ => Ok(var),
@@ -279,6 +395,7 @@ impl<T: TokenStream> Parser<T> {
                 Some(op) => self.get_bin_op_prec(&op),
                 None => {
                     return Ok(Box::from(Expression {
+                        span: lhs.span.to(&rhs.span),
                         expr: ExpressionEnum::Binary(lhs, binop, rhs),
                         type_: None,
                     }));
@@ -290,6 +407,7 @@ impl<T: TokenStream> Parser<T> {
             }
 
             lhs = Box::from(Expression {
+                span: lhs.span.to(&rhs.span),
                 expr: ExpressionEnum::Binary(lhs, binop, rhs),
                 type_: None,
             });
@@ -297,6 +415,31 @@ impl<T: TokenStream> Parser<T> {
     }
 
     fn parse_expression_single(&mut self) -> ParseResult<Box<Expression>> {
+        let mut expr = self.parse_operand()?;
+
+        // `a as float`, binds tighter than any binary operator
+        while self.eat(&Token::As).is_ok() {
+            let type_ = match self.eat(&Any)? {
+                (Token::IntType, _) => Type::Int,
+                (Token::FloatType, _) => Type::Float,
+                (Token::DoubleType, _) => Type::Double,
+                (Token::BoolType, _) => Type::Bool,
+                tok => Err(ParseError::Unexpected(
+                    tok,
+                    "Expected number type for conversion".to_string(),
+                ))?,
+            };
+            expr = Box::from(Expression {
+                span: self.span_from(&expr.span),
+                expr: ExpressionEnum::Unary(UnOp::Cast(type_), expr),
+                type_: None,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_operand(&mut self) -> ParseResult<Box<Expression>> {
         match self.curr_token {
             Token::LParen => {
                 self.eat(&Token::LParen)?;
@@ -327,12 +470,13 @@ impl<T: TokenStream> Parser<T> {
                 };
 
                 // eat unop
-                self.eat(&Any)?;
+                let (_, start) = self.eat(&Any)?;
 
                 let right = self.parse_expression_single()?;
                 Ok(Box::from(Expression {
                     expr: ExpressionEnum::Unary(unop, right),
                     type_: None,
+                    span: self.span_from(&start),
                 }))
             }
         }
@@ -345,7 +489,7 @@ impl<T: TokenStream> Parser<T> {
     }
 
     fn parse_if_statement(&mut self) -> ParseResult<If> {
-        self.eat(&Token::If)?;
+        let (_, start) = self.eat(&Token::If)?;
         self.eat(&Token::LParen)?;
         let cond = self.parse_expression()?;
         self.eat(&Token::RParen)?;
@@ -359,11 +503,16 @@ impl<T: TokenStream> Parser<T> {
             }
         }
 
-        Ok(If { cond, body, else_ })
+        Ok(If {
+            cond,
+            body,
+            else_,
+            span: self.span_from(&start),
+        })
     }
 
     fn parse_for_statement(&mut self) -> ParseResult<For> {
-        self.eat(&Token::For)?;
+        let (_, start) = self.eat(&Token::For)?;
         self.eat(&Token::LParen)?;
         let init = Box::from(self.parse_statement()?);
         self.eat(&Token::Semicolon)?;
@@ -378,29 +527,40 @@ impl<T: TokenStream> Parser<T> {
             cond: Some(cond),
             step: Some(inc),
             body,
+            span: self.span_from(&start),
         })
     }
 
     fn parse_while_statement(&mut self) -> ParseResult<While> {
-        self.eat(&Token::While)?;
+        let (_, start) = self.eat(&Token::While)?;
         self.eat(&Token::LParen)?;
         let cond = self.parse_expression()?;
         self.eat(&Token::RParen)?;
         let body = Box::from(self.parse_block()?);
 
-        Ok(While { cond, body })
+        Ok(While {
+            cond,
+            body,
+            span: self.span_from(&start),
+        })
     }
 
     fn parse_assignment(&mut self) -> ParseResult<Statement> {
         match &self.eat(&Any)? {
-            (Token::Ident(var_name), _) => {
-                let name_path = Parser::<T>::string_to_namepath(var_name);
+            (Token::Ident(var_name), start) => {
+                let name_path = NamePath {
+                    name: Reference::new(var_name.clone()),
+                    path: self.parse_field_path()?,
+                };
+                let name_path_span = self.span_from(start);
 
                 let assign_op = match self.eat(&Any)? {
                     (Token::Assign, _) => {
+                        let expr = self.parse_expression()?;
                         return Ok(Statement::VarAssign(VarAssign {
                             name_path,
-                            expr: self.parse_expression()?,
+                            expr,
+                            span: self.span_from(start),
                         }));
                     }
                     (Token::PlusAssign, _) => BinOp::Add,
@@ -425,16 +585,19 @@ impl<T: TokenStream> Parser<T> {
                                 name_path.clone(),
                             )),
                             type_: None,
+                            span: name_path_span,
                         }),
                         assign_op,
                         rhs,
                     ),
                     type_: None,
+                    span: self.span_from(start),
                 };
 
                 Ok(Statement::VarAssign(VarAssign {
                     name_path,
                     expr: Box::from(expr),
+                    span: self.span_from(start),
                 }))
             }
             tok => Err(ParseError::Unexpected(
@@ -444,6 +607,81 @@ impl<T: TokenStream> Parser<T> {
         }
     }
 
+    // `println!(a, b)`, the only macro so far
+    fn parse_print(&mut self) -> ParseResult<Vec<Expression>> {
+        match self.eat(&Any)? {
+            (Token::Ident(s), _) if s == "println" => {}
+            tok => Err(ParseError::Unexpected(tok, "Unknown macro".to_string()))?,
+        }
+        self.eat(&Token::Exclamation)?;
+        self.eat(&Token::LParen)?;
+
+        let mut args = vec![];
+        if self.eat(&Token::RParen).is_err() {
+            loop {
+                args.push(*self.parse_expression()?);
+                if self.eat(&Token::Comma).is_err() {
+                    break;
+                }
+            }
+            self.eat(&Token::RParen)?;
+        }
+
+        Ok(args)
+    }
+
+    // `xs[i] = ...`, the element has already been parsed as an expression
+    fn parse_index_assign(&mut self, element: Index, start: Span) -> ParseResult<Statement> {
+        let assign_op = match self.curr_token {
+            Token::Assign => {
+                self.eat(&Token::Assign)?;
+                let expr = self.parse_expression()?;
+                return Ok(Statement::IndexAssign(IndexAssign {
+                    element,
+                    expr,
+                    span: self.span_from(&start),
+                }));
+            }
+            Token::PlusAssign => BinOp::Add,
+            Token::MinusAssign => BinOp::Sub,
+            Token::StarAssign => BinOp::Mul,
+            Token::SlashAssign => BinOp::Div,
+            Token::PercentAssign => BinOp::Mod,
+            _ => {
+                return Ok(Statement::Expression(Box::from(Expression {
+                    expr: ExpressionEnum::AtomicExpression(AtomicExpression::Index(element)),
+                    type_: None,
+                    span: start,
+                })))
+            }
+        };
+        self.eat(&Any)?;
+
+        let rhs = self.parse_expression()?;
+
+        let expr = Expression {
+            expr: ExpressionEnum::Binary(
+                Box::from(Expression {
+                    expr: ExpressionEnum::AtomicExpression(AtomicExpression::Index(
+                        element.clone(),
+                    )),
+                    type_: None,
+                    span: start.clone(),
+                }),
+                assign_op,
+                rhs,
+            ),
+            type_: None,
+            span: self.span_from(&start),
+        };
+
+        Ok(Statement::IndexAssign(IndexAssign {
+            element,
+            expr: Box::from(expr),
+            span: self.span_from(&start),
+        }))
+    }
+
     fn parse_statement(&mut self) -> ParseResult<Statement> {
         match &self.curr_token {
             Token::Const | Token::Let => {
@@ -452,7 +690,8 @@ impl<T: TokenStream> Parser<T> {
             }
 
             Token::Ident(_) => {
-                match self.peek(1) {
+                let after_path = self.peek_past_field_path()?;
+                match self.peek(after_path)? {
                     Token::Assign
                     | Token::PlusAssign
                     | Token::MinusAssign
@@ -462,7 +701,16 @@ impl<T: TokenStream> Parser<T> {
                         // variable / struct assignment
                         self.parse_assignment()
                     }
-                    _ => Ok(Statement::Expression(self.parse_expression()?)),
+                    Token::Exclamation => Ok(Statement::Print(self.parse_print()?)),
+                    _ => {
+                        let expr = self.parse_expression()?;
+                        match &expr.expr {
+                            ExpressionEnum::AtomicExpression(AtomicExpression::Index(element)) => {
+                                self.parse_index_assign(element.clone(), expr.span.clone())
+                            }
+                            _ => Ok(Statement::Expression(expr)),
+                        }
+                    }
                 }
             }
 
@@ -470,28 +718,39 @@ impl<T: TokenStream> Parser<T> {
             Token::While => Ok(Statement::While(self.parse_while_statement()?)),
             Token::For => Ok(Statement::For(self.parse_for_statement()?)),
             Token::Return => {
-                self.eat(&Token::Return)?;
+                let (_, start) = self.eat(&Token::Return)?;
+                if matches!(self.curr_token, Token::Semicolon) {
+                    // a bare return is the same as returning null
+                    return Ok(Statement::Return(Box::from(Expression {
+                        expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
+                            LiteralValue::Null,
+                        )),
+                        type_: None,
+                        span: start,
+                    })));
+                }
                 Ok(Statement::Return(self.parse_expression()?))
             }
             Token::Break => {
-                self.eat(&Token::Break)?;
-                Ok(Statement::Break)
+                let (_, span) = self.eat(&Token::Break)?;
+                Ok(Statement::Break(span))
             }
             Token::Continue => {
-                self.eat(&Token::Continue)?;
-                Ok(Statement::Continue)
+                let (_, span) = self.eat(&Token::Continue)?;
+                Ok(Statement::Continue(span))
             }
             _ => Ok(Statement::Expression(self.parse_expression()?)),
         }
     }
 
-    fn peek_def_type(&mut self) -> Option<Token> {
-        match self.curr_token {
+    fn peek_def_type(&mut self) -> ParseResult<Option<Token>> {
+        Ok(match self.curr_token {
             Token::Fn | Token::Rec | Token::Inline => Some(Token::Fn),
             Token::StructType => Some(Token::StructType),
-            Token::Const | Token::Let => Some(Token::Const),
+            Token::Const | Token::Let => Some(Token::Let),
+            Token::Impl => Some(Token::Impl),
 
-            Token::Pub => match self.peek(1) {
+            Token::Pub => match self.peek(1)? {
                 Token::Fn | Token::Rec | Token::Inline => Some(Token::Fn),
                 Token::StructType => Some(Token::StructType),
                 Token::Const | Token::Let => Some(Token::Let),
@@ -499,15 +758,16 @@ impl<T: TokenStream> Parser<T> {
             },
 
             _ => None,
-        }
+        })
     }
 
     fn parse_definition(&mut self) -> ParseResult<Definition> {
-        if let Some(type_) = self.peek_def_type() {
+        if let Some(type_) = self.peek_def_type()? {
             match type_ {
                 Token::Fn => return Ok(Definition::FnDef(self.parse_fn_def()?)),
                 Token::StructType => return Ok(Definition::StructDef(self.parse_struct_def()?)),
                 Token::Let => return Ok(Definition::VarDecl(self.parse_var_decl()?)),
+                Token::Impl => return Ok(Definition::Impl(self.parse_impl()?)),
                 _ => {}
             }
         }
@@ -543,7 +803,7 @@ impl<T: TokenStream> Parser<T> {
 
             let pub_ = self.curr_token == Token::Pub;
 
-            if let Some(type_) = self.peek_def_type() {
+            if let Some(type_) = self.peek_def_type()? {
                 match type_ {
                     Token::Fn => {
                         if pub_ {
@@ -563,25 +823,26 @@ impl<T: TokenStream> Parser<T> {
                         }
                         continue;
                     }
-                    Token::Let => {
-                        if global {
-                            if pub_ {
-                                pub_struct_var_definitions
-                                    .push(Definition::VarDecl(self.parse_var_decl()?));
-                            } else {
-                                struct_var_definitions
-                                    .push(Definition::VarDecl(self.parse_var_decl()?));
-                            }
+                    // a local declaration is a statement, which has to end with `;`
+                    Token::Let if global => {
+                        if pub_ {
+                            pub_struct_var_definitions
+                                .push(Definition::VarDecl(self.parse_var_decl()?));
                         } else {
-                            statements.push(Statement::VarDecl(self.parse_var_decl()?));
+                            struct_var_definitions
+                                .push(Definition::VarDecl(self.parse_var_decl()?));
                         }
                         continue;
                     }
+                    Token::Impl => {
+                        fn_definitions.push(Definition::Impl(self.parse_impl()?));
+                        continue;
+                    }
                     _ => {}
                 }
             }
 
-            if pub_ && matches!(self.peek(1), Token::Mod) {
+            if pub_ && matches!(self.peek(1)?, Token::Mod) {
                 mods.push(self.parse_module_import()?);
                 continue;
             }
@@ -668,7 +929,46 @@ impl<T: TokenStream> Parser<T> {
         Ok(compound)
     }
 
+    fn parse_struct_literal(&mut self, type_name: String) -> ParseResult<StructLiteral> {
+        let mut fields = HashMap::new();
+
+        self.eat(&Token::LBrace)?;
+
+        while !matches!(self.curr_token, Token::RBrace) {
+            let (name, index) = match self.eat(&Any)? {
+                (Token::Ident(s), index) => (s, index),
+                tok => {
+                    return Err(ParseError::Unexpected(
+                        tok,
+                        "Expected identifier for struct field name".to_string(),
+                    ));
+                }
+            };
+
+            self.eat(&Token::Colon)?;
+            let value = self.parse_expression()?;
+            if fields.insert(name.clone(), *value).is_some() {
+                return Err(ParseError::Unexpected(
+                    (Token::Ident(name.clone()), index),
+                    format!("Field {name} is given more than once"),
+                ));
+            }
+
+            if self.eat(&Token::Comma).is_err() {
+                break;
+            }
+        }
+
+        self.eat(&Token::RBrace)?;
+
+        Ok(StructLiteral {
+            type_name: Reference::new(type_name),
+            fields,
+        })
+    }
+
     fn parse_var_decl(&mut self) -> ParseResult<VarDecl> {
+        let start = self.token_span.clone();
         let mut mods: Vec<VarMod> = Vec::new();
         let _ = self.eat(&Token::Pub);
 
@@ -688,11 +988,13 @@ impl<T: TokenStream> Parser<T> {
 
         let type_ = if self.eat(&Token::Colon).is_ok() {
             Some(match self.eat(&Any)? {
+                (Token::LBracket, _) => self.parse_array_type()?,
+                (Token::LBrace, _) => self.parse_compound_type()?,
                 (Token::VoidType, _) => Type::Void,
                 (Token::IntType, _) => Type::Int,
                 (Token::FloatType, _) => Type::Float,
                 (Token::DoubleType, _) => Type::Double,
-                (Token::BoolType, _) => Type::Int,
+                (Token::BoolType, _) => Type::Bool,
                 (Token::StringType, _) => Type::String,
                 (Token::Ident(s), _) => Type::Struct(Reference::new(s)),
                 tok => Err(ParseError::Unexpected(
@@ -717,10 +1019,75 @@ impl<T: TokenStream> Parser<T> {
                 mods: Rc::new(mods),
             },
             expr,
+            span: self.span_from(&start),
         })
     }
 
+    // `[int; 16]`, after the opening bracket
+    fn parse_array_type(&mut self) -> ParseResult<Type> {
+        let element = match self.eat(&Any)? {
+            (Token::LBracket, _) => self.parse_array_type()?,
+            (Token::IntType, _) => Type::Int,
+            (Token::FloatType, _) => Type::Float,
+            (Token::DoubleType, _) => Type::Double,
+            (Token::BoolType, _) => Type::Bool,
+            (Token::StringType, _) => Type::String,
+            (Token::Ident(s), _) => Type::Struct(Reference::new(s)),
+            tok => Err(ParseError::Unexpected(
+                tok,
+                "Expected element type for array type".to_string(),
+            ))?,
+        };
+        self.eat(&Token::Semicolon)?;
+        let len = match self.eat(&Any)? {
+            (Token::Int(i), _) if i >= 0 => i as u32,
+            tok => Err(ParseError::Unexpected(
+                tok,
+                "Expected length for array type".to_string(),
+            ))?,
+        };
+        self.eat(&Token::RBracket)?;
+
+        Ok(Type::Array(Box::from(element), len))
+    }
+
+    // `{ hp: int, name: string }`, after the opening brace
+    fn parse_compound_type(&mut self) -> ParseResult<Type> {
+        let mut fields = HashMap::new();
+
+        while let Token::Ident(key) = &mut self.curr_token {
+            let key = key.clone();
+
+            self.eat(&Any)?;
+            self.eat(&Token::Colon)?;
+            let field_type = match self.eat(&Any)? {
+                (Token::LBrace, _) => self.parse_compound_type()?,
+                (Token::IntType, _) => Type::Int,
+                (Token::FloatType, _) => Type::Float,
+                (Token::DoubleType, _) => Type::Double,
+                (Token::BoolType, _) => Type::Bool,
+                (Token::StringType, _) => Type::String,
+                tok => Err(ParseError::Unexpected(
+                    tok,
+                    "Expected field type for compound type".to_string(),
+                ))?,
+            };
+            fields.insert(key, field_type);
+
+            if matches!(self.curr_token, Token::RBrace) {
+                break;
+            } else {
+                self.eat(&Token::Comma)?;
+            }
+        }
+
+        self.eat(&Token::RBrace)?;
+
+        Ok(Type::Compound(fields))
+    }
+
     fn parse_fn_def(&mut self) -> ParseResult<FnDef> {
+        let start = self.token_span.clone();
         let mut mods = Vec::new();
         let _ = self.eat(&Token::Pub);
 
@@ -773,11 +1140,12 @@ impl<T: TokenStream> Parser<T> {
 
                 let type_ = if self.eat(&Token::Colon).is_ok() {
                     Some(match self.eat(&Any)? {
+                        (Token::LBrace, _) => self.parse_compound_type()?,
                         (Token::VoidType, _) => Type::Void,
                         (Token::IntType, _) => Type::Int,
                         (Token::FloatType, _) => Type::Float,
                         (Token::DoubleType, _) => Type::Double,
-                        (Token::BoolType, _) => Type::Int,
+                        (Token::BoolType, _) => Type::Bool,
                         (Token::StringType, _) => Type::String,
                         (Token::Ident(s), _) => Type::Struct(Reference::new(s)),
                         tok => Err(ParseError::Unexpected(
@@ -806,11 +1174,12 @@ impl<T: TokenStream> Parser<T> {
 
         let return_type = if self.eat(&Token::Arrow).is_ok() {
             match self.eat(&Any)? {
+                (Token::LBrace, _) => self.parse_compound_type()?,
                 (Token::VoidType, _) => Type::Void,
                 (Token::IntType, _) => Type::Int,
                 (Token::FloatType, _) => Type::Float,
                 (Token::DoubleType, _) => Type::Double,
-                (Token::BoolType, _) => Type::Int,
+                (Token::BoolType, _) => Type::Bool,
                 (Token::StringType, _) => Type::String,
                 (Token::Ident(s), _) => Type::Struct(Reference::new(s)),
                 tok => {
@@ -832,10 +1201,52 @@ impl<T: TokenStream> Parser<T> {
             args,
             body,
             mods: Rc::new(mods),
+            span: self.span_from(&start),
+        })
+    }
+
+    fn parse_impl(&mut self) -> ParseResult<Impl> {
+        let (_, start) = self.eat(&Token::Impl)?;
+
+        let type_name = match self.eat(&Any)? {
+            (Token::Ident(s), _) => s,
+            tok => {
+                return Err(ParseError::Unexpected(
+                    tok,
+                    "Expected struct name for impl".to_string(),
+                ));
+            }
+        };
+
+        let mut methods = Vec::new();
+
+        self.eat(&Token::LBrace)?;
+        while !matches!(self.curr_token, Token::RBrace) {
+            let mut method = self.parse_fn_def()?;
+
+            // self is always the struct the method is implemented for
+            match method.args.first_mut() {
+                Some(arg) if arg.name.raw == "self" => {
+                    arg.type_ = Some(Type::Struct(Reference::new(type_name.clone())));
+                }
+                _ => Err(ParseError::Unexpected(
+                    self.curr_token_info(),
+                    format!("Method {} must take self", method.name.raw),
+                ))?,
+            }
+            methods.push(method);
+        }
+        self.eat(&Token::RBrace)?;
+
+        Ok(Impl {
+            type_name: Reference::new(type_name),
+            methods,
+            span: self.span_from(&start),
         })
     }
 
     fn parse_struct_def(&mut self) -> ParseResult<StructDef> {
+        let start = self.token_span.clone();
         let mods = Vec::new();
         let _ = self.eat(&Token::Pub);
 
@@ -873,7 +1284,7 @@ impl<T: TokenStream> Parser<T> {
                 (Token::IntType, _) => Type::Int,
                 (Token::FloatType, _) => Type::Float,
                 (Token::DoubleType, _) => Type::Double,
-                (Token::BoolType, _) => Type::Int,
+                (Token::BoolType, _) => Type::Bool,
                 (Token::StringType, _) => Type::String,
                 (Token::Ident(s), _) => Type::Struct(Reference::new(s)),
                 tok => {
@@ -897,6 +1308,7 @@ impl<T: TokenStream> Parser<T> {
             mods: Rc::new(mods),
             type_name: Reference::new(struct_name),
             map,
+            span: self.span_from(&start),
         })
     }
 
@@ -907,6 +1319,7 @@ impl<T: TokenStream> Parser<T> {
         Ok(module)
     }
     fn parse_module_import(&mut self) -> ParseResult<ModuleImport> {
+        let start = self.token_span.clone();
         let public = self.eat(&Token::Pub).is_ok();
         self.eat(&Token::Mod)?;
 
@@ -914,7 +1327,11 @@ impl<T: TokenStream> Parser<T> {
 
         if let (Token::Ident(s), _) = tok {
             self.eat(&Token::Semicolon)?;
-            Ok(ModuleImport { public, name: s })
+            Ok(ModuleImport {
+                public,
+                name: s,
+                span: self.span_from(&start),
+            })
         } else {
             Err(ParseError::Unexpected(
                 tok,
@@ -923,11 +1340,12 @@ impl<T: TokenStream> Parser<T> {
         }
     }
     fn parse_use_import(&mut self) -> ParseResult<Use> {
-        self.eat(&Token::Use)?;
+        let (_, start) = self.eat(&Token::Use)?;
 
         let mut use_ = Use {
             path: Vec::new(),
             elements: Vec::new(),
+            span: Span::default(),
         };
 
         loop {
@@ -958,6 +1376,7 @@ impl<T: TokenStream> Parser<T> {
                             }),
                         });
 
+                        use_.span = self.span_from(&start);
                         return Ok(use_);
                     }
                     tok => {
@@ -1018,6 +1437,7 @@ impl<T: TokenStream> Parser<T> {
                 }
             }
         }
+        use_.span = self.span_from(&start);
         Ok(use_)
     }
 }
@@ -1026,6 +1446,7 @@ impl<T: TokenStream> Parser<T> {
 mod tests {
     use super::*;
     use crate::front::ast_retriever::reader::lexical::lexer::Lexer;
+    use crate::front::ast_types::visitor::without_spans;
     use crate::front::file_system::byte_stream::{ByteStream, StringReader};
 
     #[test]
@@ -1034,9 +1455,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.definitions.len(), 1);
         assert_eq!(
@@ -1052,9 +1473,11 @@ mod tests {
                             LiteralValue::Int(0)
                         )),
                         type_: None,
+                        span: Span::default(),
                     }))],
                 },
                 mods: Rc::new(Vec::new()),
+                span: Span::default(),
             })
         );
     }
@@ -1065,9 +1488,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 7);
         assert_eq!(
@@ -1083,7 +1506,9 @@ mod tests {
                         LiteralValue::Int(0)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1099,7 +1524,9 @@ mod tests {
                         LiteralValue::Int(1)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1115,7 +1542,9 @@ mod tests {
                         LiteralValue::Int(2)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1131,7 +1560,9 @@ mod tests {
                         LiteralValue::Float(3.0)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1147,23 +1578,27 @@ mod tests {
                         LiteralValue::Double(4.0)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
         assert_eq!(
             block.statements[5],
             (Statement::VarDecl(VarDecl {
                 var_def: VarDef {
-                    type_: Some(Type::Int),
+                    type_: Some(Type::Bool),
                     name: Reference::new("f".to_string()),
                     mods: Rc::new(Vec::new()),
                 },
                 expr: Some(Box::from(Expression {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
-                        LiteralValue::Int(1)
+                        LiteralValue::Bool(true)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1179,7 +1614,9 @@ mod tests {
                         LiteralValue::String("hello".to_string())
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
     }
@@ -1191,9 +1628,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         let compound = {
             let mut map = HashMap::new();
@@ -1204,6 +1641,7 @@ mod tests {
                         LiteralValue::Int(0),
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
             );
             map.insert(
@@ -1213,6 +1651,7 @@ mod tests {
                         LiteralValue::Int(1),
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
             );
             map.insert(
@@ -1222,6 +1661,7 @@ mod tests {
                         LiteralValue::Int(2),
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
             );
             map
@@ -1241,7 +1681,9 @@ mod tests {
                         LiteralValue::Compound(compound)
                     )),
                     type_: None,
+                    span: Span::default(),
                 })),
+                span: Span::default(),
             }))
         );
     }
@@ -1252,9 +1694,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 7);
         assert_eq!(
@@ -1266,7 +1708,9 @@ mod tests {
                         LiteralValue::Int(0)
                     )),
                     type_: None,
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1278,7 +1722,9 @@ mod tests {
                         LiteralValue::Float(2.4)
                     )),
                     type_: None,
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1292,6 +1738,7 @@ mod tests {
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
                             type_: None,
+                            span: Span::default(),
                         }),
                         BinOp::Add,
                         Box::from(Expression {
@@ -1299,10 +1746,13 @@ mod tests {
                                 LiteralValue::Int(2)
                             )),
                             type_: None,
+                            span: Span::default(),
                         }),
                     ),
                     type_: None,
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1317,6 +1767,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Sub,
                         Box::from(Expression {
@@ -1324,9 +1775,12 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(3)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1341,6 +1795,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Mul,
                         Box::from(Expression {
@@ -1348,9 +1803,12 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(4)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1365,6 +1823,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Div,
                         Box::from(Expression {
@@ -1372,9 +1831,12 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(5)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -1389,6 +1851,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Mod,
                         Box::from(Expression {
@@ -1396,9 +1859,12 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(6)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
     }
@@ -1409,9 +1875,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 2);
         assert_eq!(
@@ -1430,6 +1896,7 @@ mod tests {
                                     expr: ExpressionEnum::AtomicExpression(
                                         AtomicExpression::Literal(LiteralValue::Int(0)),
                                     ),
+                                    span: Span::default(),
                                 })),
                             );
                             map.insert(
@@ -1439,6 +1906,7 @@ mod tests {
                                     expr: ExpressionEnum::AtomicExpression(
                                         AtomicExpression::Literal(LiteralValue::Int(1)),
                                     ),
+                                    span: Span::default(),
                                 })),
                             );
                             map.insert(
@@ -1448,13 +1916,16 @@ mod tests {
                                     expr: ExpressionEnum::AtomicExpression(
                                         AtomicExpression::Literal(LiteralValue::Int(2)),
                                     ),
+                                    span: Span::default(),
                                 })),
                             );
 
                             map
                         })
                     )),
+                    span: Span::default(),
                 }),
+                span: Span::default(),
             }))
         );
     }
@@ -1465,9 +1936,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.definitions.len(), 1);
         assert_eq!(
@@ -1500,6 +1971,7 @@ mod tests {
                                             Parser::<Lexer>::string_to_namepath("a")
                                         )
                                     ),
+                                    span: Span::default(),
                                 }),
                                 BinOp::Add,
                                 Box::from(Expression {
@@ -1509,12 +1981,15 @@ mod tests {
                                             Parser::<Lexer>::string_to_namepath("b")
                                         )
                                     ),
+                                    span: Span::default(),
                                 }),
                             ),
+                            span: Span::default(),
                         })))
                     ],
                 }),
                 mods: Rc::new(Vec::new()),
+                span: Span::default(),
             }))
         );
     }
@@ -1525,9 +2000,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 1);
         assert_eq!(
@@ -1537,28 +2012,33 @@ mod tests {
                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(Box::from(
                     FnCall {
                         name: Reference::new("add".to_string()),
+                        receiver: None,
                         args: vec![
                             Expression {
                                 type_: None,
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(1)
                                 )),
+                                span: Span::default(),
                             },
                             Expression {
                                 type_: None,
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(2)
                                 )),
+                                span: Span::default(),
                             },
                             Expression {
                                 type_: None,
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(3)
                                 )),
+                                span: Span::default(),
                             },
                         ],
                     }
                 ))),
+                span: Span::default(),
             })))
         );
     }
@@ -1569,9 +2049,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 1);
         assert_eq!(
@@ -1585,6 +2065,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Eq,
                         Box::from(Expression {
@@ -1592,8 +2073,10 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(0)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
                 body: Box::from(Block {
                     definitions: vec![],
@@ -1603,10 +2086,12 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(0)
                             )),
+                            span: Span::default(),
                         })))
                     ],
                 }),
                 else_: None,
+                span: Span::default(),
             }))
         );
     }
@@ -1618,9 +2103,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 1);
         assert_eq!(
@@ -1634,6 +2119,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Eq,
                         Box::from(Expression {
@@ -1641,8 +2127,10 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(0)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
                 body: Box::from(Block {
                     definitions: vec![],
@@ -1652,6 +2140,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(0)
                             )),
+                            span: Span::default(),
                         })))
                     ],
                 }),
@@ -1664,6 +2153,7 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                     Parser::<Lexer>::string_to_namepath("a")
                                 )),
+                                span: Span::default(),
                             }),
                             BinOp::Eq,
                             Box::from(Expression {
@@ -1671,8 +2161,10 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(1)
                                 )),
+                                span: Span::default(),
                             }),
                         ),
+                        span: Span::default(),
                     }),
                     body: Box::from(Block {
                         definitions: vec![],
@@ -1682,6 +2174,7 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(1)
                                 )),
+                                span: Span::default(),
                             })))
                         ],
                     }),
@@ -1693,10 +2186,13 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(2)
                                 )),
+                                span: Span::default(),
                             })))
                         ],
                     }))),
+                    span: Span::default(),
                 }))),
+                span: Span::default(),
             }))
         );
     }
@@ -1707,9 +2203,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 1);
         assert_eq!(
@@ -1723,6 +2219,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("a")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Lt,
                         Box::from(Expression {
@@ -1730,8 +2227,10 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(10)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 }),
                 body: Box::from(Block {
                     definitions: vec![],
@@ -1748,6 +2247,7 @@ mod tests {
                                                 Parser::<Lexer>::string_to_namepath("a")
                                             )
                                         ),
+                                        span: Span::default(),
                                     }),
                                     BinOp::Add,
                                     Box::from(Expression {
@@ -1755,12 +2255,16 @@ mod tests {
                                         expr: ExpressionEnum::AtomicExpression(
                                             AtomicExpression::Literal(LiteralValue::Int(1))
                                         ),
+                                        span: Span::default(),
                                     }),
                                 ),
+                                span: Span::default(),
                             }),
+                            span: Span::default(),
                         }))
                     ],
                 }),
+                span: Span::default(),
             }))
         );
     }
@@ -1771,9 +2275,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 1);
         assert_eq!(
@@ -1790,7 +2294,9 @@ mod tests {
                         expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                             LiteralValue::Int(0)
                         )),
+                        span: Span::default(),
                     })),
+                    span: Span::default(),
                 }))),
                 cond: Some(Box::from(Expression {
                     type_: None,
@@ -1800,6 +2306,7 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                 Parser::<Lexer>::string_to_namepath("i")
                             )),
+                            span: Span::default(),
                         }),
                         BinOp::Lt,
                         Box::from(Expression {
@@ -1807,8 +2314,10 @@ mod tests {
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(10)
                             )),
+                            span: Span::default(),
                         }),
                     ),
+                    span: Span::default(),
                 })),
                 step: Some(Box::from(Statement::VarAssign(VarAssign {
                     name_path: Parser::<Lexer>::string_to_namepath("i"),
@@ -1820,6 +2329,7 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                     Parser::<Lexer>::string_to_namepath("i")
                                 )),
+                                span: Span::default(),
                             }),
                             BinOp::Add,
                             Box::from(Expression {
@@ -1827,9 +2337,12 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                     LiteralValue::Int(1)
                                 )),
+                                span: Span::default(),
                             }),
                         ),
+                        span: Span::default(),
                     }),
+                    span: Span::default(),
                 }))),
                 body: Block {
                     definitions: vec![],
@@ -1846,6 +2359,7 @@ mod tests {
                                                 Parser::<Lexer>::string_to_namepath("a")
                                             )
                                         ),
+                                        span: Span::default(),
                                     }),
                                     BinOp::Add,
                                     Box::from(Expression {
@@ -1853,25 +2367,56 @@ mod tests {
                                         expr: ExpressionEnum::AtomicExpression(
                                             AtomicExpression::Literal(LiteralValue::Int(1))
                                         ),
+                                        span: Span::default(),
                                     }),
                                 ),
+                                span: Span::default(),
                             }),
+                            span: Span::default(),
                         }))
                     ],
                 },
+                span: Span::default(),
             }))
         );
     }
 
+    #[test]
+    fn bare_return_test() {
+        let statement = "fn main() { return; }";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
+
+        match &block.definitions[0] {
+            Definition::FnDef(fn_def) => {
+                assert_eq!(
+                    fn_def.body.statements,
+                    vec![Statement::Return(Box::from(Expression {
+                        expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
+                            LiteralValue::Null
+                        )),
+                        type_: None,
+                        span: Span::default(),
+                    }))]
+                );
+            }
+            _ => panic!("Expected FnDef"),
+        }
+    }
+
     #[test]
     fn loop_break_continue_test() {
         let statement = "while (true) { break; continue; }";
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.statements.len(), 1);
         assert_eq!(
@@ -1880,13 +2425,18 @@ mod tests {
                 cond: Box::from(Expression {
                     type_: None,
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
-                        LiteralValue::Int(1)
+                        LiteralValue::Bool(true)
                     )),
+                    span: Span::default(),
                 }),
                 body: Box::from(Block {
                     definitions: vec![],
-                    statements: vec![(Statement::Break), (Statement::Continue),],
+                    statements: vec![
+                        (Statement::Break(Span::default())),
+                        (Statement::Continue(Span::default())),
+                    ],
                 }),
+                span: Span::default(),
             }))
         );
     }
@@ -1897,9 +2447,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let expr = parser.parse_expression().unwrap();
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
 
         assert_eq!(
             expr,
@@ -1927,6 +2477,7 @@ mod tests {
                                                                 ),
                                                             )
                                                         ),
+                                                        span: Span::default(),
                                                     }),
                                                     BinOp::Add,
                                                     Box::from(Expression {
@@ -1938,8 +2489,10 @@ mod tests {
                                                                 ),
                                                             )
                                                         ),
+                                                        span: Span::default(),
                                                     }),
                                                 ),
+                                                span: Span::default(),
                                             }),
                                             BinOp::Add,
                                             Box::from(Expression {
@@ -1949,8 +2502,10 @@ mod tests {
                                                         Parser::<Lexer>::string_to_namepath("c"),
                                                     )
                                                 ),
+                                                span: Span::default(),
                                             }),
                                         ),
+                                        span: Span::default(),
                                     }),
                                     BinOp::Add,
                                     Box::from(Expression {
@@ -1960,8 +2515,10 @@ mod tests {
                                                 Parser::<Lexer>::string_to_namepath("d"),
                                             )
                                         ),
+                                        span: Span::default(),
                                     }),
                                 ),
+                                span: Span::default(),
                             }),
                             BinOp::Sub,
                             Box::from(Expression {
@@ -1969,8 +2526,10 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                     Parser::<Lexer>::string_to_namepath("e"),
                                 )),
+                                span: Span::default(),
                             }),
                         ),
+                        span: Span::default(),
                     }),
                     BinOp::Sub,
                     Box::from(Expression {
@@ -1978,8 +2537,10 @@ mod tests {
                         expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                             Parser::<Lexer>::string_to_namepath("f"),
                         )),
+                        span: Span::default(),
                     }),
                 ),
+                span: Span::default(),
             })
         );
     }
@@ -1990,9 +2551,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let expr = parser.parse_expression().unwrap();
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
 
         // should result in tree
         //          -
@@ -2013,6 +2574,7 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("b"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Mul,
                 Box::from(Expression {
@@ -2020,8 +2582,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("c"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let a_plus = Box::from(Expression {
@@ -2032,10 +2596,12 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("a"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Add,
                 b_times_c,
             ),
+            span: Span::default(),
         });
 
         let d_div_e = Box::from(Expression {
@@ -2046,6 +2612,7 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("d"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Div,
                 Box::from(Expression {
@@ -2053,8 +2620,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("e"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let mod_f = Box::from(Expression {
@@ -2067,8 +2636,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("f"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         assert_eq!(
@@ -2076,6 +2647,7 @@ mod tests {
             Box::from(Expression {
                 type_: None,
                 expr: ExpressionEnum::Binary(a_plus, BinOp::Sub, mod_f),
+                span: Span::default(),
             })
         );
     }
@@ -2086,9 +2658,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let expr = parser.parse_expression().unwrap();
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
 
         // should result in tree
         //                    %
@@ -2113,6 +2685,7 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("c"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Sub,
                 Box::from(Expression {
@@ -2120,8 +2693,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("d"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let b_times_c_minus_d = Box::from(Expression {
@@ -2132,10 +2707,12 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("b"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Mul,
                 c_minus_d,
             ),
+            span: Span::default(),
         });
 
         let b_times_c_minus_d_div_e = Box::from(Expression {
@@ -2148,8 +2725,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("e"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let b_times_c_minus_d_div_e_mod_f = Box::from(Expression {
@@ -2162,8 +2741,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("f"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let a_plus_b_times_c_minus_d_div_e_mod_f = Box::from(Expression {
@@ -2174,10 +2755,12 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("a"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Add,
                 b_times_c_minus_d_div_e_mod_f,
             ),
+            span: Span::default(),
         });
 
         assert_eq!(expr, a_plus_b_times_c_minus_d_div_e_mod_f);
@@ -2189,9 +2772,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let expr = parser.parse_expression().unwrap();
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
 
         // should result in tree
         //                             ||
@@ -2213,6 +2796,7 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("a"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Eq,
                 Box::from(Expression {
@@ -2220,8 +2804,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("b"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let a_eq_b_and_c = Box::from(Expression {
@@ -2234,8 +2820,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("c"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let d_neq_e = Box::from(Expression {
@@ -2246,6 +2834,7 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("d"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Neq,
                 Box::from(Expression {
@@ -2253,13 +2842,16 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("e"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let a_eq_b_and_c_or_d_neq_e = Box::from(Expression {
             type_: None,
             expr: ExpressionEnum::Binary(a_eq_b_and_c, BinOp::Or, d_neq_e),
+            span: Span::default(),
         });
 
         assert_eq!(expr, a_eq_b_and_c_or_d_neq_e);
@@ -2271,9 +2863,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let expr = parser.parse_expression().unwrap();
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
 
         // should result in tree
         //          +
@@ -2293,8 +2885,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("a"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let deref_d = Box::from(Expression {
@@ -2306,8 +2900,10 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("d"),
                     )),
+                    span: Span::default(),
                 }),
             ),
+            span: Span::default(),
         });
 
         let b_times_deref_d = Box::from(Expression {
@@ -2318,15 +2914,18 @@ mod tests {
                     expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                         Parser::<Lexer>::string_to_namepath("b"),
                     )),
+                    span: Span::default(),
                 }),
                 BinOp::Mul,
                 deref_d,
             ),
+            span: Span::default(),
         });
 
         let deref_a_plus_b_times_deref_d = Box::from(Expression {
             type_: None,
             expr: ExpressionEnum::Binary(deref_a, BinOp::Add, b_times_deref_d),
+            span: Span::default(),
         });
 
         assert_eq!(expr, deref_a_plus_b_times_deref_d);
@@ -2338,9 +2937,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let expr = parser.parse_expression().unwrap();
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
 
         assert_eq!(
             expr,
@@ -2356,8 +2955,10 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                     Parser::<Lexer>::string_to_namepath("a")
                                 )),
+                                span: Span::default(),
                             }),
                         ),
+                        span: Span::default(),
                     }),
                     BinOp::Add,
                     Box::from(Expression {
@@ -2369,10 +2970,13 @@ mod tests {
                                 expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
                                     Parser::<Lexer>::string_to_namepath("b")
                                 )),
+                                span: Span::default(),
                             }),
                         ),
+                        span: Span::default(),
                     }),
                 ),
+                span: Span::default(),
             })
         );
     }
@@ -2383,9 +2987,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.definitions.len(), 1);
         assert_eq!(
@@ -2404,8 +3008,340 @@ mod tests {
                     );
                     map
                 },
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn struct_literal_test() {
+        let statement = "A { a: 1, b: B { c: d }, }";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let expr = Box::new(without_spans(*parser.parse_expression().unwrap()));
+
+        assert_eq!(
+            expr,
+            Box::from(Expression {
+                type_: None,
+                expr: ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(
+                    StructLiteral {
+                        type_name: Reference::new("A".to_string()),
+                        fields: HashMap::from([
+                            (
+                                "a".to_string(),
+                                Expression {
+                                    type_: None,
+                                    expr: ExpressionEnum::AtomicExpression(
+                                        AtomicExpression::Literal(LiteralValue::Int(1))
+                                    ),
+                                    span: Span::default(),
+                                }
+                            ),
+                            (
+                                "b".to_string(),
+                                Expression {
+                                    type_: None,
+                                    expr: ExpressionEnum::AtomicExpression(
+                                        AtomicExpression::StructLiteral(StructLiteral {
+                                            type_name: Reference::new("B".to_string()),
+                                            fields: HashMap::from([(
+                                                "c".to_string(),
+                                                Expression {
+                                                    type_: None,
+                                                    expr: ExpressionEnum::AtomicExpression(
+                                                        AtomicExpression::Variable(
+                                                            Parser::<Lexer>::string_to_namepath(
+                                                                "d"
+                                                            )
+                                                        )
+                                                    ),
+                                                    span: Span::default(),
+                                                }
+                                            )]),
+                                        })
+                                    ),
+                                    span: Span::default(),
+                                }
+                            ),
+                        ]),
+                    }
+                )),
+                span: Span::default(),
+            })
+        );
+
+        let statement = "A { a: 1, a: 2 }";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert!(parser.parse_expression().is_err());
+    }
+
+    #[test]
+    fn impl_definition_test() {
+        let statement =
+            "impl A { fn len(self) -> int { return self.x; } fn f(self, b: int) { self.len(b); } }";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
+
+        assert_eq!(block.definitions.len(), 1);
+        let Definition::Impl(impl_) = &block.definitions[0] else {
+            panic!("Expected Impl");
+        };
+        assert_eq!(impl_.type_name, Reference::new("A".to_string()));
+        assert_eq!(impl_.methods.len(), 2);
+        assert_eq!(impl_.methods[0].name, Reference::new("len".to_string()));
+        assert_eq!(
+            impl_.methods[0].args[0].type_,
+            Some(Type::Struct(Reference::new("A".to_string())))
+        );
+
+        assert_eq!(
+            impl_.methods[1].body.statements[0],
+            Statement::Expression(Box::from(Expression {
+                expr: ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(Box::from(
+                    FnCall {
+                        name: Reference::new("len".to_string()),
+                        receiver: Some(NamePath {
+                            name: Reference::new("self".to_string()),
+                            path: vec![],
+                        }),
+                        args: vec![Expression {
+                            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
+                                NamePath {
+                                    name: Reference::new("b".to_string()),
+                                    path: vec![],
+                                }
+                            )),
+                            type_: None,
+                            span: Span::default(),
+                        }],
+                    }
+                ))),
+                type_: None,
+                span: Span::default(),
+            }))
+        );
+    }
+
+    #[test]
+    fn impl_without_self_test() {
+        let statement = "impl A { fn new() {} }";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert!(parser.parse_module_no_brace(false).is_err());
+    }
+
+    #[test]
+    fn print_test() {
+        let statement = "println!(a, \"b\");";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert_eq!(
+            without_spans(parser.parse_statement().unwrap()),
+            Statement::Print(vec![
+                Expression {
+                    type_: None,
+                    expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
+                        Parser::<Lexer>::string_to_namepath("a"),
+                    )),
+                    span: Span::default(),
+                },
+                Expression {
+                    type_: None,
+                    expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
+                        LiteralValue::String("b".to_string())
+                    )),
+                    span: Span::default(),
+                },
+            ])
+        );
+
+        let statement = "print!(a);";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert!(parser.parse_statement().is_err());
+    }
+
+    #[test]
+    fn compound_type_test() {
+        let statement = "let c: { hp: int, pos: { x: float } } = c;";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert_eq!(
+            without_spans(parser.parse_statement().unwrap()),
+            Statement::VarDecl(VarDecl {
+                var_def: VarDef {
+                    type_: Some(Type::Compound(HashMap::from([
+                        ("hp".to_string(), Type::Int),
+                        (
+                            "pos".to_string(),
+                            Type::Compound(HashMap::from([("x".to_string(), Type::Float)]))
+                        ),
+                    ]))),
+                    name: Reference::new("c".to_string()),
+                    mods: Rc::new(Vec::new()),
+                },
+                expr: Some(Box::from(Expression {
+                    type_: None,
+                    expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
+                        Parser::<Lexer>::string_to_namepath("c"),
+                    )),
+                    span: Span::default(),
+                })),
+                span: Span::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn cast_test() {
+        let statement = "-a as float * 2.0";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let a = Box::from(Expression {
+            type_: None,
+            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
+                Parser::<Lexer>::string_to_namepath("a"),
+            )),
+            span: Span::default(),
+        });
+
+        assert_eq!(
+            Box::new(without_spans(*parser.parse_expression().unwrap())),
+            Box::from(Expression {
+                type_: None,
+                expr: ExpressionEnum::Binary(
+                    Box::from(Expression {
+                        type_: None,
+                        expr: ExpressionEnum::Unary(
+                            UnOp::Neg,
+                            Box::from(Expression {
+                                type_: None,
+                                expr: ExpressionEnum::Unary(UnOp::Cast(Type::Float), a),
+                                span: Span::default(),
+                            }),
+                        ),
+                        span: Span::default(),
+                    }),
+                    BinOp::Mul,
+                    Box::from(Expression {
+                        type_: None,
+                        expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
+                            LiteralValue::Float(2.0)
+                        )),
+                        span: Span::default(),
+                    }),
+                ),
+                span: Span::default(),
             })
         );
+
+        let statement = "a as A";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert!(parser.parse_expression().is_err());
+    }
+
+    #[test]
+    fn array_test() {
+        let statement = "let xs: [[int; 2]; 16]";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert_eq!(
+            parser.parse_var_decl().unwrap().var_def.type_,
+            Some(Type::Array(
+                Box::from(Type::Array(Box::from(Type::Int), 2)),
+                16
+            ))
+        );
+
+        let statement = "xs[i] += xs[0];";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let element = |index: Expression| Index {
+            array: Parser::<Lexer>::string_to_namepath("xs"),
+            index: Box::from(index),
+        };
+        let i = element(Expression {
+            type_: None,
+            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(
+                Parser::<Lexer>::string_to_namepath("i"),
+            )),
+            span: Span::default(),
+        });
+        let zero = element(Expression {
+            type_: None,
+            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Int(0))),
+            span: Span::default(),
+        });
+
+        assert_eq!(
+            without_spans(parser.parse_statement().unwrap()),
+            Statement::IndexAssign(IndexAssign {
+                element: i.clone(),
+                expr: Box::from(Expression {
+                    type_: None,
+                    expr: ExpressionEnum::Binary(
+                        Box::from(Expression {
+                            type_: None,
+                            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Index(i)),
+                            span: Span::default(),
+                        }),
+                        BinOp::Add,
+                        Box::from(Expression {
+                            type_: None,
+                            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Index(zero)),
+                            span: Span::default(),
+                        }),
+                    ),
+                    span: Span::default(),
+                }),
+                span: Span::default(),
+            })
+        );
+
+        let statement = "let xs: [int; -1]";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        assert!(parser.parse_var_decl().is_err());
     }
 
     #[test]
@@ -2414,9 +3350,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let block = parser.parse_module_no_brace(false).unwrap().block;
+        let block = without_spans(parser.parse_module_no_brace(false).unwrap().block);
 
         assert_eq!(block.definitions.len(), 1);
         assert_eq!(block.statements.len(), 1);
@@ -2429,6 +3365,7 @@ mod tests {
                     mods: Rc::new(Vec::new()),
                 },
                 expr: None,
+                span: Span::default(),
             }))
         );
         assert_eq!(
@@ -2451,6 +3388,7 @@ mod tests {
                                 mods: Rc::new(Vec::new()),
                             },
                             expr: None,
+                            span: Span::default(),
                         })),
                         (Statement::Expression(Box::from(Expression {
                             type_: None,
@@ -2462,6 +3400,7 @@ mod tests {
                                             Parser::<Lexer>::string_to_namepath("a")
                                         )
                                     ),
+                                    span: Span::default(),
                                 }),
                                 BinOp::Add,
                                 Box::from(Expression {
@@ -2469,18 +3408,22 @@ mod tests {
                                     expr: ExpressionEnum::AtomicExpression(
                                         AtomicExpression::Literal(LiteralValue::Int(1))
                                     ),
+                                    span: Span::default(),
                                 }),
                             ),
+                            span: Span::default(),
                         }))),
                         (Statement::Return(Box::from(Expression {
                             type_: None,
                             expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(
                                 LiteralValue::Int(0)
                             )),
+                            span: Span::default(),
                         }))),
                     ],
                 }),
                 mods: Rc::new(Vec::new()),
+                span: Span::default(),
             }))
         );
     }
@@ -2491,9 +3434,9 @@ mod tests {
         let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
             statement.to_string(),
         ))));
-        let mut parser = Parser::new(lexer);
+        let mut parser = Parser::new(lexer).unwrap();
 
-        let module = parser.parse_module_no_brace(false).unwrap();
+        let module = without_spans(parser.parse_module_no_brace(false).unwrap());
 
         assert_eq!(module.uses.len(), 1);
         assert_eq!(
@@ -2518,9 +3461,123 @@ mod tests {
                         },
                     },
                 ],
+                span: Span::default(),
             }
         );
 
         println!("{:?}", module);
     }
+
+    // (line, column, end line, end column)
+    fn position(span: &Span) -> (u32, u32, u32, u32) {
+        (span.line, span.column, span.end_line, span.end_column)
+    }
+
+    #[test]
+    fn spans() {
+        let statement = "fn main() {\n    let a = 1 + b(2);\n    a *= 3;\n}";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let block = parser.parse_module_no_brace(false).unwrap().block;
+
+        let Definition::FnDef(fn_def) = &block.definitions[0] else {
+            panic!("expected a function")
+        };
+        assert_eq!(position(&fn_def.span), (1, 1, 4, 2));
+
+        let Statement::VarDecl(var_decl) = &fn_def.body.statements[0] else {
+            panic!("expected a declaration")
+        };
+        assert_eq!(position(&var_decl.span), (2, 5, 2, 21));
+        let expr = var_decl.expr.as_ref().unwrap();
+        assert_eq!(position(&expr.span), (2, 13, 2, 21));
+        let ExpressionEnum::Binary(_, _, call) = &expr.expr else {
+            panic!("expected an addition")
+        };
+        assert_eq!(position(&call.span), (2, 17, 2, 21));
+
+        let Statement::VarAssign(var_assign) = &fn_def.body.statements[1] else {
+            panic!("expected an assignment")
+        };
+        assert_eq!(position(&var_assign.span), (3, 5, 3, 11));
+    }
+
+    #[test]
+    fn field_paths() {
+        let statement = "a . b += c.d.e;\np.pos.len(1);";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        let block = parser.parse_module_no_brace(false).unwrap().block;
+
+        let Statement::VarAssign(var_assign) = &block.statements[0] else {
+            panic!("expected an assignment")
+        };
+        assert_eq!(var_assign.name_path.name.raw, "a");
+        assert_eq!(var_assign.name_path.path, vec!["b".to_string()]);
+        let ExpressionEnum::Binary(target, BinOp::Add, value) = &var_assign.expr.expr else {
+            panic!("expected an addition")
+        };
+        assert_eq!(position(&target.span), (1, 1, 1, 6));
+        assert_eq!(
+            value.expr,
+            ExpressionEnum::AtomicExpression(AtomicExpression::Variable(NamePath {
+                name: Reference::new("c".to_string()),
+                path: vec!["d".to_string(), "e".to_string()],
+            }))
+        );
+        assert_eq!(position(&value.span), (1, 10, 1, 15));
+
+        let Statement::Expression(call) = &block.statements[1] else {
+            panic!("expected a call")
+        };
+        let ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(fn_call)) = &call.expr else {
+            panic!("expected a call")
+        };
+        assert_eq!(fn_call.name.raw, "len");
+        assert_eq!(
+            fn_call.receiver,
+            Some(NamePath {
+                name: Reference::new("p".to_string()),
+                path: vec!["pos".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn error_span() {
+        let statement = "fn main() {\n    a = 1\n    return a;\n}";
+        let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+            statement.to_string(),
+        ))));
+        let mut parser = Parser::new(lexer).unwrap();
+
+        match parser.parse_module_no_brace(false) {
+            Err(ParseError::Unexpected((Token::Return, span), _)) => {
+                assert_eq!(position(&span), (3, 5, 3, 11))
+            }
+            result => panic!("expected an error at the return, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_semicolon() {
+        for (statement, global) in [
+            ("pub fn main() { let a: int = 1 }", true),
+            ("let a = 1 let b = 2", false),
+            ("const a = 1 a", false),
+        ] {
+            let lexer = Lexer::new(ByteStream::new(Box::from(StringReader::new(
+                statement.to_string(),
+            ))));
+            let mut parser = Parser::new(lexer).unwrap();
+
+            assert!(parser.parse_module_no_brace(global).is_err(), "{statement}");
+        }
+    }
 }
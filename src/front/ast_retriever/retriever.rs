@@ -2,8 +2,10 @@ use crate::front::ast_retriever::name_resolution::resolve_module;
 use crate::front::ast_retriever::reader::lexical::lexer::Lexer;
 use crate::front::ast_retriever::reader::syntax::parser::Parser;
 use crate::front::ast_types::Module;
+use crate::front::diagnostics::Diagnostic;
 use crate::front::file_system::fs::{FileSystem, RelUtf8PathBuf};
 use std::collections::HashMap;
+use std::rc::Rc;
 
 pub type ModuleSource = String;
 #[derive(Debug, PartialEq)]
@@ -20,15 +22,15 @@ pub struct FileRetriever<T> {
 }
 
 impl<T: FileSystem> FileRetriever<T> {
-    pub fn new(file_system: T) -> FileRetriever<T> {
+    pub fn new(file_system: T) -> Result<FileRetriever<T>, Diagnostic> {
         let mut f = FileRetriever {
             file_system,
             root: None,
             modules: Default::default(),
         };
         f.read_nodes();
-        f.parse_files();
-        f
+        f.parse_files()?;
+        Ok(f)
     }
     fn read_nodes_rec(&mut self, parent_module: &mut ModuleNode) {
         let module_file_paths = self.file_system.ls_files_with_extension("ing");
@@ -90,34 +92,51 @@ impl<T: FileSystem> FileRetriever<T> {
         }
     }
 
-    fn parse_files(&mut self) {
+    fn parse_files(&mut self) -> Result<(), Diagnostic> {
         for (mod_path, module_node) in self.modules.iter_mut() {
             let file_source = module_node.file_path.clone();
 
             // TODO: add option to read from cached object file
             if let Ok(byte_stream) = self.file_system.read_file(file_source) {
-                let lexer = Lexer::new(byte_stream);
-                let mut parser = Parser::new(lexer);
-                let mut module = parser.parse_module().unwrap();
+                let lexer =
+                    Lexer::new_with_file(byte_stream, Rc::from(module_node.file_path.as_str()));
+                let mut parser = Parser::new(lexer)?;
+                let mut module = parser.parse_module()?;
 
                 for import in &module.mods {
                     let mut path = mod_path.clone();
                     path.push_str("/");
                     path.push_str(&import.name);
 
-                    if let Some(None) = module_node.submodules.remove(&path) {
-                        module_node.submodules.insert(path, Some(import.public));
-                    } else {
-                        panic!("Submodule not found, or already resolved"); // TODO: error instead of panic
+                    match module_node.submodules.remove(&path) {
+                        Some(None) => {
+                            module_node.submodules.insert(path, Some(import.public));
+                        }
+                        Some(Some(_)) => {
+                            return Err(Diagnostic::error(format!(
+                                "The module `{}` is declared more than once",
+                                import.name
+                            ))
+                            .at(&import.span));
+                        }
+                        None => {
+                            return Err(Diagnostic::error(format!(
+                                "Cannot find the module `{}`",
+                                import.name
+                            ))
+                            .at(&import.span)
+                            .help(format!("add the file {}.ing next to it", import.name)));
+                        }
                     }
                 }
 
-                resolve_module(&mut module);
+                resolve_module(&mut module)?;
                 module_node.module = Some(module);
             } else {
                 panic!("File not found");
             }
         }
+        Ok(())
     }
 }
 
@@ -136,7 +155,7 @@ mod tests {
         mock_file_system.insert_dir(Utf8PathBuf::from("test"));
         mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
 
-        let mut file_retriever = FileRetriever::new(mock_file_system);
+        let mut file_retriever = FileRetriever::new(mock_file_system).unwrap();
         file_retriever.read_nodes();
 
         assert_eq!(file_retriever.modules.len(), 3);
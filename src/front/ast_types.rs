@@ -1,11 +1,49 @@
 pub mod visitor;
 
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::rc::Rc;
 
 pub type RawName = String;
 pub type ResolvedName = String;
 
+/*
+Where a token or a node is in its source file. Lines and columns start at 1 and the end is exclusive. Nodes that are
+not read from a file, such as those built by passes or tests, have the default span with an empty file name.
+ */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Span {
+    pub file: Rc<str>,
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Span {
+    // nodes that were not read from a file have the default span, which is on no line
+    pub fn is_default(&self) -> bool {
+        self.line == 0
+    }
+
+    // from the start of this span to the end of the other one
+    pub fn to(&self, end: &Span) -> Span {
+        Span {
+            file: Rc::clone(&self.file),
+            line: self.line,
+            column: self.column,
+            end_line: end.end_line,
+            end_column: end.end_column,
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Hash, Eq)]
 pub struct GlobalResolvedName {
     pub package: Rc<str>,
@@ -34,10 +72,34 @@ impl Reference {
 pub enum Type {
     Void,
     Int,
+    // held in a score as 0 or 1
+    Bool,
     Float,
     Double,
     String,
     Struct(Reference),
+    // `[int; 16]`, a fixed number of elements held in storage
+    Array(Box<Type>, u32),
+    // `{ hp: int, name: string }`, a compound held in storage with the types of its fields
+    Compound(HashMap<String, Type>),
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Struct(struct_name) => write!(f, "{}", struct_name.raw),
+            Type::Array(element, len) => write!(f, "[{element}; {len}]"),
+            Type::Compound(fields) => {
+                let mut fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, field)| format!("{name}: {field}"))
+                    .collect();
+                fields.sort();
+                write!(f, "{{ {} }}", fields.join(", "))
+            }
+            _ => write!(f, "{}", format!("{self:?}").to_lowercase()),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -50,6 +112,8 @@ pub enum UnOp {
     PreDec,
     PostInc,
     PostDec,
+    // `a as float`, converts between number types
+    Cast(Type),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -84,17 +148,26 @@ pub enum VarMod {
 pub struct VarDecl {
     pub var_def: VarDef,
     pub expr: Option<Box<Expression>>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct VarAssign {
     pub name_path: NamePath,
     pub expr: Box<Expression>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IndexAssign {
+    pub element: Index,
+    pub expr: Box<Expression>,
+    pub span: Span,
 }
 
 pub type Compound = HashMap<String, CompoundValue>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum CompoundValue {
     Expression(Box<Expression>),
     Compound(Box<Compound>),
@@ -114,6 +187,7 @@ pub struct StructDef {
     pub mods: Rc<Vec<StructMod>>,
     pub type_name: Reference,
     pub map: HashMap<String, Type>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
@@ -130,42 +204,70 @@ pub struct FnDef {
     pub name: Reference,
     pub args: Vec<VarDef>,
     pub body: Block,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
+pub struct Impl {
+    pub type_name: Reference,
+    pub methods: Vec<FnDef>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct FnCall {
     pub name: Reference,
+    // `p.len()` calls the method `len` of the type of `p`, which is only known once variables are typed
+    pub receiver: Option<NamePath>,
     pub args: Vec<Expression>,
 }
 
-#[derive(Debug, PartialEq)]
+// `Point { x: 1, y: 2 }`, every field of the struct has to be given
+#[derive(Debug, PartialEq, Clone)]
+pub struct StructLiteral {
+    pub type_name: Reference,
+    pub fields: HashMap<String, Expression>,
+}
+
+// `xs[i]`, an element of an array
+#[derive(Debug, PartialEq, Clone)]
+pub struct Index {
+    pub array: NamePath,
+    pub index: Box<Expression>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum LiteralValue {
     Null,
     Int(i32),
+    Bool(bool),
     Float(f32),
     Double(f64),
     String(String),
     Compound(Compound),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum AtomicExpression {
     Literal(LiteralValue),
     Variable(NamePath),
     FnCall(Box<FnCall>),
+    StructLiteral(StructLiteral),
+    Index(Index),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ExpressionEnum {
     AtomicExpression(AtomicExpression),
     Unary(UnOp, Box<Expression>),
     Binary(Box<Expression>, BinOp, Box<Expression>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Expression {
     pub expr: ExpressionEnum,
     pub type_: Option<Type>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
@@ -179,12 +281,14 @@ pub struct If {
     pub cond: Box<Expression>,
     pub body: Box<Block>,
     pub else_: Option<Else>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct While {
     pub cond: Box<Expression>,
     pub body: Box<Block>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
@@ -193,20 +297,24 @@ pub struct For {
     pub cond: Option<Box<Expression>>,
     pub step: Option<Box<Statement>>,
     pub body: Block,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Statement {
     VarDecl(VarDecl),
     VarAssign(VarAssign),
+    IndexAssign(IndexAssign),
     If(If),
     While(While),
     For(For),
     Return(Box<Expression>),
-    Break,
-    Continue,
+    Break(Span),
+    Continue(Span),
     Expression(Box<Expression>),
     Block(Block),
+    // `println!(a, b)`
+    Print(Vec<Expression>),
 }
 
 #[derive(Debug, PartialEq)]
@@ -214,6 +322,7 @@ pub enum Definition {
     VarDecl(VarDecl),
     StructDef(StructDef),
     FnDef(FnDef),
+    Impl(Impl),
 }
 
 #[derive(Debug, PartialEq)]
@@ -234,6 +343,7 @@ pub struct Block {
 pub struct ModuleImport {
     pub public: bool,
     pub name: String,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq)]
@@ -246,4 +356,5 @@ pub struct UseElement {
 pub struct Use {
     pub path: Vec<String>,
     pub elements: Vec<UseElement>,
+    pub span: Span,
 }
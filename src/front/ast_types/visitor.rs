@@ -1,7 +1,7 @@
 use crate::front::ast_types::{
-    AtomicExpression, Block, Definition, Else, Expression, ExpressionEnum, FnCall, FnDef, For, If,
-    LiteralValue, Module, NamePath, Reference, Statement, StructDef, Type, Use, VarAssign, VarDecl,
-    VarDef, While,
+    AtomicExpression, Block, CompoundValue, Definition, Else, Expression, ExpressionEnum, FnCall,
+    FnDef, For, If, Impl, Index, IndexAssign, LiteralValue, Module, NamePath, Reference, Statement,
+    StructDef, StructLiteral, Type, Use, VarAssign, VarDecl, VarDef, While,
 };
 
 pub enum ASTNodeEnum<'a> {
@@ -10,24 +10,25 @@ pub enum ASTNodeEnum<'a> {
 
     VarDecl(&'a mut VarDecl),
     VarAssign(&'a mut VarAssign),
+    IndexAssign(&'a mut IndexAssign),
     VarDef(&'a mut VarDef),
 
     FnDef(&'a mut FnDef),
     FnCall(&'a mut FnCall),
+    StructLiteral(&'a mut StructLiteral),
+    Index(&'a mut Index),
 
     StructDef(&'a mut StructDef),
+    Impl(&'a mut Impl),
 
-    LiteralValue(&'a mut LiteralValue),
     AtomicExpression(&'a mut AtomicExpression),
     Expression(&'a mut Expression),
 
     If(&'a mut If),
-    Else(&'a mut Else),
     While(&'a mut While),
     For(&'a mut For),
 
     Statement(&'a mut Statement),
-    Definition(&'a mut Definition),
     Block(&'a mut Block),
     Module(&'a mut Module),
 
@@ -50,7 +51,11 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for FnCall {
     fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
         let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::FnCall(self))?;
         if visit_result {
-            self.name.visit(visitor)?;
+            if let Some(receiver) = &mut self.receiver {
+                receiver.visit(visitor)?;
+            } else {
+                self.name.visit(visitor)?;
+            }
             for arg in &mut self.args {
                 arg.visit(visitor)?;
             }
@@ -59,9 +64,52 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for FnCall {
     }
 }
 
+impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for StructLiteral {
+    fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
+        let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::StructLiteral(self))?;
+        if visit_result {
+            self.type_name.visit(visitor)?;
+            for field in self.fields.values_mut() {
+                field.visit(visitor)?;
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Index {
+    fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
+        let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::Index(self))?;
+        if visit_result {
+            self.array.visit(visitor)?;
+            self.index.visit(visitor)?;
+        }
+        Ok(res)
+    }
+}
+
 impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for LiteralValue {
     fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
-        Ok(visitor.apply(&mut ASTNodeEnum::LiteralValue(self))?.1)
+        if let LiteralValue::Compound(compound) = self {
+            for value in compound.values_mut() {
+                value.visit(visitor)?;
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for CompoundValue {
+    fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
+        match self {
+            CompoundValue::Expression(x) => x.visit(visitor),
+            CompoundValue::Compound(x) => {
+                for value in x.values_mut() {
+                    value.visit(visitor)?;
+                }
+                Ok(None)
+            }
+        }
     }
 }
 
@@ -89,6 +137,8 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for AtomicExpression {
                 AtomicExpression::Literal(x) => x.visit(visitor)?,
                 AtomicExpression::FnCall(x) => x.visit(visitor)?,
                 AtomicExpression::Variable(x) => x.visit(visitor)?,
+                AtomicExpression::StructLiteral(x) => x.visit(visitor)?,
+                AtomicExpression::Index(x) => x.visit(visitor)?,
             };
         }
         Ok(res)
@@ -118,18 +168,10 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Expression {
 
 impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Else {
     fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
-        let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::Else(self))?;
-        if visit_result {
-            match self {
-                Else::If(x) => {
-                    x.visit(visitor)?;
-                }
-                Else::Block(x) => {
-                    x.visit(visitor)?;
-                }
-            }
+        match self {
+            Else::If(x) => x.visit(visitor),
+            Else::Block(x) => x.visit(visitor),
         }
-        Ok(res)
     }
 }
 
@@ -201,6 +243,17 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for VarAssign {
     }
 }
 
+impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for IndexAssign {
+    fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
+        let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::IndexAssign(self))?;
+        if visit_result {
+            self.expr.visit(visitor)?;
+            self.element.visit(visitor)?;
+        }
+        Ok(res)
+    }
+}
+
 impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for VarDecl {
     fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
         let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::VarDecl(self))?;
@@ -225,6 +278,9 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Statement {
                 Statement::VarAssign(x) => {
                     x.visit(visitor)?;
                 }
+                Statement::IndexAssign(x) => {
+                    x.visit(visitor)?;
+                }
                 Statement::If(x) => {
                     x.visit(visitor)?;
                 }
@@ -243,7 +299,12 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Statement {
                 Statement::Block(x) => {
                     x.visit(visitor)?;
                 }
-                Statement::Continue | Statement::Break => {}
+                Statement::Print(x) => {
+                    for arg in x {
+                        arg.visit(visitor)?;
+                    }
+                }
+                Statement::Continue(_) | Statement::Break(_) => {}
             };
         }
         Ok(res)
@@ -282,20 +343,29 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for StructDef {
     }
 }
 
-impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Definition {
+impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Impl {
     fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
-        let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::Definition(self))?;
+        let (visit_result, res) = visitor.apply(&mut ASTNodeEnum::Impl(self))?;
         if visit_result {
-            match self {
-                Definition::VarDecl(x) => x.visit(visitor)?,
-                Definition::StructDef(x) => x.visit(visitor)?,
-                Definition::FnDef(x) => x.visit(visitor)?,
-            };
+            for method in &mut self.methods {
+                method.visit(visitor)?;
+            }
         }
         Ok(res)
     }
 }
 
+impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Definition {
+    fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
+        match self {
+            Definition::VarDecl(x) => x.visit(visitor),
+            Definition::StructDef(x) => x.visit(visitor),
+            Definition::FnDef(x) => x.visit(visitor),
+            Definition::Impl(x) => x.visit(visitor),
+        }
+    }
+}
+
 impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Use {
     fn visit(&mut self, visitor: &mut T) -> Result<Option<K>, V> {
         Ok(visitor.apply(&mut ASTNodeEnum::Use(self))?.1)
@@ -318,3 +388,39 @@ impl<T: Visitor<K, V>, K, V> Visitable<T, K, V> for Module {
         Ok(res)
     }
 }
+
+// resets every span, so that tests can compare nodes by what they mean rather than where they were written
+#[cfg(test)]
+pub struct ClearSpans;
+
+#[cfg(test)]
+impl Visitor<(), std::convert::Infallible> for ClearSpans {
+    fn apply(
+        &mut self,
+        ast_node: &mut ASTNodeEnum,
+    ) -> GenericResolveResult<(), std::convert::Infallible> {
+        let span = match ast_node {
+            ASTNodeEnum::VarDecl(x) => &mut x.span,
+            ASTNodeEnum::VarAssign(x) => &mut x.span,
+            ASTNodeEnum::IndexAssign(x) => &mut x.span,
+            ASTNodeEnum::FnDef(x) => &mut x.span,
+            ASTNodeEnum::StructDef(x) => &mut x.span,
+            ASTNodeEnum::Impl(x) => &mut x.span,
+            ASTNodeEnum::Expression(x) => &mut x.span,
+            ASTNodeEnum::If(x) => &mut x.span,
+            ASTNodeEnum::While(x) => &mut x.span,
+            ASTNodeEnum::For(x) => &mut x.span,
+            ASTNodeEnum::Use(x) => &mut x.span,
+            ASTNodeEnum::Statement(Statement::Break(x) | Statement::Continue(x)) => x,
+            _ => return Ok((true, None)),
+        };
+        *span = Default::default();
+        Ok((true, None))
+    }
+}
+
+#[cfg(test)]
+pub fn without_spans<T: Visitable<ClearSpans, (), std::convert::Infallible>>(mut node: T) -> T {
+    node.visit(&mut ClearSpans).unwrap();
+    node
+}
@@ -0,0 +1,255 @@
+use crate::front::ast_types::Span;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/*
+An error or a warning about a program, rendered with the line of source it points at:
+
+error: Expected `;`, found `return`
+ --> main.ing:3:5
+  |
+3 |     return a;
+  |     ^^^^^^
+ */
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Option<Span>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span: None,
+            notes: vec![],
+            help: None,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(message)
+        }
+    }
+
+    // nodes that were not read from a file are not pointed at
+    pub fn at(mut self, span: &Span) -> Diagnostic {
+        if !span.is_default() {
+            self.span = Some(span.clone());
+        }
+        self
+    }
+
+    pub fn note(mut self, note: impl Into<String>) -> Diagnostic {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn help(mut self, help: impl Into<String>) -> Diagnostic {
+        self.help = Some(help.into());
+        self
+    }
+
+    // the source is the text of the file the span is in, without it only the location is shown
+    pub fn render(&self, source: Option<&str>) -> String {
+        let mut lines = vec![format!("{}: {}", self.severity, self.message)];
+
+        let mut gutter = String::new();
+        if let Some(span) = &self.span {
+            let line_number = span.line.to_string();
+            gutter = " ".repeat(line_number.len());
+            lines.push(format!("{gutter}--> {span}"));
+
+            if let Some(line) = source.and_then(|source| source.lines().nth(span.line as usize - 1))
+            {
+                // a span over several lines is underlined to the end of its first line
+                let end = if span.end_line == span.line {
+                    span.end_column
+                } else {
+                    line.chars().count() as u32 + 1
+                };
+                // tabs are kept so the carets line up with the source however wide a tab is shown
+                let indent: String = line
+                    .chars()
+                    .take(span.column as usize - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let carets = "^".repeat(end.saturating_sub(span.column).max(1) as usize);
+
+                lines.push(format!("{gutter} |"));
+                lines.push(format!("{line_number} | {line}"));
+                lines.push(format!("{gutter} | {indent}{carets}"));
+            }
+        }
+
+        for note in &self.notes {
+            lines.push(format!("{gutter} = note: {note}"));
+        }
+        if let Some(help) = &self.help {
+            lines.push(format!("{gutter} = help: {help}"));
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::front::ast_types::Span;
+    use crate::front::diagnostics::Diagnostic;
+    use crate::front::file_system::fs::FileSystem;
+    use crate::front::file_system::mock_fs::MockFileSystem;
+    use crate::front::mergers::program::ProgramMerger;
+    use camino::Utf8PathBuf;
+    use std::rc::Rc;
+
+    fn span(line: u32, column: u32, end_line: u32, end_column: u32) -> Span {
+        Span {
+            file: Rc::from("main.ing"),
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "fn main() {\n\tlet a = 1;\n\treturn a + b;\n}";
+
+        let diagnostic = Diagnostic::error("Cannot find `b` in this scope")
+            .at(&span(3, 13, 3, 14))
+            .note("variables have to be declared before they are used")
+            .help("declare it with `let b = ...;`");
+        assert_eq!(
+            diagnostic.render(Some(source)),
+            "error: Cannot find `b` in this scope\n \
+             --> main.ing:3:13\n  \
+               |\n\
+             3 | \treturn a + b;\n  \
+               | \t           ^\n  \
+               = note: variables have to be declared before they are used\n  \
+               = help: declare it with `let b = ...;`"
+        );
+
+        // only the first line of a longer span is underlined
+        let diagnostic = Diagnostic::warning("Function main is empty").at(&span(1, 1, 4, 2));
+        assert_eq!(
+            diagnostic.render(Some(source)),
+            "warning: Function main is empty\n \
+             --> main.ing:1:1\n  \
+               |\n\
+             1 | fn main() {\n  \
+               | ^^^^^^^^^^^"
+        );
+
+        // without its source only the location is shown
+        assert_eq!(
+            diagnostic.render(None),
+            "warning: Function main is empty\n --> main.ing:1:1"
+        );
+
+        // nodes that are not from a file are not pointed at
+        let diagnostic = Diagnostic::error("Null access detected")
+            .at(&Span::default())
+            .note("a is used before it is assigned");
+        assert_eq!(diagnostic.span, None);
+        assert_eq!(
+            diagnostic.render(Some(source)),
+            "error: Null access detected\n = note: a is used before it is assigned"
+        );
+    }
+
+    fn read_error(source: &str) -> Diagnostic {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), source);
+
+        let mut program_merger = ProgramMerger::new("pkg");
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .err()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_front_errors() {
+        let source = "fn main() {\n    let a = 1;\n    a = 2\n    return a;\n}";
+        assert_eq!(
+            read_error(source).render(Some(source)),
+            "error: Expected `;`, found `return`\n \
+             --> main.ing:4:5\n  \
+               |\n\
+             4 |     return a;\n  \
+               |     ^^^^^^"
+        );
+
+        let source = "fn main() {\n    let a = 1;\n    a = a + b;\n}";
+        assert_eq!(
+            read_error(source).render(Some(source)),
+            "error: Cannot find `b` in this scope\n \
+             --> main.ing:3:13\n  \
+               |\n\
+             3 |     a = a + b;\n  \
+               |             ^"
+        );
+
+        let source = "fn main() {\n    let a = 1;\n    let b = 1.5 ^ 2;\n}";
+        assert_eq!(
+            read_error(source).render(Some(source)),
+            "error: Invalid token `^`\n \
+             --> main.ing:3:17\n  \
+               |\n\
+             3 |     let b = 1.5 ^ 2;\n  \
+               |                 ^"
+        );
+
+        let source = "mod foo;\nfn main() {}";
+        assert_eq!(
+            read_error(source).render(Some(source)),
+            "error: Cannot find the module `foo`\n \
+             --> main.ing:1:1\n  \
+               |\n\
+             1 | mod foo;\n  \
+               | ^^^^^^^^\n  \
+               = help: add the file foo.ing next to it"
+        );
+
+        let source = "mod foo;\npub mod foo;\nfn main() {}";
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), source);
+        mock_file_system.insert_file(Utf8PathBuf::from("foo.ing"), "");
+        let mut program_merger = ProgramMerger::new("pkg");
+        let error = program_merger
+            .read_package("pkg", mock_file_system)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.render(Some(source)),
+            "error: The module `foo` is declared more than once\n \
+             --> main.ing:2:1\n  \
+               |\n\
+             2 | pub mod foo;\n  \
+               | ^^^^^^^^^^^^"
+        );
+    }
+}
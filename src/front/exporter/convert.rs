@@ -1,16 +1,20 @@
 pub mod context;
+mod frames;
 
 use crate::front::ast_types::{
-    AtomicExpression, BinOp, Block, Else, Expression, ExpressionEnum, FnCall, FnDef, For,
-    GlobalResolvedName, If, LiteralValue, Reference, Statement, UnOp, VarAssign, VarDecl, While,
+    AtomicExpression, BinOp, Block, Compound, CompoundValue, Else, Expression, ExpressionEnum,
+    FnCall, FnDef, For, GlobalResolvedName, If, Index, IndexAssign, LiteralValue, Reference, Span,
+    Statement, StructLiteral, Type, UnOp, VarAssign, VarDecl, While,
 };
-use crate::front::exporter::convert::context::Context;
+use crate::front::exporter::convert::context::{Context, ControlFlow};
 use crate::front::mergers::definition_table::DefinitionTable;
 use crate::middle::format::ir_types::{
-    Address, CheckVal, CompareOp, CompareVal, Cond, IrBlock, IrFnCall, IrFnDef, IrIf,
-    IrScoreOperation, IrScoreOperationType, IrStatement,
+    Address, AddressKind, AddressOrigin, ArrayIndex, CheckVal, CompareOp, CompareVal, Cond,
+    IrArrayElement, IrBlock, IrCompoundField, IrFnCall, IrFnDef, IrIf, IrScoreOperation,
+    IrScoreOperationType, IrStatement,
 };
-use crate::middle::format::types::GlobalName;
+use crate::middle::format::types::{GlobalName, LOAD_FN_NAME};
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub fn global_name_updater(global_resolved_name: &Rc<GlobalResolvedName>) -> GlobalName {
@@ -40,24 +44,97 @@ pub fn global_name_updater(global_resolved_name: &Rc<GlobalResolvedName>) -> Glo
 //
 fn convert_fn_call(context: &mut Context, ast_node: &FnCall) -> Vec<IrStatement> {
     let mut s: Vec<IrStatement> = vec![];
-    for (i, arg) in ast_node.args.iter().enumerate() {
-        s.append(&mut convert_expr(
-            context,
-            arg,
-            &context
-                .get_parameter_variable(&ast_node.name.global_resolved.as_ref().unwrap(), i as u32),
+
+    // a method gets its receiver as the first argument, self
+    let receiver = ast_node.receiver.as_ref().map(|receiver| Expression {
+        expr: ExpressionEnum::AtomicExpression(AtomicExpression::Variable(receiver.clone())),
+        type_: None,
+        span: Span::default(),
+    });
+    let fn_name = match &ast_node.receiver {
+        Some(receiver) => context.get_method_name(receiver, &ast_node.name.raw),
+        None => Rc::clone(ast_node.name.global_resolved.as_ref().unwrap()),
+    };
+    let args: Vec<&Expression> = receiver.iter().chain(ast_node.args.iter()).collect();
+
+    // the frame is pushed before the arguments are set, since they may be the parameters of this function
+    let frame = if context.saves_frame(&fn_name) {
+        let frame = context.get_frame();
+        s.push(IrStatement::PushFrame(frame.clone()));
+        Some(frame)
+    } else {
+        None
+    };
+
+    let parameters: Vec<(Address, Type)> = (0..args.len() as u32)
+        .map(|i| {
+            (
+                context.get_parameter_variable(&fn_name, i),
+                context.get_parameter_type(&fn_name, i),
+            )
+        })
+        .collect();
+
+    /*
+    An argument is evaluated into a temporary when a later argument could see or change its parameter, e.g.
+    `f(1, f(2, 3))` or `f(b, a)` inside of `f`. The parameters are only assigned once every argument is evaluated.
+     */
+    let mut temporaries = vec![];
+    for (i, arg) in args.iter().enumerate() {
+        let (parameter, type_) = &parameters[i];
+        let clobbered = args[i + 1..].iter().any(|later| {
+            (0..context.get_type_size(type_))
+                .any(|offset| reads_address(context, later, &parameter.with_offset(offset)))
+        });
+
+        if clobbered {
+            let temporary = context.get_variable_of_type(type_);
+            s.append(&mut convert_assignment(context, arg, &temporary, type_));
+            temporaries.push((parameter, type_, temporary));
+        } else {
+            s.append(&mut convert_assignment(context, arg, parameter, type_));
+        }
+    }
+    for (parameter, type_, temporary) in temporaries {
+        s.append(&mut copy_slots(
+            &temporary,
+            parameter,
+            context.get_type_size(type_),
         ));
+        context.forfeit_variable(&temporary);
     }
+
     s.push(IrStatement::FnCall(IrFnCall {
-        fn_name: convert_reference(&ast_node.name),
+        fn_name: global_name_updater(&fn_name),
     }));
+
+    // a method can assign the fields of self, which are copied back to its receiver
+    let Some(receiver) = &ast_node.receiver else {
+        if let Some(frame) = frame {
+            s.push(IrStatement::PopFrame(frame));
+        }
+        return s;
+    };
+    let (self_, type_) = &parameters[0];
+    let size = context.get_type_size(type_);
+    let receiver = context.convert_name_path(receiver);
+    if let Some(frame) = frame {
+        // self may be restored with the frame, so it is kept in a temporary until the frame is popped
+        let temporary = context.get_variable_of_type(type_);
+        s.append(&mut copy_slots(self_, &temporary, size));
+        s.push(IrStatement::PopFrame(frame));
+        s.append(&mut copy_slots(&temporary, &receiver, size));
+        context.forfeit_variable(&temporary);
+    } else {
+        s.append(&mut copy_slots(self_, &receiver, size));
+    }
     s
 }
 
 fn set_from_atomic(
     context: &mut Context,
     ast_node: &AtomicExpression,
-    _result_var_name: &Address,
+    result_var_name: &Address,
 ) -> ExprEval {
     match ast_node {
         AtomicExpression::Literal(x) => {
@@ -70,12 +147,38 @@ fn set_from_atomic(
                     statements: vec![],
                     existing_address: Some(context.const_generator.get_const(x.clone())),
                 },
-                // LiteralValue::Decimal(_) => {}
+                LiteralValue::Bool(x) => ExprEval {
+                    statements: vec![],
+                    existing_address: Some(context.const_generator.get_const(*x as i32)),
+                },
+                LiteralValue::Float(x) => ExprEval {
+                    statements: vec![],
+                    existing_address: Some(
+                        context
+                            .const_generator
+                            .get_const(to_fixed_point(*x as f64, context.float_scale)),
+                    ),
+                },
+                LiteralValue::Double(x) => ExprEval {
+                    statements: vec![],
+                    existing_address: Some(
+                        context
+                            .const_generator
+                            .get_const(to_fixed_point(*x, context.float_scale)),
+                    ),
+                },
                 // LiteralValue::String(_) => {}
                 // LiteralValue::Compound(_) => {}
                 _ => panic!("Not implemented"), // TODO: implement storage types
             }
         }
+        AtomicExpression::Variable(x) if context.convert_compound_field(x).is_some() => ExprEval {
+            statements: vec![IrStatement::FieldGet(
+                context.convert_compound_field(x).unwrap(),
+                result_var_name.clone(),
+            )],
+            existing_address: None,
+        },
         AtomicExpression::Variable(x) => {
             // TODO: this only works with score types for now
             ExprEval {
@@ -83,6 +186,26 @@ fn set_from_atomic(
                 existing_address: Some(context.convert_name_path(x)),
             }
         }
+        AtomicExpression::FnCall(x) if array_len(context, x).is_some() => ExprEval {
+            statements: vec![],
+            existing_address: Some(
+                context
+                    .const_generator
+                    .get_const(array_len(context, x).unwrap() as i32),
+            ),
+        },
+        AtomicExpression::Index(x) => {
+            let (mut s, element, temporary) = convert_element(context, x);
+            s.push(IrStatement::ArrayGet(element, result_var_name.clone()));
+            if let Some(temporary) = temporary {
+                context.forfeit_variable(&temporary);
+            }
+
+            ExprEval {
+                statements: s,
+                existing_address: None,
+            }
+        }
         AtomicExpression::FnCall(x) => {
             let mut s = vec![];
             s.append(&mut convert_fn_call(context, x));
@@ -92,9 +215,151 @@ fn set_from_atomic(
                 existing_address: Some(context.get_return_variable()),
             }
         }
+        AtomicExpression::StructLiteral(_) => {
+            unreachable!("CheckTypes only lets a struct be assigned as a whole")
+        }
+    }
+}
+
+// whether evaluating the expression may read (or, through a call, change) the address
+fn reads_address(context: &Context, ast_node: &Expression, address: &Address) -> bool {
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Compound(x))) => {
+            compound_expressions(x)
+                .iter()
+                .any(|(_, field)| reads_address(context, field, address))
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(_)) => false,
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => {
+            // a whole struct is read, so every one of its fields is
+            let start = context.convert_name_path(x);
+            let size = context.get_type_size(&context.get_name_path_type(x));
+            start.name == address.name
+                && (start.offset..start.offset + size).contains(&address.offset)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Index(x)) => {
+            reads_address(context, &x.index, address)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(x)) => x
+            .fields
+            .values()
+            .any(|field| reads_address(context, field, address)),
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x)) => {
+            // a called function can not see the temporaries of this one
+            !matches!(address.name, AddressOrigin::CtxGenerated(_, _))
+                || x.args
+                    .iter()
+                    .any(|arg| reads_address(context, arg, address))
+        }
+        ExpressionEnum::Unary(_, e) => reads_address(context, e, address),
+        ExpressionEnum::Binary(e0, _, e1) => {
+            reads_address(context, e0, address) || reads_address(context, e1, address)
+        }
     }
 }
 
+// the fields of compounds are not scores, so they are incremented through convert_field_increment
+fn get_increment_address(context: &Context, ast_node: &Expression) -> Address {
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x))
+            if context.convert_compound_field(x).is_none() =>
+        {
+            context.convert_name_path(x)
+        }
+        _ => panic!("Only variables can be incremented or decremented"),
+    }
+}
+
+fn get_increment_field(context: &Context, ast_node: &Expression) -> Option<IrCompoundField> {
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => {
+            context.convert_compound_field(x)
+        }
+        _ => None,
+    }
+}
+
+// `c.hp++` is `c.hp += 1`, the field is read into the score, updated and stored back
+fn convert_field_increment(
+    context: &mut Context,
+    field: IrCompoundField,
+    address: &Address,
+    unop: &UnOp,
+    type_: &Type,
+) -> Vec<IrStatement> {
+    vec![
+        IrStatement::FieldGet(field.clone(), address.clone()),
+        convert_increment(context, address, unop, type_),
+        IrStatement::FieldSet(field, address.clone()),
+    ]
+}
+
+fn convert_increment(
+    context: &mut Context,
+    address: &Address,
+    unop: &UnOp,
+    type_: &Type,
+) -> IrStatement {
+    // a float is incremented by 1.0, which is the scale
+    let step = if is_fixed_point(type_) {
+        context.float_scale
+    } else {
+        1
+    };
+    IrStatement::ScoreOperation(IrScoreOperation {
+        left: address.clone(),
+        op: match unop {
+            UnOp::PreInc | UnOp::PostInc => IrScoreOperationType::Add,
+            UnOp::PreDec | UnOp::PostDec => IrScoreOperationType::Sub,
+            _ => panic!("Impossible, match arms must be incorrect"),
+        },
+        right: context.const_generator.get_const(step),
+    })
+}
+
+fn is_fixed_point(type_: &Type) -> bool {
+    matches!(type_, Type::Float | Type::Double)
+}
+
+fn to_fixed_point(value: f64, scale: i32) -> i32 {
+    (value * scale as f64).round() as i32
+}
+
+/*
+Converts the value in the address from one number type to another. Floats and doubles share a scale, so
+only conversions between them and ints change the value. Scores are divided rounding down, so a float is
+converted to the int below it.
+ */
+fn convert_cast(
+    context: &mut Context,
+    address: &Address,
+    from: &Type,
+    to: &Type,
+) -> Vec<IrStatement> {
+    let op = match (from, to) {
+        // every int that is not 0 is true
+        (Type::Int, Type::Bool) => {
+            return vec![IrStatement::ScoreOperation(IrScoreOperation {
+                left: address.clone(),
+                op: IrScoreOperationType::Neq,
+                right: context.const_generator.get_const(0),
+            })];
+        }
+        (Type::Bool, Type::Int | Type::Bool) => return vec![],
+        (Type::Int, Type::Float | Type::Double) => IrScoreOperationType::Mul,
+        (Type::Float | Type::Double, Type::Int) => IrScoreOperationType::Div,
+        (Type::Int | Type::Float | Type::Double, Type::Int | Type::Float | Type::Double) => {
+            return vec![];
+        }
+        _ => unreachable!("CheckTypes only lets numbers and bools be converted"),
+    };
+    vec![IrStatement::ScoreOperation(IrScoreOperation {
+        left: address.clone(),
+        op,
+        right: context.const_generator.get_const(context.float_scale),
+    })]
+}
+
 struct ExprEval {
     statements: Vec<IrStatement>,
     existing_address: Option<Address>,
@@ -107,6 +372,64 @@ fn rec_convert_expr(
 ) -> ExprEval {
     return match &ast_node.expr {
         ExpressionEnum::AtomicExpression(x) => set_from_atomic(context, x, result_var_name),
+        ExpressionEnum::Unary(unop @ (UnOp::PreInc | UnOp::PreDec), e) => {
+            let type_ = infer_type(context, e);
+            if let Some(field) = get_increment_field(context, e) {
+                return ExprEval {
+                    statements: convert_field_increment(
+                        context,
+                        field,
+                        result_var_name,
+                        unop,
+                        &type_,
+                    ),
+                    existing_address: None,
+                };
+            }
+
+            // the variable itself holds the value after the update
+            let address = get_increment_address(context, e);
+            ExprEval {
+                statements: vec![convert_increment(context, &address, unop, &type_)],
+                existing_address: Some(address),
+            }
+        }
+        ExpressionEnum::Unary(unop @ (UnOp::PostInc | UnOp::PostDec), e) => {
+            // the value before the update is kept in the result, which is nothing to do if the
+            // result is the variable itself (`a = a++`)
+            let type_ = infer_type(context, e);
+            if let Some(field) = get_increment_field(context, e) {
+                let temporary = context.get_variable();
+                let mut s = vec![IrStatement::FieldGet(
+                    field.clone(),
+                    result_var_name.clone(),
+                )];
+                s.append(&mut convert_field_increment(
+                    context, field, &temporary, unop, &type_,
+                ));
+                context.forfeit_variable(&temporary);
+                return ExprEval {
+                    statements: s,
+                    existing_address: None,
+                };
+            }
+
+            let address = get_increment_address(context, e);
+            let mut s = vec![];
+            if &address != result_var_name {
+                s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                    left: result_var_name.clone(),
+                    op: IrScoreOperationType::Assign,
+                    right: address.clone(),
+                }));
+                s.push(convert_increment(context, &address, unop, &type_));
+            }
+
+            ExprEval {
+                statements: s,
+                existing_address: None,
+            }
+        }
         ExpressionEnum::Unary(unop, e) => {
             let mut s = vec![];
 
@@ -126,19 +449,17 @@ fn rec_convert_expr(
                     op: IrScoreOperationType::Mul,
                     right: context.const_generator.get_const(-1),
                 })),
-                UnOp::Not => {
-                    s.push(IrStatement::ScoreOperation(IrScoreOperation {
-                        left: result_var_name.clone(),
-                        op: IrScoreOperationType::Eq,
-                        right: context.const_generator.get_const(0),
-                    })) // TODO can use match instead
+                UnOp::Not => s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                    left: result_var_name.clone(),
+                    op: IrScoreOperationType::Eq,
+                    right: context.const_generator.get_const(0),
+                })),
+                UnOp::Cast(type_) => {
+                    let from = infer_type(context, e);
+                    s.append(&mut convert_cast(context, result_var_name, &from, type_));
                 }
                 // UnOp::Deref => IrScoreOperationType::Deref, // TODO
                 // UnOp::Ref => IrScoreOperationType::Ref,
-                // UnOp::PreInc => {},
-                // UnOp::PreDec => IrScoreOperationType::PreDec,
-                // UnOp::PostInc => IrScoreOperationType::PostInc,
-                // UnOp::PostDec => IrScoreOperationType::PostDec,
                 _ => {}
             };
 
@@ -147,11 +468,53 @@ fn rec_convert_expr(
                 existing_address: None,
             }
         }
+        ExpressionEnum::Binary(_, BinOp::And | BinOp::Or, _) => {
+            // evaluated as a condition, so the right side only runs when it decides the result
+            let temporary = if reads_address(context, ast_node, result_var_name) {
+                Some(context.get_variable())
+            } else {
+                None
+            };
+            let target = temporary.as_ref().unwrap_or(result_var_name).clone();
+
+            let (mut s, cond, invert) = convert_expr_for_comparison(context, ast_node, false);
+            s.push(set_flag(context, &target, 0));
+            s.push(IrStatement::If(IrIf {
+                invert,
+                cond,
+                body: Box::from(set_flag(context, &target, 1)),
+            }));
+
+            if let Some(temporary) = temporary {
+                s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                    left: result_var_name.clone(),
+                    op: IrScoreOperationType::Assign,
+                    right: temporary.clone(),
+                }));
+                context.forfeit_variable(&temporary);
+            }
+
+            ExprEval {
+                statements: s,
+                existing_address: None,
+            }
+        }
         ExpressionEnum::Binary(e0, binop, e1) => {
             let mut s = vec![];
 
+            // the left side is computed into the result first, so if the right side reads the result
+            // (`x = 1 - x`) the computation has to happen in a temporary instead
+            let result_is_e0 = matches!(&e0.expr, ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) if &context.convert_name_path(x) == result_var_name);
+            let use_temporary = !result_is_e0 && reads_address(context, e1, result_var_name);
+            let final_var_name = result_var_name;
+            let temporary = if use_temporary {
+                Some(context.get_variable())
+            } else {
+                None
+            };
+            let result_var_name = temporary.as_ref().unwrap_or(final_var_name);
+
             let mut expr0 = rec_convert_expr(context, e0, result_var_name);
-            expr0.existing_address.as_ref().unwrap_or(result_var_name);
             s.append(&mut expr0.statements);
             if let Some(e_a) = expr0.existing_address {
                 if result_var_name != &e_a {
@@ -163,6 +526,19 @@ fn rec_convert_expr(
                 }
             }
 
+            let type_0 = operand_type(context, e0, e1);
+
+            // the product of two fixed-point numbers has the scale twice, so it is divided by it once, and
+            // the dividend is multiplied by it before a division so the quotient keeps it
+            let fixed_point = is_fixed_point(&type_0);
+            if fixed_point && binop == &BinOp::Div {
+                s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                    left: result_var_name.clone(),
+                    op: IrScoreOperationType::Mul,
+                    right: context.const_generator.get_const(context.float_scale),
+                }));
+            }
+
             let a0 = context.get_variable();
             let mut expr1 = rec_convert_expr(context, e1, &a0);
             let mut f = false;
@@ -191,15 +567,31 @@ fn rec_convert_expr(
                         BinOp::Leq => IrScoreOperationType::Leq,
                         BinOp::Geq => IrScoreOperationType::Geq,
 
-                        BinOp::And => IrScoreOperationType::And,
-                        BinOp::Or => IrScoreOperationType::Or,
+                        BinOp::And | BinOp::Or => {
+                            panic!("Impossible, match arms must be incorrect")
+                        }
                     }
                 },
                 right: existing_address1,
             }));
+            if fixed_point && binop == &BinOp::Mul {
+                s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                    left: result_var_name.clone(),
+                    op: IrScoreOperationType::Div,
+                    right: context.const_generator.get_const(context.float_scale),
+                }));
+            }
             if f {
                 context.forfeit_variable(&a0);
             }
+            if let Some(temporary) = temporary {
+                s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                    left: final_var_name.clone(),
+                    op: IrScoreOperationType::Assign,
+                    right: temporary.clone(),
+                }));
+                context.forfeit_variable(&temporary);
+            }
 
             ExprEval {
                 statements: s,
@@ -228,9 +620,15 @@ fn convert_expr(
     s
 }
 
+/*
+Converts an expression to a condition, which holds when the expression is not 0 (or is 0 if `negate` is set).
+The returned bool inverts the condition. A returned `Cond::And` is never inverted, so it can always be
+checked as a chain of `execute if ... if ...`.
+ */
 fn convert_expr_for_comparison(
     context: &mut Context,
     ast_node: &Expression,
+    negate: bool,
 ) -> (Vec<IrStatement>, Cond, bool) {
     match &ast_node.expr {
         ExpressionEnum::AtomicExpression(x) => {
@@ -243,39 +641,36 @@ fn convert_expr_for_comparison(
                 s,
                 Cond::CheckVal(CheckVal {
                     var_name: address,
-                    min: 0,
-                    max: 0,
+                    min: 1,
+                    max: 1,
                 }),
-                false,
+                negate,
             );
         }
-        ExpressionEnum::Unary(unop, x) => {
-            if matches!(unop, UnOp::Not) {
-                let a0 = context.get_variable();
-                let expr = rec_convert_expr(context, x, &a0);
-                let s = expr.statements;
-                let address = expr.existing_address.unwrap_or(a0.clone());
-                context.forfeit_variable(&a0);
-
-                return (
-                    s,
-                    Cond::CheckVal(CheckVal {
-                        var_name: address,
-                        min: 0,
-                        max: 0,
-                    }),
-                    true,
-                );
-            }
+        ExpressionEnum::Unary(UnOp::Not, x) => {
+            return convert_expr_for_comparison(context, x, !negate);
         }
         ExpressionEnum::Binary(e0, binop, e1) => match binop {
             BinOp::Eq | BinOp::Neq | BinOp::Lt | BinOp::Gt | BinOp::Leq | BinOp::Geq => {
+                operand_type(context, e0, e1);
                 let a0 = context.get_variable();
-                let expr0 = rec_convert_expr(context, e0, &a0);
+                let mut expr0 = rec_convert_expr(context, e0, &a0);
+                let mut s = expr0.statements;
+
+                // the right side could change the value the left side points to (`f(1) == f(2)`)
+                if let Some(e_a) = &expr0.existing_address {
+                    if reads_address(context, e1, e_a) {
+                        s.push(IrStatement::ScoreOperation(IrScoreOperation {
+                            left: a0.clone(),
+                            op: IrScoreOperationType::Assign,
+                            right: e_a.clone(),
+                        }));
+                        expr0.existing_address = None;
+                    }
+                }
+
                 let a1 = context.get_variable();
                 let mut expr1 = rec_convert_expr(context, e1, &a1);
-
-                let mut s = expr0.statements;
                 s.append(&mut expr1.statements);
 
                 let address0 = expr0.existing_address.unwrap_or(a0.clone());
@@ -300,100 +695,818 @@ fn convert_expr_for_comparison(
                             var_1: address1,
                         }
                     }),
-                    false,
+                    negate,
                 );
             }
+            BinOp::And | BinOp::Or => {
+                // a negated && is an || of the negated operands, and the other way around
+                return if matches!(binop, BinOp::And) != negate {
+                    convert_conjunction(context, e0, e1, negate)
+                } else {
+                    convert_disjunction(context, e0, e1, negate)
+                };
+            }
             _ => {}
         },
+        _ => {}
     }
 
     let a0 = context.get_variable();
     let expr = convert_expr(context, ast_node, &a0);
+    context.forfeit_variable(&a0);
 
     (
         expr,
         Cond::CheckVal(CheckVal {
             var_name: a0,
-            min: 0,
-            max: 0,
+            min: 1,
+            max: 1,
         }),
-        false,
+        negate,
     )
 }
 
-fn convert_var_decl(context: &mut Context, ast_node: &VarDecl) -> Vec<IrStatement> {
-    if let Some(expr) = &ast_node.expr {
-        convert_expr(
-            context,
-            expr,
-            &context.convert_var_name(&ast_node.var_def.name),
-        )
-    } else {
-        vec![]
+// an int is not a condition, it has to be compared to get a bool
+fn cond_term(cond: Cond, invert: bool) -> Vec<Cond> {
+    match cond {
+        Cond::And(conds) => conds,
+        cond if invert => vec![Cond::Not(Box::from(cond))],
+        cond => vec![cond],
     }
 }
 
-fn convert_var_assign(context: &mut Context, ast_node: &VarAssign) -> Vec<IrStatement> {
-    convert_expr(
-        context,
-        &ast_node.expr,
-        &context.convert_name_path(&ast_node.name_path),
-    )
+fn set_flag(context: &mut Context, flag: &Address, value: i32) -> IrStatement {
+    IrStatement::ScoreOperation(IrScoreOperation {
+        left: flag.clone(),
+        op: IrScoreOperationType::Assign,
+        right: context.const_generator.get_const(value),
+    })
 }
 
-fn convert_condition(
+fn convert_conjunction(
     context: &mut Context,
-    cond: &Box<Expression>,
-    invert_cond: bool,
-    body: IrStatement,
-) -> Vec<IrStatement> {
-    let mut condition = vec![];
-    // if condition is 0, return
-    let (mut expr_statements, cond, invert) = convert_expr_for_comparison(context, cond);
-    condition.append(&mut expr_statements);
-    condition.push(IrStatement::If(IrIf {
-        invert: invert != invert_cond,
-        cond,
-        body: Box::from(body),
+    e0: &Expression,
+    e1: &Expression,
+    negate: bool,
+) -> (Vec<IrStatement>, Cond, bool) {
+    // allocated first, so it is not one of the temporaries the operands are checked on
+    let flag = context.get_variable();
+
+    let (mut s0, c0, i0) = convert_expr_for_comparison(context, e0, negate);
+    let (mut s1, c1, i1) = convert_expr_for_comparison(context, e1, negate);
+
+    if s1.is_empty() {
+        // nothing has to run between the checks: execute if <e0> if <e1>
+        context.forfeit_variable(&flag);
+
+        let mut conds = cond_term(c0, i0);
+        conds.append(&mut cond_term(c1, i1));
+        return (s0, Cond::And(conds), false);
+    }
+
+    // the right side is only evaluated if the left side holds
+    let mut s = vec![set_flag(context, &flag, 0)];
+    s.append(&mut s0);
+    s1.push(IrStatement::If(IrIf {
+        invert: i1,
+        cond: c1,
+        body: Box::from(set_flag(context, &flag, 1)),
     }));
-    return condition;
+    s.push(IrStatement::If(IrIf {
+        invert: i0,
+        cond: c0,
+        body: Box::from(IrStatement::Block(IrBlock {
+            can_embed: true,
+            root_fn_name: context.fn_name.clone(),
+            fn_block_index: context.use_block(),
+            statements: s1,
+        })),
+    }));
+    context.forfeit_variable(&flag);
+
+    (
+        s,
+        Cond::CheckVal(CheckVal {
+            var_name: flag,
+            min: 0,
+            max: 0,
+        }),
+        true,
+    )
 }
 
-fn convert_if(context: &mut Context, ast_node: &If) -> Vec<IrStatement> {
-    /**
-    Convert the if statement to a series of commands
+fn convert_disjunction(
+    context: &mut Context,
+    e0: &Expression,
+    e1: &Expression,
+    negate: bool,
+) -> (Vec<IrStatement>, Cond, bool) {
+    // allocated first, so it is not one of the temporaries the operands are checked on
+    let flag = context.get_variable();
+
+    let (mut s0, c0, i0) = convert_expr_for_comparison(context, e0, negate);
+    let (mut s1, c1, i1) = convert_expr_for_comparison(context, e1, negate);
+
+    let mut s = vec![set_flag(context, &flag, 0)];
+    s.append(&mut s0);
+    s.push(IrStatement::If(IrIf {
+        invert: i0,
+        cond: c0,
+        body: Box::from(set_flag(context, &flag, 1)),
+    }));
 
-    if cond {
-        body
-    } else if cond {
-        body
+    let set_right = IrStatement::If(IrIf {
+        invert: i1,
+        cond: c1,
+        body: Box::from(set_flag(context, &flag, 1)),
+    });
+    if s1.is_empty() {
+        // checking the right side when the left side already held does not change the flag
+        s.push(set_right);
     } else {
-        body
+        // the right side is only evaluated if the left side did not hold
+        s1.push(set_right);
+        s.push(IrStatement::If(IrIf {
+            invert: false,
+            cond: Cond::CheckVal(CheckVal {
+                var_name: flag.clone(),
+                min: 0,
+                max: 0,
+            }),
+            body: Box::from(IrStatement::Block(IrBlock {
+                can_embed: true,
+                root_fn_name: context.fn_name.clone(),
+                fn_block_index: context.use_block(),
+                statements: s1,
+            })),
+        }));
     }
+    context.forfeit_variable(&flag);
 
-    becomes
+    (
+        s,
+        Cond::CheckVal(CheckVal {
+            var_name: flag,
+            min: 0,
+            max: 0,
+        }),
+        true,
+    )
+}
 
-    set if_check to 1
-    compute cond
-    execute if cond run {
-        body
-        set if_check to 0
+// the type of an expression that is assigned to a variable without a type annotation
+fn infer_type(context: &Context, ast_node: &Expression) -> Type {
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => {
+            context.get_name_path_type(x)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x))
+            if array_len(context, x).is_some() =>
+        {
+            Type::Int
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Index(x)) => {
+            match context.get_name_path_type(&x.array) {
+                Type::Array(element, _) => *element,
+                _ => unreachable!("CheckTypes only lets arrays be indexed"),
+            }
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x)) => match &x.receiver {
+            Some(receiver) => {
+                context.get_return_type(&context.get_method_name(receiver, &x.name.raw))
+            }
+            None => context.get_return_type(x.name.global_resolved.as_ref().unwrap()),
+        },
+        ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(x)) => {
+            Type::Struct(x.type_name.clone())
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Float(_))) => {
+            Type::Float
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::String(_))) => {
+            Type::String
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Double(_))) => {
+            Type::Double
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Compound(x))) => {
+            infer_compound_type(context, x)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Bool(_))) => {
+            Type::Bool
+        }
+        ExpressionEnum::Unary(UnOp::Cast(type_), _) => type_.clone(),
+        ExpressionEnum::Unary(UnOp::Not, _) => Type::Bool,
+        ExpressionEnum::Unary(_, e) => infer_type(context, e),
+        ExpressionEnum::Binary(
+            e0,
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod,
+            _,
+        ) => infer_type(context, e0),
+        ExpressionEnum::Binary(_, _, _) => Type::Bool,
+        _ => Type::Int,
     }
+}
 
-    execute if if_check == 1 run {
-        compute cond
-        execute if cond run {
-            body
-            set if_check to 0
+fn infer_compound_type(context: &Context, ast_node: &Compound) -> Type {
+    Type::Compound(
+        ast_node
+            .iter()
+            .map(|(name, value)| {
+                let type_ = match value {
+                    CompoundValue::Expression(x) => infer_type(context, x),
+                    CompoundValue::Compound(x) => infer_compound_type(context, x),
+                };
+                (name.clone(), type_)
+            })
+            .collect(),
+    )
+}
+
+// CheckTypes only lets both sides of a binary operator be of the same type, ints and floats are mixed through `as`
+fn operand_type(context: &Context, e0: &Expression, e1: &Expression) -> Type {
+    let type_0 = infer_type(context, e0);
+    debug_assert!(is_same_type(&type_0, &infer_type(context, e1)));
+    type_0
+}
+
+// a scalar only matches itself, ints are neither fixed-point numbers nor bools
+fn is_same_type(type_0: &Type, type_1: &Type) -> bool {
+    match (type_0, type_1) {
+        (Type::Bool, Type::Bool)
+        | (Type::Float, Type::Float)
+        | (Type::Double, Type::Double)
+        | (Type::String, Type::String) => true,
+        (Type::Bool | Type::Float | Type::Double | Type::String, _)
+        | (_, Type::Bool | Type::Float | Type::Double | Type::String) => false,
+        (Type::Struct(struct_0), Type::Struct(struct_1)) => {
+            struct_0.global_resolved == struct_1.global_resolved
         }
+        (Type::Array(element_0, len_0), Type::Array(element_1, len_1)) => {
+            len_0 == len_1 && is_same_type(element_0, element_1)
+        }
+        (Type::Compound(fields_0), Type::Compound(fields_1)) => {
+            fields_0.len() == fields_1.len()
+                && fields_0.iter().all(|(name, field_0)| {
+                    fields_1
+                        .get(name)
+                        .is_some_and(|field_1| is_same_type(field_0, field_1))
+                })
+        }
+        (Type::Struct(_) | Type::Array(_, _) | Type::Compound(_), _)
+        | (_, Type::Struct(_) | Type::Array(_, _) | Type::Compound(_)) => false,
+        _ => true,
     }
-    execute if if_check == 1 run {
-        body
-    }
-     */
-    let mut elses = vec![];
+}
 
-    let mut cur = ast_node;
+fn copy_slots(source: &Address, address: &Address, size: i32) -> Vec<IrStatement> {
+    (0..size)
+        .filter(|offset| source.with_offset(*offset) != address.with_offset(*offset))
+        .map(|offset| match source.kind {
+            AddressKind::Score => IrStatement::ScoreOperation(IrScoreOperation {
+                left: address.with_offset(offset),
+                op: IrScoreOperationType::Assign,
+                right: source.with_offset(offset),
+            }),
+            AddressKind::Storage => {
+                IrStatement::StorageCopy(address.with_offset(offset), source.with_offset(offset))
+            }
+        })
+        .collect()
+}
+
+// a string written as SNBT, quoted and with its quotes and backslashes escaped
+fn to_snbt_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// the expressions of a compound and their paths, in the order of the paths
+fn compound_expressions(ast_node: &Compound) -> Vec<(Vec<String>, &Expression)> {
+    let mut expressions = vec![];
+    for (name, value) in ast_node {
+        match value {
+            CompoundValue::Expression(x) => expressions.push((vec![name.clone()], &**x)),
+            CompoundValue::Compound(x) => {
+                for (mut path, expr) in compound_expressions(x) {
+                    path.insert(0, name.clone());
+                    expressions.push((path, expr));
+                }
+            }
+        }
+    }
+    expressions.sort_by(|(path_0, _), (path_1, _)| path_0.cmp(path_1));
+    expressions
+}
+
+// the value as SNBT if it is known when it is compiled, numbers are kept as they would be in a score
+fn constant_to_snbt(context: &Context, ast_node: &Expression) -> Option<String> {
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(x)) => match x {
+            LiteralValue::Null => Some("0".to_string()),
+            LiteralValue::Int(x) => Some(x.to_string()),
+            LiteralValue::Bool(x) => Some((*x as i32).to_string()),
+            LiteralValue::Float(x) => {
+                Some(to_fixed_point(*x as f64, context.float_scale).to_string())
+            }
+            LiteralValue::Double(x) => Some(to_fixed_point(*x, context.float_scale).to_string()),
+            LiteralValue::String(x) => Some(to_snbt_string(x)),
+            LiteralValue::Compound(_) => None,
+        },
+        _ => None,
+    }
+}
+
+// the constant fields of the compound, the nested compounds are always there so their fields can be stored into
+fn compound_to_snbt(context: &Context, ast_node: &Compound) -> String {
+    let mut fields: Vec<String> = ast_node
+        .iter()
+        .filter_map(|(name, value)| match value {
+            CompoundValue::Expression(x) => {
+                constant_to_snbt(context, x).map(|x| format!("{name}: {x}"))
+            }
+            CompoundValue::Compound(x) => Some(format!("{name}: {}", compound_to_snbt(context, x))),
+        })
+        .collect();
+    fields.sort();
+    format!("{{{}}}", fields.join(", "))
+}
+
+// stores the value into the field, a storage variable is copied from where it is
+fn convert_field_set(
+    context: &mut Context,
+    value: &Expression,
+    field: IrCompoundField,
+    type_: &Type,
+) -> Vec<IrStatement> {
+    match (context.get_address_kind(type_), &value.expr) {
+        (AddressKind::Score, _) => {
+            let temporary = context.get_variable();
+            let value = rec_convert_expr(context, value, &temporary);
+            let mut s = value.statements;
+            s.push(IrStatement::FieldSet(
+                field,
+                value.existing_address.unwrap_or(temporary.clone()),
+            ));
+            context.forfeit_variable(&temporary);
+            s
+        }
+        (AddressKind::Storage, ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)))
+            if context.convert_compound_field(x).is_none() =>
+        {
+            vec![IrStatement::FieldSet(field, context.convert_name_path(x))]
+        }
+        (AddressKind::Storage, _) => {
+            let temporary = context.get_variable_of_type(type_);
+            let mut s = convert_storage_assignment(context, value, &temporary, type_);
+            s.push(IrStatement::FieldSet(field, temporary.clone()));
+            context.forfeit_variable(&temporary);
+            s
+        }
+    }
+}
+
+/*
+The constant fields are written with a single command, every other field is evaluated and then stored into its
+path. A score is stored as an int, which keeps a fixed-point number in the same representation.
+ */
+fn convert_compound(
+    context: &mut Context,
+    ast_node: &Compound,
+    address: &Address,
+) -> Vec<IrStatement> {
+    let mut s = vec![IrStatement::StorageSet(
+        address.clone(),
+        compound_to_snbt(context, ast_node),
+    )];
+
+    for (path, value) in compound_expressions(ast_node) {
+        if constant_to_snbt(context, value).is_some() {
+            continue;
+        }
+
+        let field = IrCompoundField {
+            compound: address.clone(),
+            path,
+        };
+        let type_ = infer_type(context, value);
+        s.append(&mut convert_field_set(context, value, field, &type_));
+    }
+    s
+}
+
+// strings, arrays and compounds are held in storage and are copied as a whole
+fn convert_storage_assignment(
+    context: &mut Context,
+    ast_node: &Expression,
+    address: &Address,
+    type_: &Type,
+) -> Vec<IrStatement> {
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::String(x))) => {
+            vec![IrStatement::StorageSet(address.clone(), to_snbt_string(x))]
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Compound(x))) => {
+            // `c = { hp: c.hp + 1 }` has to be built in a temporary before it is assigned
+            if reads_address(context, ast_node, address) {
+                let temporary = context.get_variable_of_type(type_);
+                let mut s = convert_compound(context, x, &temporary);
+                s.append(&mut copy_slots(&temporary, address, 1));
+                context.forfeit_variable(&temporary);
+                s
+            } else {
+                convert_compound(context, x, address)
+            }
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => {
+            match context.convert_compound_field(x) {
+                Some(field) => vec![IrStatement::FieldGet(field, address.clone())],
+                None => copy_slots(&context.convert_name_path(x), address, 1),
+            }
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x)) => {
+            let mut s = convert_fn_call(context, x);
+            s.append(&mut copy_slots(
+                &context.get_return_variable_of_type(type_),
+                address,
+                1,
+            ));
+            s
+        }
+        _ => panic!("Not implemented"), // TODO: string operations
+    }
+}
+
+// assigns a value of the type to the address, a struct is assigned field by field
+fn convert_assignment(
+    context: &mut Context,
+    ast_node: &Expression,
+    address: &Address,
+    type_: &Type,
+) -> Vec<IrStatement> {
+    // CheckTypes only lets a value be assigned to an address of its type
+    debug_assert!(is_same_type(&infer_type(context, ast_node), type_));
+
+    if matches!(type_, Type::String | Type::Array(_, _) | Type::Compound(_)) {
+        return convert_storage_assignment(context, ast_node, address, type_);
+    }
+    if !matches!(type_, Type::Struct(_)) {
+        return convert_expr(context, ast_node, address);
+    }
+
+    let size = context.get_type_size(type_);
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => {
+            copy_slots(&context.convert_name_path(x), address, size)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x)) => {
+            // a struct is returned in as many return scores as it has fields
+            let mut s = convert_fn_call(context, x);
+            s.append(&mut copy_slots(
+                &context.get_return_variable(),
+                address,
+                size,
+            ));
+            s
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(x)) => {
+            // `p = Point { x: p.y, y: p.x }` has to be built in a temporary before it is assigned
+            if (0..size)
+                .any(|offset| reads_address(context, ast_node, &address.with_offset(offset)))
+            {
+                let temporary = context.get_variable_of_type(type_);
+                let mut s = convert_struct_literal(context, x, &temporary);
+                s.append(&mut copy_slots(&temporary, address, size));
+                context.forfeit_variable(&temporary);
+                s
+            } else {
+                convert_struct_literal(context, x, address)
+            }
+        }
+        _ => panic!("Not implemented"),
+    }
+}
+
+fn convert_struct_literal(
+    context: &mut Context,
+    ast_node: &StructLiteral,
+    address: &Address,
+) -> Vec<IrStatement> {
+    let fields = context.get_field_offsets(&ast_node.type_name);
+
+    let mut s = vec![];
+    for (name, offset, field_type) in fields {
+        let Some(value) = ast_node.fields.get(&name) else {
+            unreachable!("CheckTypes only lets a struct literal set every field of its struct");
+        };
+        s.append(&mut convert_assignment(
+            context,
+            value,
+            &address.with_offset(offset),
+            &field_type,
+        ));
+    }
+    s
+}
+
+fn convert_var_decl(context: &mut Context, ast_node: &VarDecl) -> Vec<IrStatement> {
+    let type_ = match (&ast_node.var_def.type_, &ast_node.expr) {
+        (Some(type_), _) => type_.clone(),
+        (None, Some(expr)) => infer_type(context, expr),
+        (None, None) => Type::Int,
+    };
+    // the variable is only declared once its value is set, so its kind comes from the type here
+    let address = context
+        .convert_var_name(&ast_node.var_def.name)
+        .with_kind(context.get_address_kind(&type_));
+
+    let s = match (&ast_node.expr, &type_) {
+        (Some(expr), _) => convert_assignment(context, expr, &address, &type_),
+        (None, Type::Array(element, len)) => {
+            if matches!(
+                **element,
+                Type::Struct(_) | Type::Array(_, _) | Type::String | Type::Compound(_)
+            ) {
+                unreachable!("CheckTypes only lets arrays hold numbers and bools");
+            }
+            // every element starts out as 0, like a score that was never set
+            vec![IrStatement::ArrayInit(address, *len)]
+        }
+        (None, _) => vec![],
+    };
+    context.declare_variable(&ast_node.var_def.name, type_);
+    s
+}
+
+// `xs.len()` is the length of the array, which is known when it is compiled
+fn array_len(context: &Context, ast_node: &FnCall) -> Option<u32> {
+    let receiver = ast_node.receiver.as_ref()?;
+    match context.get_name_path_type(receiver) {
+        Type::Array(_, len) if ast_node.name.raw == "len" => Some(len),
+        Type::Array(_, _) => unreachable!("CheckTypes only lets arrays call len"),
+        _ => None,
+    }
+}
+
+/*
+The element an index expression points to. A constant index is checked against the length of the array, any
+other index is evaluated into a score, which is returned as well if it is a temporary that has to be forfeited.
+ */
+fn convert_element(
+    context: &mut Context,
+    ast_node: &Index,
+) -> (Vec<IrStatement>, IrArrayElement, Option<Address>) {
+    let array = context.convert_name_path(&ast_node.array);
+    let Type::Array(_, len) = context.get_name_path_type(&ast_node.array) else {
+        unreachable!("CheckTypes only lets arrays be indexed");
+    };
+    // CheckTypes only lets an array be indexed with an int
+    debug_assert!(is_same_type(
+        &infer_type(context, &ast_node.index),
+        &Type::Int
+    ));
+
+    let (s, index, temporary) = match &ast_node.index.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Int(i))) => {
+            // CheckTypes rejects a constant index out of the bounds of the array
+            debug_assert!(*i >= 0 && (*i as u32) < len);
+            (vec![], ArrayIndex::Const(*i), None)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => (
+            vec![],
+            ArrayIndex::Score(context.convert_name_path(x)),
+            None,
+        ),
+        _ => {
+            let temporary = context.get_variable();
+            let s = convert_expr(context, &ast_node.index, &temporary);
+            (s, ArrayIndex::Score(temporary.clone()), Some(temporary))
+        }
+    };
+
+    (s, IrArrayElement { array, index }, temporary)
+}
+
+fn convert_index_assign(context: &mut Context, ast_node: &IndexAssign) -> Vec<IrStatement> {
+    let element_type = infer_type(
+        context,
+        &Expression {
+            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Index(
+                ast_node.element.clone(),
+            )),
+            type_: None,
+            span: ast_node.span.clone(),
+        },
+    );
+    // CheckTypes only lets a value be assigned to an element of its type
+    debug_assert!(is_same_type(
+        &infer_type(context, &ast_node.expr),
+        &element_type
+    ));
+
+    // the value is computed before the index, which is only kept in a score for as long as it is needed
+    let value_temporary = context.get_variable();
+    let value = rec_convert_expr(context, &ast_node.expr, &value_temporary);
+    let mut s = value.statements;
+    let value_address = value.existing_address.unwrap_or(value_temporary.clone());
+
+    let (mut element_s, element, index_temporary) = convert_element(context, &ast_node.element);
+    s.append(&mut element_s);
+    s.push(IrStatement::ArraySet(element, value_address));
+
+    if let Some(index_temporary) = index_temporary {
+        context.forfeit_variable(&index_temporary);
+    }
+    context.forfeit_variable(&value_temporary);
+    s
+}
+
+// every value is evaluated before any of them is shown
+fn convert_print(context: &mut Context, ast_node: &[Expression]) -> Vec<IrStatement> {
+    let mut s = vec![];
+    let mut values = vec![];
+    let mut temporaries = vec![];
+    for arg in ast_node {
+        let type_ = infer_type(context, arg);
+        if matches!(type_, Type::Struct(_) | Type::Array(_, _)) {
+            unreachable!("CheckTypes does not let structs and arrays be printed");
+        }
+
+        match (&type_, &arg.expr) {
+            (
+                Type::String | Type::Compound(_),
+                ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)),
+            ) if context.convert_compound_field(x).is_none() => {
+                values.push(context.convert_name_path(x));
+            }
+            (Type::String | Type::Compound(_), _) => {
+                let temporary = context.get_variable_of_type(&type_);
+                s.append(&mut convert_storage_assignment(
+                    context, arg, &temporary, &type_,
+                ));
+                values.push(temporary.clone());
+                temporaries.push(temporary);
+            }
+            _ => {
+                let temporary = context.get_variable();
+                let mut expr = rec_convert_expr(context, arg, &temporary);
+                s.append(&mut expr.statements);
+                values.push(expr.existing_address.unwrap_or(temporary.clone()));
+                temporaries.push(temporary);
+            }
+        }
+    }
+
+    s.push(IrStatement::Print(values));
+    for temporary in temporaries {
+        context.forfeit_variable(&temporary);
+    }
+    s
+}
+
+fn convert_var_assign(context: &mut Context, ast_node: &VarAssign) -> Vec<IrStatement> {
+    if let Some(field) = context.convert_compound_field(&ast_node.name_path) {
+        return convert_field_assign(context, ast_node, field);
+    }
+
+    let address = context.convert_name_path(&ast_node.name_path);
+    let type_ = context.get_name_path_type(&ast_node.name_path);
+    convert_assignment(context, &ast_node.expr, &address, &type_)
+}
+
+// the value is assigned to a temporary of the type of the field, which is then stored into the field
+fn convert_field_assign(
+    context: &mut Context,
+    ast_node: &VarAssign,
+    field: IrCompoundField,
+) -> Vec<IrStatement> {
+    let type_ = context.get_name_path_type(&ast_node.name_path);
+    // CheckTypes only lets a value be assigned to a field of its type
+    debug_assert!(is_same_type(&infer_type(context, &ast_node.expr), &type_));
+    convert_field_set(context, &ast_node.expr, field, &type_)
+}
+
+fn convert_expr_statement(context: &mut Context, ast_node: &Expression) -> Vec<IrStatement> {
+    // the value is discarded, so only the side effects are emitted and nothing is stored in a temporary
+    match &ast_node.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x))
+            if array_len(context, x).is_some() =>
+        {
+            vec![]
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x)) => {
+            convert_fn_call(context, x)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Index(x)) => {
+            convert_expr_statement(context, &x.index)
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(x)) => {
+            let mut s = vec![];
+            for (name, _, _) in context.get_field_offsets(&x.type_name) {
+                if let Some(field) = x.fields.get(&name) {
+                    s.append(&mut convert_expr_statement(context, field));
+                }
+            }
+            s
+        }
+        ExpressionEnum::AtomicExpression(_) => vec![],
+        ExpressionEnum::Unary(
+            unop @ (UnOp::PreInc | UnOp::PreDec | UnOp::PostInc | UnOp::PostDec),
+            x,
+        ) => {
+            let type_ = infer_type(context, x);
+            if let Some(field) = get_increment_field(context, x) {
+                let temporary = context.get_variable();
+                let s = convert_field_increment(context, field, &temporary, unop, &type_);
+                context.forfeit_variable(&temporary);
+                return s;
+            }
+
+            let address = get_increment_address(context, x);
+            vec![convert_increment(context, &address, unop, &type_)]
+        }
+        ExpressionEnum::Unary(_, x) => convert_expr_statement(context, x),
+        ExpressionEnum::Binary(e0, binop @ (BinOp::And | BinOp::Or), e1) => {
+            // the right side only runs if the left side does not decide the result
+            let (mut s, cond, invert) =
+                convert_expr_for_comparison(context, e0, matches!(binop, BinOp::Or));
+            let body = convert_expr_statement(context, e1);
+            if !body.is_empty() {
+                s.push(IrStatement::If(IrIf {
+                    invert,
+                    cond,
+                    body: Box::from(IrStatement::Block(IrBlock {
+                        can_embed: true,
+                        root_fn_name: context.fn_name.clone(),
+                        fn_block_index: context.use_block(),
+                        statements: body,
+                    })),
+                }));
+            }
+            s
+        }
+        ExpressionEnum::Binary(e0, _, e1) => {
+            let mut s = convert_expr_statement(context, e0);
+            s.append(&mut convert_expr_statement(context, e1));
+            s
+        }
+    }
+}
+
+fn convert_condition(
+    context: &mut Context,
+    cond: &Expression,
+    invert_cond: bool,
+    body: IrStatement,
+) -> Vec<IrStatement> {
+    let mut condition = vec![];
+    let (mut expr_statements, cond, invert) =
+        convert_expr_for_comparison(context, cond, invert_cond);
+    condition.append(&mut expr_statements);
+    condition.push(IrStatement::If(IrIf {
+        invert,
+        cond,
+        body: Box::from(body),
+    }));
+    return condition;
+}
+
+fn convert_if(context: &mut Context, ast_node: &If) -> Vec<IrStatement> {
+    /**
+    Convert the if statement to a series of commands
+
+    if cond {
+        body
+    } else if cond {
+        body
+    } else {
+        body
+    }
+
+    becomes
+
+    set if_check to 1
+    compute cond
+    execute if cond run {
+        set if_check to 0
+        body
+    }
+
+    execute if if_check == 1 run {
+        compute cond
+        execute if cond run {
+            set if_check to 0
+            body
+        }
+    }
+    execute if if_check == 1 run {
+        body
+    }
+
+    if_check is cleared before the body so that an early exit from the body still skips the elses.
+     */
+    let mut elses = vec![];
+
+    let mut cur = ast_node;
 
     loop {
         if let Some(next) = cur.else_.as_ref() {
@@ -412,7 +1525,9 @@ fn convert_if(context: &mut Context, ast_node: &If) -> Vec<IrStatement> {
         }
     }
 
-    let if_variable = context.get_if_variable();
+    // every chain gets its own flag, an if/else nested in one of the bodies must not clobber it
+    let if_variable = context.get_variable();
+    let control_exits = context.control_exits();
 
     let mut s = vec![];
 
@@ -427,14 +1542,16 @@ fn convert_if(context: &mut Context, ast_node: &If) -> Vec<IrStatement> {
 
     // compute block for first if statement
     let block = IrStatement::Block({
-        let mut s = convert_block(context, &ast_node.body, true);
+        let mut s = convert_nested_block(context, &ast_node.body, true);
         if elses.len() > 0 {
-            s.statements
-                .push(IrStatement::ScoreOperation(IrScoreOperation {
+            s.statements.insert(
+                0,
+                IrStatement::ScoreOperation(IrScoreOperation {
                     left: if_variable.clone(),
                     op: IrScoreOperationType::Assign,
                     right: context.const_generator.get_const(0),
-                }));
+                }),
+            );
         }
         s
     });
@@ -448,13 +1565,15 @@ fn convert_if(context: &mut Context, ast_node: &If) -> Vec<IrStatement> {
 
     for (condition, body) in elses {
         let block = IrStatement::Block({
-            let mut s = convert_block(context, &body, true);
-            s.statements
-                .push(IrStatement::ScoreOperation(IrScoreOperation {
+            let mut s = convert_nested_block(context, &body, true);
+            s.statements.insert(
+                0,
+                IrStatement::ScoreOperation(IrScoreOperation {
                     left: if_variable.clone(),
                     op: IrScoreOperationType::Assign,
                     right: context.const_generator.get_const(0),
-                }));
+                }),
+            );
             s
         });
 
@@ -480,27 +1599,13 @@ fn convert_if(context: &mut Context, ast_node: &If) -> Vec<IrStatement> {
             body: Box::from(else_block),
         }));
     }
+    context.forfeit_variable(&if_variable);
+    s.append(&mut convert_control_check(context, control_exits));
     s
 }
 
 fn convert_while(context: &mut Context, ast_node: &While) -> Vec<IrStatement> {
-    let mut s: Vec<IrStatement> = vec![];
-
-    let mut condition = convert_condition(context, &ast_node.cond, true, IrStatement::Return);
-
-    // parse body
-    let mut body = convert_block(context, &ast_node.body, false);
-
-    // insert condition before body
-    condition.append(&mut body.statements);
-    body.statements = condition;
-
-    // insert recursion
-    body.statements.push(IrStatement::FnCall(IrFnCall {
-        fn_name: body.get_fn_name(),
-    }));
-    s.push(IrStatement::Block(body));
-    s
+    convert_loop(context, Some(&ast_node.cond), &ast_node.body, None)
 }
 
 fn convert_for(context: &mut Context, ast_node: &For) -> Vec<IrStatement> {
@@ -509,8 +1614,59 @@ fn convert_for(context: &mut Context, ast_node: &For) -> Vec<IrStatement> {
         s.append(&mut convert_statement(context, init));
     }
 
+    s.append(&mut convert_loop(
+        context,
+        ast_node.cond.as_deref(),
+        &ast_node.body,
+        ast_node.step.as_deref(),
+    ));
+    s
+}
+
+fn convert_loop(
+    context: &mut Context,
+    cond: Option<&Expression>,
+    body: &Block,
+    step: Option<&Statement>,
+) -> Vec<IrStatement> {
+    /*
+    Convert a loop to a self-recursive block
+
+    while cond {
+        body
+    }
+
+    becomes
+
+    loop {
+        compute cond
+        execute unless cond run return
+        body
+        step
+        function loop
+    }
+
+    If the body has a continue, it gets its own block, so that returning from it still runs the step:
+
+    loop {
+        compute cond
+        execute unless cond run return
+        function body
+        execute if control == continue run set control to 0
+        execute unless control == 0 run return
+        step
+        function loop
+    }
+
+    A break or continue deeper in the body sets the control variable and returns, which the blocks in
+    between pass on. Breaks are cleared after the loop.
+     */
+    let mut s: Vec<IrStatement> = vec![];
+    let control_exits = context.control_exits();
+
+    context.enter_block();
     let mut condition = vec![];
-    if let Some(cond) = &ast_node.cond {
+    if let Some(cond) = cond {
         condition.append(&mut convert_condition(
             context,
             cond,
@@ -519,39 +1675,187 @@ fn convert_for(context: &mut Context, ast_node: &For) -> Vec<IrStatement> {
         ));
     }
 
-    // parse body
-    let mut body = convert_block(context, &ast_node.body, false);
+    let (mut loop_block, loop_exits) = if has_continue(body) {
+        context.enter_block();
+        context.enter_loop(true);
+        let body = convert_block(context, body, false);
+        let loop_exits = context.exit_loop();
+        context.exit_block();
+
+        let mut statements = condition;
+        statements.push(IrStatement::Block(body));
+        if loop_exits.continues > 0 {
+            statements.push(convert_control_clear(context, ControlFlow::Continue));
+        }
+        statements.append(&mut convert_control_check(context, control_exits));
 
-    // insert condition before body
-    condition.append(&mut body.statements);
-    body.statements = condition;
+        let loop_block = IrBlock {
+            can_embed: false,
+            root_fn_name: context.fn_name.clone(),
+            fn_block_index: context.use_block(),
+            statements,
+        };
+        (loop_block, loop_exits)
+    } else {
+        context.enter_loop(false);
+        let mut body = convert_block(context, body, false);
+        let loop_exits = context.exit_loop();
+
+        // insert condition before body
+        condition.append(&mut body.statements);
+        body.statements = condition;
+        (body, loop_exits)
+    };
 
     // insert step statement after body
-    body.statements.append(&mut {
-        if let Some(step) = &ast_node.step {
-            convert_statement(context, step)
-        } else {
-            vec![]
-        }
-    });
+    if let Some(step) = step {
+        loop_block
+            .statements
+            .append(&mut convert_statement(context, step));
+    }
+    context.exit_block();
 
     // insert recursion
-    body.statements.push(IrStatement::FnCall(IrFnCall {
-        fn_name: body.get_fn_name(),
+    loop_block.statements.push(IrStatement::FnCall(IrFnCall {
+        fn_name: loop_block.get_fn_name(),
     }));
-    s.push(IrStatement::Block(body));
+    s.push(IrStatement::Block(loop_block));
+
+    context.consume_loop_exits(&loop_exits);
+    if loop_exits.breaks > 0 {
+        s.push(convert_control_clear(context, ControlFlow::Break));
+    }
+    s.append(&mut convert_control_check(context, control_exits));
+    s
+}
+
+// continues belong to the innermost loop, so nested loops are not searched
+fn has_continue(block: &Block) -> bool {
+    block.statements.iter().any(|statement| match statement {
+        Statement::Continue(_) => true,
+        Statement::Block(block) => has_continue(block),
+        Statement::If(if_) => {
+            let mut cur = if_;
+            loop {
+                if has_continue(&cur.body) {
+                    return true;
+                }
+                match &cur.else_ {
+                    Some(Else::If(if_)) => cur = if_,
+                    Some(Else::Block(block)) => return has_continue(block),
+                    None => return false,
+                }
+            }
+        }
+        _ => false,
+    })
+}
+
+fn convert_loop_exit(context: &mut Context, control_flow: ControlFlow) -> Vec<IrStatement> {
+    if !context.is_in_loop() {
+        panic!("{:?} outside of a loop", control_flow);
+    }
+
+    let mut s = vec![];
+    if context.loop_exit_needs_control(control_flow) {
+        s.push(IrStatement::ScoreOperation(IrScoreOperation {
+            left: context.get_control_variable(),
+            op: IrScoreOperationType::Assign,
+            right: context.const_generator.get_const(control_flow as i32),
+        }));
+        context.record_loop_exit(control_flow);
+    }
+    s.push(IrStatement::Return);
+    s
+}
+
+fn convert_return(context: &mut Context, ast_node: &Expression) -> Vec<IrStatement> {
+    let mut s = vec![];
+
+    if !matches!(
+        ast_node.expr,
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Null))
+    ) {
+        let return_type = context.return_type.clone();
+        let return_variable = context.get_return_variable_of_type(&return_type);
+        s.append(&mut convert_assignment(
+            context,
+            ast_node,
+            &return_variable,
+            &return_type,
+        ));
+    }
+
+    if context.is_nested() {
+        // only the current block is exited, so the blocks above need to be told to exit as well
+        s.push(IrStatement::ScoreOperation(IrScoreOperation {
+            left: context.get_control_variable(),
+            op: IrScoreOperationType::Assign,
+            right: context
+                .const_generator
+                .get_const(ControlFlow::Return as i32),
+        }));
+        context.record_control_exit();
+    }
+
+    s.push(IrStatement::Return);
     s
 }
 
+fn convert_control_check(context: &mut Context, control_exits: usize) -> Vec<IrStatement> {
+    // if a nested block exited early, exit this block as well
+    if context.control_exits() == control_exits {
+        return vec![];
+    }
+
+    vec![IrStatement::If(IrIf {
+        invert: true,
+        cond: Cond::CheckVal(CheckVal {
+            var_name: context.get_control_variable(),
+            min: 0,
+            max: 0,
+        }),
+        body: Box::from(IrStatement::Return),
+    })]
+}
+
+// once the loop has handled a break or continue, the control variable is cleared for the blocks above
+fn convert_control_clear(context: &mut Context, control_flow: ControlFlow) -> IrStatement {
+    let control_variable = context.get_control_variable();
+    IrStatement::If(IrIf {
+        invert: false,
+        cond: Cond::CheckVal(CheckVal {
+            var_name: control_variable.clone(),
+            min: control_flow as i32,
+            max: control_flow as i32,
+        }),
+        body: Box::from(IrStatement::ScoreOperation(IrScoreOperation {
+            left: control_variable,
+            op: IrScoreOperationType::Assign,
+            right: context.const_generator.get_const(0),
+        })),
+    })
+}
+
 fn convert_statement(context: &mut Context, ast_node: &Statement) -> Vec<IrStatement> {
     return match ast_node {
         Statement::VarDecl(x) => convert_var_decl(context, x),
         Statement::VarAssign(x) => convert_var_assign(context, x),
+        Statement::IndexAssign(x) => convert_index_assign(context, x),
+        Statement::Print(x) => convert_print(context, x),
         Statement::If(x) => convert_if(context, x),
         Statement::While(x) => convert_while(context, x),
         Statement::For(x) => convert_for(context, x),
-        Statement::Block(x) => vec![IrStatement::Block(convert_block(context, x, false))],
-        _ => panic!("Not implemented"),
+        Statement::Return(x) => convert_return(context, x),
+        Statement::Block(x) => {
+            let control_exits = context.control_exits();
+            let mut s = vec![IrStatement::Block(convert_nested_block(context, x, false))];
+            s.append(&mut convert_control_check(context, control_exits));
+            s
+        }
+        Statement::Break(_) => convert_loop_exit(context, ControlFlow::Break),
+        Statement::Continue(_) => convert_loop_exit(context, ControlFlow::Continue),
+        Statement::Expression(x) => convert_expr_statement(context, x),
     };
 }
 
@@ -569,6 +1873,13 @@ fn convert_block(context: &mut Context, ast_node: &Block, can_embed: bool) -> Ir
     }
 }
 
+fn convert_nested_block(context: &mut Context, ast_node: &Block, can_embed: bool) -> IrBlock {
+    context.enter_block();
+    let block = convert_block(context, ast_node, can_embed);
+    context.exit_block();
+    block
+}
+
 fn convert_reference(ast_node: &Reference) -> String {
     return global_name_updater(ast_node.global_resolved.as_ref().unwrap());
 }
@@ -576,14 +1887,64 @@ fn convert_reference(ast_node: &Reference) -> String {
 pub fn convert_fn(
     ast_node: &FnDef,
     definition_table: &DefinitionTable<Rc<GlobalResolvedName>>,
+    frame_calls: HashSet<Rc<GlobalResolvedName>>,
     const_generator: &mut context::ConstGenerator,
+    float_scale: i32,
 ) -> IrFnDef {
     let fn_name = convert_reference(&ast_node.name);
-    let mut ctx = Context::new(&fn_name, definition_table, const_generator);
+    let mut ctx = Context::new(&fn_name, definition_table, const_generator, float_scale);
+    ctx.set_frame_calls(frame_calls);
+    ctx.return_type = ast_node.return_type.clone();
+
+    for arg in &ast_node.args {
+        ctx.declare_variable(&arg.name, arg.type_.clone().unwrap_or(Type::Int));
+    }
+    if ctx.has_frame_calls() {
+        // a reentering call leaves the control variable set when it returns early, so it is saved by the frames of
+        // the calls it is checked after
+        ctx.get_control_variable();
+    }
+
+    let mut statements = convert_block(&mut ctx, &ast_node.body, true).statements;
+
+    // the control variable keeps its value from the last call, so it has to be reset on entry
+    if let Some(control_variable) = ctx.used_control_variable() {
+        statements.insert(
+            0,
+            IrStatement::ScoreOperation(IrScoreOperation {
+                left: control_variable.clone(),
+                op: IrScoreOperationType::Assign,
+                right: ctx.const_generator.get_const(0),
+            }),
+        );
+    }
+
+    frames::prune_frames(&mut statements);
 
     IrFnDef {
         fn_name: convert_reference(&ast_node.name),
-        statements: convert_block(&mut ctx, &ast_node.body, true).statements,
+        statements,
+        block_count: ctx.block_count,
+    }
+}
+
+// the initialisers run in the load function, in the given order
+pub fn convert_global_initializers(
+    globals: &[&VarDecl],
+    definition_table: &DefinitionTable<Rc<GlobalResolvedName>>,
+    const_generator: &mut context::ConstGenerator,
+    float_scale: i32,
+) -> IrFnDef {
+    let mut ctx = Context::new(LOAD_FN_NAME, definition_table, const_generator, float_scale);
+
+    let mut statements = vec![];
+    for global in globals {
+        statements.append(&mut convert_var_decl(&mut ctx, global));
+    }
+
+    IrFnDef {
+        fn_name: LOAD_FN_NAME.to_string(),
+        statements,
         block_count: ctx.block_count,
     }
 }
@@ -595,9 +1956,10 @@ mod tests {
     use crate::front::mergers::program::ProgramMerger;
     use crate::middle::format::ir_types::IrFnDef;
     use crate::middle::format::ir_types::{
-        Address, AddressOrigin, CompareOp, Cond, IrScoreOperationType, IrStatement,
+        Address, AddressKind, AddressOrigin, ArrayIndex, CompareOp, Cond, IrArrayElement,
+        IrScoreOperationType, IrStatement,
     };
-    use crate::middle::format::types::GlobalName;
+    use crate::middle::format::types::{GlobalName, LOAD_FN_NAME};
     use camino::Utf8PathBuf;
     use std::collections::HashMap;
     use std::ops::Deref;
@@ -609,6 +1971,9 @@ mod tests {
     ) -> i32 {
         struct Vars {
             var_map: HashMap<Address, i32>,
+            // the score and the array held by each address of a frame
+            stack: Vec<Vec<(i32, Option<Vec<i32>>)>>,
+            arrays: HashMap<Address, Vec<i32>>,
         }
         impl Vars {
             fn get(&self, address: &Address) -> i32 {
@@ -628,6 +1993,37 @@ mod tests {
 
                 self.var_map.insert(address, value);
             }
+
+            fn element(&mut self, element: &IrArrayElement) -> &mut i32 {
+                let index = match &element.index {
+                    ArrayIndex::Const(i) => *i,
+                    ArrayIndex::Score(address) => self.get(address),
+                };
+                &mut self.arrays.get_mut(&element.array).unwrap()[index as usize]
+            }
+        }
+
+        fn check_cond(cond: &Cond, vars_ref: &Vars) -> bool {
+            match cond {
+                Cond::CheckVal(y) => {
+                    let a = vars_ref.get(&y.var_name);
+                    y.min <= a && a <= y.max
+                }
+                Cond::CompareVal(y) => {
+                    let a = vars_ref.get(&y.var_0);
+                    let b = vars_ref.get(&y.var_1);
+                    match y.op {
+                        CompareOp::Eq => a == b,
+                        CompareOp::Neq => a != b,
+                        CompareOp::Lt => a < b,
+                        CompareOp::Gt => a > b,
+                        CompareOp::Leq => a <= b,
+                        CompareOp::Geq => a >= b,
+                    }
+                }
+                Cond::Not(y) => !check_cond(y, vars_ref),
+                Cond::And(y) => y.iter().all(|cond| check_cond(cond, vars_ref)),
+            }
         }
 
         fn run_statements(
@@ -650,59 +2046,74 @@ mod tests {
                             IrScoreOperationType::Mul => left * right,
                             IrScoreOperationType::Div => left / right,
                             IrScoreOperationType::Mod => left % right,
-                            IrScoreOperationType::Eq => ((left != 0) == (right != 0)) as i32,
-                            IrScoreOperationType::Neq => ((left != 0) != (right != 0)) as i32,
-                            IrScoreOperationType::Lt => ((left != 0) < (right != 0)) as i32,
-                            IrScoreOperationType::Gt => ((left != 0) > (right != 0)) as i32,
-                            IrScoreOperationType::Leq => ((left != 0) <= (right != 0)) as i32,
-                            IrScoreOperationType::Geq => ((left != 0) >= (right != 0)) as i32,
-                            IrScoreOperationType::And => ((left != 0) && (right != 0)) as i32,
-                            IrScoreOperationType::Or => ((left != 0) || (right != 0)) as i32,
+                            IrScoreOperationType::Eq => (left == right) as i32,
+                            IrScoreOperationType::Neq => (left != right) as i32,
+                            IrScoreOperationType::Lt => (left < right) as i32,
+                            IrScoreOperationType::Gt => (left > right) as i32,
+                            IrScoreOperationType::Leq => (left <= right) as i32,
+                            IrScoreOperationType::Geq => (left >= right) as i32,
                             IrScoreOperationType::Assign => right,
                         };
                         vars_ref.insert(x.left.clone(), result);
                     }
-                    IrStatement::If(x) => match &x.cond {
-                        Cond::CheckVal(y) => {
-                            let a = vars_ref.get(&y.var_name);
-                            if (y.min <= a && a <= y.max) != x.invert {
-                                run_statements(
-                                    curr_fn_name,
-                                    &vec![(*x.body.deref()).clone()],
-                                    vars_ref,
-                                    functions,
-                                );
-                            }
-                        }
-                        Cond::CompareVal(y) => {
-                            let a = vars_ref.get(&y.var_0);
-                            let b = vars_ref.get(&y.var_1);
-                            if match y.op {
-                                CompareOp::Eq => a == b,
-                                CompareOp::Neq => a != b,
-                                CompareOp::Lt => a < b,
-                                CompareOp::Gt => a > b,
-                                CompareOp::Leq => a <= b,
-                                CompareOp::Geq => a >= b,
-                            } != x.invert
-                            {
-                                if run_statements(
-                                    curr_fn_name,
-                                    &vec![(*x.body.deref()).clone()],
-                                    vars_ref,
-                                    functions,
-                                ) {
-                                    return false;
-                                }
-                            }
+                    IrStatement::If(x) => {
+                        let cond = check_cond(&x.cond, vars_ref);
+                        // the body runs in the current function, so a return in it exits this function
+                        if cond != x.invert
+                            && run_statements(
+                                curr_fn_name,
+                                &vec![(*x.body.deref()).clone()],
+                                vars_ref,
+                                functions,
+                            )
+                        {
+                            return true;
                         }
-                    },
+                    }
                     IrStatement::Return => {
                         return true;
                     }
                     IrStatement::Block(x) => {
                         run_statements(&x.get_fn_name(), &x.statements, vars_ref, functions);
                     }
+                    IrStatement::PushFrame(x) => {
+                        // like a score that was never set, a missing value is stored as 0
+                        let frame = x
+                            .iter()
+                            .map(|address| {
+                                (
+                                    vars_ref.var_map.get(address).copied().unwrap_or(0),
+                                    vars_ref.arrays.get(address).cloned(),
+                                )
+                            })
+                            .collect();
+                        vars_ref.stack.push(frame);
+                    }
+                    IrStatement::PopFrame(x) => {
+                        let frame = vars_ref.stack.pop().unwrap();
+                        for (address, (value, array)) in x.iter().zip(frame) {
+                            vars_ref.insert(address.clone(), value);
+                            if let Some(array) = array {
+                                vars_ref.arrays.insert(address.clone(), array);
+                            }
+                        }
+                    }
+                    IrStatement::ArrayInit(x, len) => {
+                        vars_ref.arrays.insert(x.clone(), vec![0; *len as usize]);
+                    }
+                    IrStatement::StorageCopy(x, source) => {
+                        if let Some(array) = vars_ref.arrays.get(source).cloned() {
+                            vars_ref.arrays.insert(x.clone(), array);
+                        }
+                    }
+                    IrStatement::ArrayGet(x, address) => {
+                        let value = *vars_ref.element(x);
+                        vars_ref.insert(address.clone(), value);
+                    }
+                    IrStatement::ArraySet(x, address) => {
+                        let value = vars_ref.get(address);
+                        *vars_ref.element(x) = value;
+                    }
                     IrStatement::FnCall(x) => {
                         if &x.fn_name == curr_fn_name {
                             run_statements(&x.fn_name, statements, vars_ref, functions);
@@ -725,7 +2136,17 @@ mod tests {
 
         let mut vars = Vars {
             var_map: HashMap::new(),
+            stack: vec![],
+            arrays: HashMap::new(),
         };
+        if let Some(load_function) = functions.get(LOAD_FN_NAME) {
+            run_statements(
+                LOAD_FN_NAME,
+                &load_function.statements,
+                &mut vars,
+                functions,
+            );
+        }
         run_statements(
             "pkg/root/0_main",
             &functions.get("pkg/root/0_main").unwrap().statements,
@@ -746,42 +2167,746 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        match &program
+            .function_definitions
+            .get("pkg/root/0_main")
+            .unwrap()
+            .statements[0]
+        {
+            IrStatement::ScoreOperation(x) => {
+                assert_eq!(x.left.name, AddressOrigin::User("pkg/root/0_a".to_string()));
+                assert_eq!(
+                    x.right,
+                    Address {
+                        name: AddressOrigin::Const(1),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    }
+                );
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_simple_expression() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 8; let b: int = 2 * a / 8 + 9; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_b".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            11
+        );
+    }
+
+    #[test]
+    fn test_complicated_expression() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 2 * 18 / 9 * (6 - 8 * 3 % 3) + (true && (false || true) && !false) as int; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_a".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            25
+        );
+    }
+
+    #[test]
+    fn test_if_else() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 3; let r: int = 0; if (a == 1) { r = 1; } else if (a == 2) { r = 2; } else if (a == 3) { r = 3; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            3
+        );
+    }
+
+    #[test]
+    fn test_while() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 3; while (a < 10) { a+=1; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_a".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            10
+        );
+    }
+
+    #[test]
+    fn test_for() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { for (let a: int = 0; a < 10; a += 1) { } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_a".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            10
+        );
+    }
+
+    #[test]
+    fn test_return_from_if() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn check(a: int) -> int { if (a > 5) { return 1; } return 2; } pub fn main() { let r: int = check(7); let s: int = check(3); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            1
+        );
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_s".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            2
+        );
+    }
+
+    #[test]
+    fn test_return_from_while() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn find() -> int { let i: int = 0; while (i < 100) { if (i * i > 50) { return i; } i += 1; } return 0 - 1; } pub fn main() { let r: int = find(); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            8
+        );
+    }
+
+    #[test]
+    fn test_return_from_nested_for() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn find() -> int { for (let i: int = 1; i < 10; i += 1) { for (let j: int = 1; j < 10; j += 1) { if (i * j == 12) { return i * 10 + j; } } } return 0; } pub fn main() { let r: int = find(); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            26
+        );
+    }
+
+    #[test]
+    fn test_break_from_while() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let i: int = 0; while (i < 100) { if (i == 7) { break; } i += 1; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_i".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            7
+        );
+    }
+
+    #[test]
+    fn test_continue_in_for() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let s: int = 0; for (let i: int = 0; i < 10; i += 1) { if (i % 2 == 0) { continue; } s += i; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_s".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            25
+        );
+    }
+
+    #[test]
+    fn test_break_inner_loop() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let c: int = 0; for (let i: int = 0; i < 5; i += 1) { for (let j: int = 0; j < 5; j += 1) { if (j > i) { break; } c += 1; } } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_c".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            15
+        );
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let i: int = 0; let s: int = 0; while (i < 100) { i += 1; if (i < 5) { continue; } s += i; if (s > 20) { break; } } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_s".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            26
+        );
+    }
+
+    #[test]
+    fn test_top_level_break_and_continue() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let i: int = 0; let s: int = 0; while (i < 100) { i += 1; break; } for (let j: int = 0; j < 10; j += 1) { s += 1; continue; s += 100; } s += i; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_s".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            11
+        );
+    }
+
+    #[test]
+    fn test_increment_statements() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn f(a: int) -> int { a++; ++a; --a; return a; } pub fn main() { let r: int = f(5); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            6
+        );
+    }
+
+    #[test]
+    fn test_void_call_statement() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn set(v: int) { let x: int = v * 2; } pub fn main() { set(4); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_x".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            8
+        );
+    }
+
+    #[test]
+    fn test_discarded_expression_has_no_temporaries() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 0; a++; a * (a + 1); -a; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let main = program.function_definitions.get("pkg/root/0_main").unwrap();
+        assert_eq!(main.statements.len(), 2);
+        assert!(main.statements.iter().all(|statement| match statement {
+            IrStatement::ScoreOperation(x) =>
+                !matches!(x.left.name, AddressOrigin::CtxGenerated(_, _)),
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn test_pre_and_post_increment() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a: int = 5; let b: int = a++; let c: int = ++a; let d: int = a -- + 10; let e: int = --a * 2; a = a++; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [("a", 5), ("b", 5), ("c", 7), ("d", 17), ("e", 10)] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(format!("pkg/root/0_{name}")),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_assignment_reading_itself() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let x: int = 3; x = 1 - x; let y: int = 4; y = (y + 1) * y; let z: int = 6; z -= z; z += 7; z *= z; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [("x", -2), ("y", 20), ("z", 49)] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(format!("pkg/root/0_{name}")),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_short_circuit_conditions() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn f(a: int) -> int { return 10 / a; } pub fn main() { let a: int = 0; let r: int = 0; let s: int = 0; if (a != 0 && f(a) > 1) { r = 1; } if (a == 0 || f(a) > 1) { s = 2; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [("r", 0), ("s", 2)] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(format!("pkg/root/0_{name}")),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_short_circuit_values() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn f(a: int) -> int { return 10 / a; } pub fn main() { let a: int = 0; let b: bool = a != 0 && f(a) > 1; let c: bool = a == 0 || f(a) > 1; a = 5; let d: bool = a != 0 && f(a) > 1; let e: bool = a == 0 || f(a) > 3; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [("b", 0), ("c", 1), ("d", 1), ("e", 0)] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(format!("pkg/root/0_{name}")),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_short_circuit_statements() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn f(a: int) -> int { return 10 / a; } pub fn main() { let a: int = 0; let b: int = 0; a != 0 && f(a); a == 0 || f(a); b = 1; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert_eq!(
+            test_calculation(
+                "pkg/root/0_main",
+                &program.function_definitions,
+                &Address {
+                    name: AddressOrigin::User("pkg/root/0_b".to_string()),
+                    offset: 0,
+                    kind: AddressKind::Score,
+                },
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn test_negated_conditions() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let x: int = 4; let y: int = 1; let z: int = 0; let a: int = 0; let b: int = 0; let c: int = 0; if (!(x > 3 && y < 2) || z == 1) { a = 1; } if (!(x < 3 || y > 2)) { b = 1; } let p: bool = x > 3; let q: bool = z == 1; if (p) { c += 1; } if (q) { c += 10; } if (!q && p) { c += 100; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
 
-        match &program
-            .function_definitions
-            .get("pkg/root/0_main")
-            .unwrap()
-            .statements[0]
-        {
-            IrStatement::ScoreOperation(x) => {
-                assert_eq!(x.left.name, AddressOrigin::User("pkg/root/0_a".to_string()));
-                assert_eq!(
-                    x.right,
-                    Address {
-                        name: AddressOrigin::Const(1),
+        for (name, value) in [("a", 0), ("b", 1), ("c", 101)] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(format!("pkg/root/0_{name}")),
                         offset: 0,
-                    }
-                );
-            }
-            _ => {}
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
         }
     }
 
     #[test]
-    fn test_simple_expression() {
+    fn test_conjunction_in_loop_condition() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
         mock_file_system.insert_file(
             Utf8PathBuf::from("main.ing"),
-            "pub fn main() { let a: int = 8; let b: int = 2 * a / 8 + 9; }",
+            "pub fn main() { let i: int = 0; while (i < 10 && i * i < 50) { i++; } }",
         );
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -791,25 +2916,28 @@ mod tests {
                 "pkg/root/0_main",
                 &program.function_definitions,
                 &Address {
-                    name: AddressOrigin::User("pkg/root/0_b".to_string()),
+                    name: AddressOrigin::User("pkg/root/0_i".to_string()),
                     offset: 0,
+                    kind: AddressKind::Score,
                 },
             ),
-            11
+            8
         );
     }
 
     #[test]
-    fn test_complicated_expression() {
+    fn test_call_private_functions() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
         mock_file_system.insert_file(
             Utf8PathBuf::from("main.ing"),
-            "pub fn main() { let a: int = 2 * 18 / 9 * (6 - 8 * 3 % 3) + (true && (false || true) && !false); }",
+            "pub fn main() { let r: int = helper(3); } fn helper(a: int) -> int { return twice(a) + 1; } fn twice(b: int) -> int { return b * 2; }",
         );
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -819,25 +2947,110 @@ mod tests {
                 "pkg/root/0_main",
                 &program.function_definitions,
                 &Address {
-                    name: AddressOrigin::User("pkg/root/0_a".to_string()),
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
                     offset: 0,
+                    kind: AddressKind::Score,
                 },
             ),
-            25
+            7
         );
     }
 
     #[test]
-    fn test_if_else() {
+    fn test_global_variables() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
         mock_file_system.insert_file(
             Utf8PathBuf::from("main.ing"),
-            "pub fn main() { let a: int = 3; let r: int = 0; if (a == 1) { r = 1; } else if (a == 2) { r = 2; } else if (a == 3) { r = 3; } }",
+            "mod other; use root::other::counter; let z: int = 4; let a: int = z * 2 + 1; pub fn main() { bump(); bump(); let r: int = counter + a; } fn bump() { counter += 1; }",
+        );
+        mock_file_system.insert_file(Utf8PathBuf::from("other.ing"), "pub let counter: int = 3;");
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let mut functions = program.function_definitions.clone();
+        functions.insert(LOAD_FN_NAME.to_string(), program.load_function.clone());
+
+        for (name, value) in [
+            ("pkg/root/0_a", 9),
+            ("pkg/root/other/0_counter", 5),
+            ("pkg/root/0_r", 14),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &functions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_if_else() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn pick(a: int, b: int) -> int { let r: int = 0; if (a == 1) { if (b == 1) { r = 1; } else if (b == 2) { r = 2; } } else if (a == 2) { if (b == 1) { r = 3; } else { r = 4; } } else { r = 5; } return r; } \
+            pub fn main() { let r0: int = pick(1, 1); let r1: int = pick(1, 2); let r2: int = pick(1, 3); let r3: int = pick(2, 1); let r4: int = pick(2, 2); let r5: int = pick(3, 1); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [
+            ("pkg/root/0_r0", 1),
+            ("pkg/root/0_r1", 2),
+            ("pkg/root/0_r2", 0),
+            ("pkg/root/0_r3", 3),
+            ("pkg/root/0_r4", 4),
+            ("pkg/root/0_r5", 5),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_if_else_nested_in_loop() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let r: int = 0; for (let i: int = 0; i < 6; i += 1) { if (i < 3) { if (i == 0) { r += 1; } else if (i == 1) { r += 10; } else { if (r > 5) { r += 100; } else { r += 1000; } } } else { if (i == 3) { r += 10000; } } } }",
         );
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -849,23 +3062,26 @@ mod tests {
                 &Address {
                     name: AddressOrigin::User("pkg/root/0_r".to_string()),
                     offset: 0,
+                    kind: AddressKind::Score,
                 },
             ),
-            3
+            10111
         );
     }
 
     #[test]
-    fn test_while() {
+    fn test_recursive_fibonacci() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
         mock_file_system.insert_file(
             Utf8PathBuf::from("main.ing"),
-            "pub fn main() { let a: int = 3; while (a < 10) { a+=1; } }",
+            "rec fn fib(n: int) -> int { if (n < 2) { return n; } return fib(n - 1) + fib(n - 2); } pub fn main() { let r: int = fib(10); }",
         );
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
@@ -875,39 +3091,511 @@ mod tests {
                 "pkg/root/0_main",
                 &program.function_definitions,
                 &Address {
-                    name: AddressOrigin::User("pkg/root/0_a".to_string()),
+                    name: AddressOrigin::User("pkg/root/0_r".to_string()),
                     offset: 0,
+                    kind: AddressKind::Score,
                 },
             ),
-            10
+            55
+        );
+
+        // the first call saves only the parameter, the second also saves the result of the first call
+        let frames: Vec<Vec<AddressOrigin>> = program.function_definitions["pkg/root/0_fib"]
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                IrStatement::PushFrame(frame) => {
+                    Some(frame.iter().map(|address| address.name.clone()).collect())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            frames,
+            vec![
+                vec![AddressOrigin::User("pkg/root/0_n".to_string())],
+                vec![
+                    AddressOrigin::User("pkg/root/0_n".to_string()),
+                    AddressOrigin::CtxGenerated("pkg/root/0_fib".to_string(), 1)
+                ],
+            ]
         );
     }
 
     #[test]
-    fn test_for() {
+    fn test_detected_recursion() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
         mock_file_system.insert_file(
             Utf8PathBuf::from("main.ing"),
-            "pub fn main() { for (let a: int = 0; a < 10; a += 1) { } }",
+            "fn sum(n: int) -> int { let r: int = 0; for (let i: int = 1; i <= n; i += 1) { if (is_even(i)) { r += sum(i - 1); } else { r += i; } } return r; } \
+            fn is_even(n: int) -> bool { if (n == 0) { return true; } return is_odd(n - 1); } \
+            fn is_odd(n: int) -> bool { if (n == 0) { return false; } return is_even(n - 1); } \
+            pub fn main() { let r: int = sum(6); let e: bool = is_even(7); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        // only the calls that can reenter the caller save its frame
+        let main = program.function_definitions.get("pkg/root/0_main").unwrap();
+        assert!(!main
+            .statements
+            .iter()
+            .any(|x| matches!(x, IrStatement::PushFrame(_))));
+
+        for (name, value) in [("pkg/root/0_r", 30), ("pkg/root/0_e", 0)] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_call_arguments() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn sub(a: int, b: int) -> int { return a - b; } \
+            fn swap_sub(a: int, b: int, n: int) -> int { if (n == 0) { return a - b; } return swap_sub(b, a, n - 1); } \
+            fn chain(x: int) -> int { return x * 2 + sub(x, 1) * sub(x, 2); } \
+            pub fn main() { let r0: int = sub(10, sub(5, 2)); let r1: int = sub(sub(20, 1), sub(8, 2)); let r2: int = sub(1, 2) + sub(10, 3) * sub(5, 3); \
+            let r3: int = swap_sub(10, 3, 1); let r4: int = sub(sub(sub(9, 1), 2), sub(3, sub(2, 1))); let r5: int = chain(3); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [
+            ("pkg/root/0_r0", 7),
+            ("pkg/root/0_r1", 13),
+            ("pkg/root/0_r2", 13),
+            ("pkg/root/0_r3", -7),
+            ("pkg/root/0_r4", 4),
+            ("pkg/root/0_r5", 8),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_arguments_without_temporaries() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn sub(a: int, b: int) -> int { return a - b; } pub fn main() { let x: int = 4; let r: int = sub(x + 1, 2); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        // nothing can change the parameters once they are set, so they are computed in place
+        let main = program.function_definitions.get("pkg/root/0_main").unwrap();
+        assert!(!main.statements.iter().any(|x| matches!(
+            x,
+            IrStatement::ScoreOperation(op) if matches!(op.left.name, AddressOrigin::CtxGenerated(_, _))
+        )));
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let p: Point; p.x = 1; p.y = p.x + 2; p.x = p.y + 1; let q: Point = p; q.y = 10; \
+            let l: Line; l.a = q; l.b = p; l.b.x = l.a.y * 2; l.len = l.b.x - l.a.x; let c = l; \
+            let r0: int = c.a.x; let r1: int = c.a.y; let r2: int = c.b.x; let r3: int = c.b.y; let r4: int = c.len; let r5: int = p.x; } \
+            struct Point { x: int, y: int } struct Line { len: int, a: Point, b: Point }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, offset, value) in [
+            ("pkg/root/0_r0", 0, 4),
+            ("pkg/root/0_r1", 0, 10),
+            ("pkg/root/0_r2", 0, 20),
+            ("pkg/root/0_r3", 0, 3),
+            ("pkg/root/0_r4", 0, 16),
+            ("pkg/root/0_r5", 0, 4),
+            // the fields are laid out in the order of their names: a.x, a.y, b.x, b.y, len
+            ("pkg/root/0_c", 2, 20),
+            ("pkg/root/0_c", 4, 16),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_struct_methods() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "struct Point { x: int, y: int } \
+            impl Point { fn len(self) -> int { return self.x + self.y; } \
+            fn scaled(self, k: int) -> int { return self.len() * k; } \
+            fn sum(self, n: int) -> int { if (n < 1) { return 0; } return self.sum(n - 1) + self.x; } } \
+            struct Line { a: Point, b: Point } \
+            pub fn main() { let p: Point; p.x = 3; p.y = 4; let l: Line; l.b = p; l.b.y = 10; \
+            let r0: int = p.len(); let r1: int = p.scaled(2); let r2: int = l.b.len(); let r3: int = p.sum(3); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        assert!(program
+            .function_definitions
+            .contains_key("pkg/root/0_Point.len"));
+
+        for (name, value) in [
+            ("pkg/root/0_r0", 7),
+            ("pkg/root/0_r1", 14),
+            ("pkg/root/0_r2", 13),
+            ("pkg/root/0_r3", 9),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_methods_assign_self() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "struct Point { x: int, y: int } \
+            impl Point { fn set(self, x: int) { self.x = x; } \
+            fn count(self, n: int) { if (n < 1) { return; } self.y += 1; self.count(n - 1); } } \
+            struct Line { a: Point, b: Point } \
+            pub fn main() { let p: Point; p.x = 3; p.y = 4; let l: Line; l.b = p; \
+            p.set(5); p.count(3); l.b.set(8); let r0: int = p.x; let r1: int = p.y; let r2: int = l.b.x; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        // the fields of self are copied back to the receiver after the call
+        for (name, value) in [
+            ("pkg/root/0_r0", 5),
+            ("pkg/root/0_r1", 7),
+            ("pkg/root/0_r2", 8),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "struct Point { x: int, y: int } struct Line { a: Point, b: Point } \
+            fn mk(x: int) -> Point { return Point { x: x, y: x * 2 }; } \
+            fn add(p: Point, q: Point) -> Point { return Point { x: p.x + q.x, y: p.y + q.y }; } \
+            rec fn sum(p: Point, n: int) -> Point { if (n < 1) { return p; } \
+            return add(sum(Point { x: p.x, y: p.y + 1 }, n - 1), p); } \
+            pub fn main() { let p = mk(3); p = Point { y: p.x, x: p.y }; \
+            let l = Line { a: p, b: add(p, mk(1)) }; let s: Point = sum(mk(1), 2); \
+            let r0: int = p.x; let r1: int = p.y; let r2: int = l.a.x; let r3: int = l.b.x; let r4: int = l.b.y; \
+            let r5: int = s.x; let r6: int = s.y; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [
+            ("pkg/root/0_r0", 6),
+            ("pkg/root/0_r1", 3),
+            ("pkg/root/0_r2", 6),
+            ("pkg/root/0_r3", 7),
+            ("pkg/root/0_r4", 5),
+            // (1, 2) + (1, 3) + (1, 4)
+            ("pkg/root/0_r5", 3),
+            ("pkg/root/0_r6", 9),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_arrays() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn f(x: int) -> int { return x * 10; } \
+            pub fn main() { let xs: [int; 8]; let i: int = 0; \
+            while (i < xs.len()) { xs[i] = i * i; i++; } \
+            xs[0] = 5; xs[2] += xs[1] + f(xs[3]); xs[i - 1] -= 1; \
+            let j: int = 3; xs[j + 1] = xs[j - 1]; \
+            let r0: int = xs[0]; let r1: int = xs[2]; let r2: int = xs[7]; let r3: int = xs[4]; \
+            let r4: int = xs.len(); let r5: int = xs[5]; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [
+            ("pkg/root/0_r0", 5),
+            ("pkg/root/0_r1", 95),
+            ("pkg/root/0_r2", 48),
+            ("pkg/root/0_r3", 95),
+            ("pkg/root/0_r4", 8),
+            ("pkg/root/0_r5", 25),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+
+        // arrays are copied as a whole, and the arrays of a recursive call are part of its frame
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn f(n: int) -> int { let xs: [int; 2]; xs[0] = n; if (n > 0) { f(n - 1); } return xs[0]; } \
+            pub fn main() { let xs: [int; 3]; xs[1] = 4; let ys = xs; ys[1] = 7; \
+            let zs: [int; 3]; zs = ys; zs[1] += 1; \
+            let r0: int = xs[1]; let r1: int = ys[1]; let r2: int = zs[1]; let r3: int = f(3); }",
         );
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let program = front_program.export_program();
 
+        for (name, value) in [
+            ("pkg/root/0_r0", 4),
+            ("pkg/root/0_r1", 7),
+            ("pkg/root/0_r2", 8),
+            ("pkg/root/0_r3", 3),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "fn half(x: float) -> float { return x / 2.0; } \
+            pub fn main() { let a: float = 1.5; let b = a * 2.25 - 0.375; let c = half(b) + 0.1; \
+            a++; let d: double = -2.5d; let r0: int = b as int; let r1: int = (c * 100.0) as int; \
+            let r2: int = (a as int) * 10 + (a > 2.0) as int; let r3: int = (d * d) as int; \
+            let r4: int = ((7 as float) / 2.0 * 10.0) as int; let r5: bool = c == 1.55; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        for (name, value) in [
+            ("pkg/root/0_r0", 3),
+            ("pkg/root/0_r1", 160),
+            ("pkg/root/0_r2", 21),
+            ("pkg/root/0_r3", 6),
+            ("pkg/root/0_r4", 35),
+            ("pkg/root/0_r5", 0),
+        ] {
+            assert_eq!(
+                test_calculation(
+                    "pkg/root/0_main",
+                    &program.function_definitions,
+                    &Address {
+                        name: AddressOrigin::User(name.to_string()),
+                        offset: 0,
+                        kind: AddressKind::Score,
+                    },
+                ),
+                value
+            );
+        }
+
+        // with a scale of 100, 0.375 is rounded to 0.38 and halving 2.99 loses the last digit
+        front_program.float_scale = 100;
+        let program = front_program.export_program();
+
         assert_eq!(
             test_calculation(
                 "pkg/root/0_main",
                 &program.function_definitions,
                 &Address {
-                    name: AddressOrigin::User("pkg/root/0_a".to_string()),
+                    name: AddressOrigin::User("pkg/root/0_r1".to_string()),
                     offset: 0,
+                    kind: AddressKind::Score,
                 },
             ),
-            10
+            159
+        );
+    }
+
+    #[test]
+    fn test_increment_compound_field() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let c = { hp: 1 }; c.hp++; }",
         );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        // like `c.hp += 1`, the field is read into a score and stored back
+        let main = program.function_definitions.get("pkg/root/0_main").unwrap();
+        let [IrStatement::FieldGet(get, score), IrStatement::ScoreOperation(x), IrStatement::FieldSet(set, result)] =
+            &main.statements[1..]
+        else {
+            panic!("Expected the field to be read, incremented and stored");
+        };
+        assert_eq!(get.path, vec!["hp".to_string()]);
+        assert_eq!(get, set);
+        assert_eq!(score, result);
+        assert_eq!(&x.left, score);
+        assert_eq!(x.op, IrScoreOperationType::Add);
+        assert_eq!(x.right.name, AddressOrigin::Const(1));
     }
 }
@@ -1,9 +1,9 @@
-use crate::front::ast_types::{GlobalResolvedName, NamePath, Reference};
+use crate::front::ast_types::{GlobalResolvedName, NamePath, Reference, Type};
 use crate::front::exporter::convert::global_name_updater;
 use crate::front::mergers::definition_table::DefinitionTable;
-use crate::middle::format::ir_types::{Address, AddressOrigin};
+use crate::middle::format::ir_types::{Address, AddressKind, AddressOrigin, IrCompoundField};
 use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::rc::Rc;
 
 struct VarGenerator {
@@ -29,6 +29,13 @@ impl VarGenerator {
         }
     }
 
+    // the variables that have been handed out and not forfeited yet
+    fn live_vars(&self) -> Vec<u32> {
+        (0..self.next_var)
+            .filter(|var| !self.spare_vars.iter().any(|spare| spare.0 == *var))
+            .collect()
+    }
+
     fn forfeit_var(&mut self, var: u32) {
         self.spare_vars.push(Reverse(var));
 
@@ -50,21 +57,57 @@ impl ConstGenerator {
         }
     }
 
+    pub fn into_consts(self) -> HashSet<i32> {
+        self.consts
+    }
+
     pub fn get_const(&mut self, value: i32) -> Address {
         self.consts.insert(value);
         Address {
             name: AddressOrigin::Const(value),
             offset: 0,
+            kind: AddressKind::Score,
         }
     }
 }
 
+/// Values of the control variable, which tells the blocks above which kind of early exit is happening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFlow {
+    Return = 1,
+    Break = 2,
+    Continue = 3,
+}
+
+struct LoopFrame {
+    body_depth: usize,
+    split_body: bool,
+    breaks: usize,
+    continues: usize,
+}
+
+/// The breaks and continues of a loop that went through the control variable.
+pub struct LoopExits {
+    pub breaks: usize,
+    pub continues: usize,
+}
+
 pub struct Context<'a> {
     pub fn_name: String,
     pub block_count: usize,
     var_generator: VarGenerator,
     definition_table: &'a DefinitionTable<Rc<GlobalResolvedName>>,
     pub const_generator: &'a mut ConstGenerator,
+    block_depth: usize,
+    control_variable: Option<Address>,
+    control_exits: usize,
+    loops: Vec<LoopFrame>,
+    frame_calls: HashSet<Rc<GlobalResolvedName>>,
+    locals: Vec<Address>,
+    var_types: HashMap<Rc<GlobalResolvedName>, Type>,
+    temporary_types: HashMap<u32, Type>,
+    pub return_type: Type,
+    pub float_scale: i32,
 }
 
 impl Context<'_> {
@@ -72,6 +115,7 @@ impl Context<'_> {
         fn_name: &str,
         definition_table: &'a DefinitionTable<Rc<GlobalResolvedName>>,
         const_generator: &'a mut ConstGenerator,
+        float_scale: i32,
     ) -> Context<'a> {
         Context {
             fn_name: fn_name.to_string(),
@@ -79,7 +123,250 @@ impl Context<'_> {
             var_generator: VarGenerator::new(),
             definition_table,
             const_generator,
+            block_depth: 0,
+            control_variable: None,
+            control_exits: 0,
+            loops: vec![],
+            frame_calls: HashSet::new(),
+            locals: vec![],
+            var_types: HashMap::new(),
+            temporary_types: HashMap::new(),
+            return_type: Type::Void,
+            float_scale,
+        }
+    }
+
+    // calls to these functions may reenter the current one, so they are wrapped in a push and pop of its frame
+    pub fn set_frame_calls(&mut self, frame_calls: HashSet<Rc<GlobalResolvedName>>) {
+        self.frame_calls = frame_calls;
+    }
+
+    pub fn saves_frame(&self, function_name: &Rc<GlobalResolvedName>) -> bool {
+        self.frame_calls.contains(function_name)
+    }
+
+    pub fn has_frame_calls(&self) -> bool {
+        !self.frame_calls.is_empty()
+    }
+
+    // registers the type of a parameter or local, every slot of it is part of the frame
+    pub fn declare_variable(&mut self, var_name: &Reference, type_: Type) {
+        let size = self.get_type_size(&type_);
+        self.var_types
+            .insert(Rc::clone(var_name.global_resolved.as_ref().unwrap()), type_);
+
+        let address = self.convert_var_name(var_name);
+        for offset in 0..size {
+            let slot = address.with_offset(offset);
+            if !self.locals.contains(&slot) {
+                self.locals.push(slot);
+            }
+        }
+    }
+
+    // variables that are neither declared in this function nor typed are ints
+    pub fn get_var_type(&self, var_name: &Reference) -> Type {
+        let global_resolved_name = var_name.global_resolved.as_ref().unwrap();
+        if let Some(type_) = self.var_types.get(global_resolved_name) {
+            return type_.clone();
+        }
+
+        self.definition_table
+            .global_var_definitions
+            .get(global_resolved_name)
+            .and_then(|x| x.var_def.type_.clone())
+            .unwrap_or(Type::Int)
+    }
+
+    // the fields of a struct are laid out in the order of their names
+    fn get_struct_fields(&self, struct_name: &Reference) -> Vec<(&String, &Type)> {
+        let struct_def = self
+            .definition_table
+            .struct_definitions
+            .get(struct_name.global_resolved.as_ref().unwrap())
+            .unwrap();
+
+        let mut fields: Vec<(&String, &Type)> = struct_def.map.iter().collect();
+        if fields.iter().any(|(_, type_)| **type_ == Type::String) {
+            panic!("Not implemented"); // TODO: strings in structs
+        }
+        fields.sort_by_key(|(name, _)| *name);
+        fields
+    }
+
+    // the fields of a struct with their offsets, in the order they are laid out
+    pub fn get_field_offsets(&self, struct_name: &Reference) -> Vec<(String, i32, Type)> {
+        let mut offset = 0;
+        self.get_struct_fields(struct_name)
+            .into_iter()
+            .map(|(name, type_)| {
+                let field = (name.clone(), offset, type_.clone());
+                offset += self.get_type_size(type_);
+                field
+            })
+            .collect()
+    }
+
+    // strings and arrays are held in storage, anything else in scores
+    pub fn get_address_kind(&self, type_: &Type) -> AddressKind {
+        match type_ {
+            Type::String | Type::Array(_, _) | Type::Compound(_) => AddressKind::Storage,
+            _ => AddressKind::Score,
+        }
+    }
+
+    // the number of slots a value of the type takes up, an array is a single storage path
+    pub fn get_type_size(&self, type_: &Type) -> i32 {
+        match type_ {
+            Type::Struct(struct_name) => self
+                .get_struct_fields(struct_name)
+                .iter()
+                .map(|(_, field_type)| self.get_type_size(field_type))
+                .sum(),
+            _ => 1,
+        }
+    }
+
+    // the offset of the field the path points to and its type
+    fn resolve_path(&self, name_path: &NamePath) -> (i32, Type) {
+        let mut offset = 0;
+        let mut type_ = self.get_var_type(&name_path.name);
+
+        for field in &name_path.path {
+            // the fields of a compound are part of its storage path, so they have no offset
+            if let Type::Compound(fields) = &type_ {
+                type_ = fields
+                    .get(field)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("{} has no field {field}", name_path.name.raw));
+                continue;
+            }
+            let Type::Struct(struct_name) = &type_ else {
+                panic!("{} is not a struct", name_path.name.raw);
+            };
+
+            let mut field_type = None;
+            for (name, t) in self.get_struct_fields(struct_name) {
+                if name == field {
+                    field_type = Some(t.clone());
+                    break;
+                }
+                offset += self.get_type_size(t);
+            }
+
+            type_ =
+                field_type.unwrap_or_else(|| panic!("{} has no field {field}", struct_name.raw));
+        }
+
+        (offset, type_)
+    }
+
+    pub fn get_name_path_type(&self, name_path: &NamePath) -> Type {
+        self.resolve_path(name_path).1
+    }
+
+    /*
+    The frame holds the parameters and locals declared so far and the temporaries that are in use. Anything
+    declared later can not hold a value the caller still needs. The temporaries that are not live at the call
+    are left out once the function is converted.
+     */
+    pub fn get_frame(&self) -> Vec<Address> {
+        let mut frame = self.locals.clone();
+        for var in self.var_generator.live_vars() {
+            let type_ = self.temporary_types.get(&var).unwrap_or(&Type::Int);
+            let kind = self.get_address_kind(type_);
+            frame.extend((0..self.get_type_size(type_)).map(|offset| Address {
+                name: AddressOrigin::CtxGenerated(self.fn_name.clone(), var),
+                offset,
+                kind: kind.clone(),
+            }));
+        }
+        frame
+    }
+
+    pub fn enter_block(&mut self) {
+        self.block_depth += 1;
+    }
+
+    pub fn exit_block(&mut self) {
+        self.block_depth -= 1;
+    }
+
+    // statements inside a nested block are emitted into their own function, so `return` only exits that block
+    pub fn is_nested(&self) -> bool {
+        self.block_depth > 0
+    }
+
+    pub fn get_control_variable(&mut self) -> Address {
+        if let Some(address) = &self.control_variable {
+            return address.clone();
         }
+
+        let address = self.get_variable();
+        self.control_variable = Some(address.clone());
+        address
+    }
+
+    pub fn used_control_variable(&self) -> Option<&Address> {
+        self.control_variable.as_ref()
+    }
+
+    pub fn record_control_exit(&mut self) {
+        self.control_exits += 1;
+    }
+
+    pub fn control_exits(&self) -> usize {
+        self.control_exits
+    }
+
+    // must be called at the depth of the loop body, split_body is set when the body has its own function
+    pub fn enter_loop(&mut self, split_body: bool) {
+        self.loops.push(LoopFrame {
+            body_depth: self.block_depth,
+            split_body,
+            breaks: 0,
+            continues: 0,
+        });
+    }
+
+    pub fn exit_loop(&mut self) -> LoopExits {
+        let frame = self.loops.pop().unwrap();
+        LoopExits {
+            breaks: frame.breaks,
+            continues: frame.continues,
+        }
+    }
+
+    pub fn is_in_loop(&self) -> bool {
+        !self.loops.is_empty()
+    }
+
+    /*
+    A break or continue at the top of the loop body can simply return: returning from the body function
+    continues the loop, and returning from the loop function (which calls itself last) ends it.
+    Anything deeper has to go through the control variable.
+     */
+    pub fn loop_exit_needs_control(&self, control_flow: ControlFlow) -> bool {
+        let frame = self.loops.last().unwrap();
+        if frame.body_depth != self.block_depth {
+            return true;
+        }
+        control_flow == ControlFlow::Break && frame.split_body
+    }
+
+    pub fn record_loop_exit(&mut self, control_flow: ControlFlow) {
+        let frame = self.loops.last_mut().unwrap();
+        match control_flow {
+            ControlFlow::Break => frame.breaks += 1,
+            ControlFlow::Continue => frame.continues += 1,
+            ControlFlow::Return => panic!("Return does not exit a loop"),
+        }
+        self.control_exits += 1;
+    }
+
+    // the loop consumes its own breaks and continues, so the blocks above it do not need to check for them
+    pub fn consume_loop_exits(&mut self, loop_exits: &LoopExits) {
+        self.control_exits -= loop_exits.breaks + loop_exits.continues;
     }
 
     pub fn use_block(&mut self) -> usize {
@@ -98,51 +385,120 @@ impl Context<'_> {
             .function_definitions
             .get(function_name)
             .unwrap();
+        // the parameter is not declared in this function, so its type is taken from the definition
         self.convert_var_name(&fn_.args[index as usize].name)
+            .with_kind(self.get_address_kind(&self.get_parameter_type(function_name, index)))
+    }
+
+    // parameters without a type are ints
+    pub fn get_parameter_type(&self, function_name: &Rc<GlobalResolvedName>, index: u32) -> Type {
+        let fn_ = self
+            .definition_table
+            .function_definitions
+            .get(function_name)
+            .unwrap();
+        fn_.args[index as usize].type_.clone().unwrap_or(Type::Int)
+    }
+
+    pub fn get_return_type(&self, function_name: &Rc<GlobalResolvedName>) -> Type {
+        self.definition_table
+            .function_definitions
+            .get(function_name)
+            .unwrap()
+            .return_type
+            .clone()
+    }
+
+    // the methods of a struct are defined next to it as `<struct>.<method>`
+    pub fn get_method_name(&self, receiver: &NamePath, method: &str) -> Rc<GlobalResolvedName> {
+        let Type::Struct(struct_name) = self.get_name_path_type(receiver) else {
+            panic!("{} is not a struct", receiver.name.raw);
+        };
+        let struct_global_name = struct_name.global_resolved.as_ref().unwrap();
+
+        let method_name = GlobalResolvedName {
+            package: Rc::clone(&struct_global_name.package),
+            module: Rc::clone(&struct_global_name.module),
+            name: format!("{}.{method}", struct_global_name.name),
+        };
+        match self
+            .definition_table
+            .function_definitions
+            .get_key_value(&method_name)
+        {
+            Some((name, _)) => Rc::clone(name),
+            None => panic!("{} has no method {method}", struct_name.raw),
+        }
     }
 
     pub fn get_variable(&mut self) -> Address {
         Address {
             name: AddressOrigin::CtxGenerated(self.fn_name.clone(), self.var_generator.get_var()),
             offset: 0,
+            kind: AddressKind::Score,
         }
     }
 
+    // a temporary that can hold a value of the type, a struct takes up the scores after it as well
+    pub fn get_variable_of_type(&mut self, type_: &Type) -> Address {
+        let address = self.get_variable().with_kind(self.get_address_kind(type_));
+        if let AddressOrigin::CtxGenerated(_, var) = &address.name {
+            self.temporary_types.insert(*var, type_.clone());
+        }
+        address
+    }
+
     pub fn forfeit_variable(&mut self, address: &Address) {
         if let AddressOrigin::CtxGenerated(fn_name, var) = &address.name {
             if *fn_name != self.fn_name {
                 panic!("Cannot forfeit variable from another function"); // TODO: make this not panic
             }
 
+            self.temporary_types.remove(var);
             self.var_generator.forfeit_var(*var);
         }
     }
 
-    pub fn get_if_variable(&mut self) -> Address {
-        Address {
-            name: AddressOrigin::If,
-            offset: 0,
-        }
-    }
-
     pub fn get_return_variable(&mut self) -> Address {
         Address {
             name: AddressOrigin::Return,
             offset: 0,
+            kind: AddressKind::Score,
         }
     }
 
+    pub fn get_return_variable_of_type(&mut self, type_: &Type) -> Address {
+        self.get_return_variable()
+            .with_kind(self.get_address_kind(type_))
+    }
+
     pub fn convert_var_name(&self, var_name: &Reference) -> Address {
         let global_resolved_name = var_name.global_resolved.as_ref().unwrap();
 
         Address {
             name: AddressOrigin::User(global_name_updater(&global_resolved_name)),
             offset: 0,
+            kind: self.get_address_kind(&self.get_var_type(var_name)),
         }
     }
 
+    // the address of the first score of the field, the following fields of a struct come after it
+    // `c.pos.x` is a field of the compound `c`, which is not an address of its own
+    pub fn convert_compound_field(&self, name_path: &NamePath) -> Option<IrCompoundField> {
+        if name_path.path.is_empty()
+            || !matches!(self.get_var_type(&name_path.name), Type::Compound(_))
+        {
+            return None;
+        }
+
+        Some(IrCompoundField {
+            compound: self.convert_var_name(&name_path.name),
+            path: name_path.path.clone(),
+        })
+    }
+
     pub fn convert_name_path(&self, name_path: &NamePath) -> Address {
-        // TODO: does not work for structs yet
-        self.convert_var_name(&name_path.name)
+        let (offset, _) = self.resolve_path(name_path);
+        self.convert_var_name(&name_path.name).with_offset(offset)
     }
 }
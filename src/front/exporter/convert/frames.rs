@@ -0,0 +1,194 @@
+use crate::middle::format::ir_types::{
+    Address, AddressOrigin, ArrayIndex, Cond, IrArrayElement, IrScoreOperationType, IrStatement,
+};
+use std::collections::HashMap;
+
+type Slot = (AddressOrigin, i32);
+
+fn slot(address: &Address) -> Slot {
+    (address.name.clone(), address.offset)
+}
+
+// the positions of the statements that read and write every address, counted in the order they are written
+#[derive(Default)]
+struct Accesses {
+    position: usize,
+    // the position of the statement that is collected
+    current: usize,
+    reads: HashMap<Slot, Vec<usize>>,
+    writes: HashMap<Slot, Vec<usize>>,
+    // the first and last position of every loop
+    loops: Vec<(usize, usize)>,
+    // the positions a frame is pushed and popped at
+    frames: Vec<(usize, usize, Vec<Address>)>,
+}
+
+impl Accesses {
+    fn read(&mut self, address: &Address) {
+        self.reads
+            .entry(slot(address))
+            .or_default()
+            .push(self.current);
+    }
+
+    fn write(&mut self, address: &Address) {
+        self.writes
+            .entry(slot(address))
+            .or_default()
+            .push(self.current);
+    }
+
+    fn read_element(&mut self, element: &IrArrayElement) {
+        self.read(&element.array);
+        if let ArrayIndex::Score(index) = &element.index {
+            self.read(index);
+        }
+    }
+
+    fn read_cond(&mut self, cond: &Cond) {
+        match cond {
+            Cond::CheckVal(x) => self.read(&x.var_name),
+            Cond::CompareVal(x) => {
+                self.read(&x.var_0);
+                self.read(&x.var_1);
+            }
+            Cond::Not(x) => self.read_cond(x),
+            Cond::And(x) => x.iter().for_each(|cond| self.read_cond(cond)),
+        }
+    }
+
+    fn collect(&mut self, statements: &[IrStatement]) {
+        let mut pushed = vec![];
+        for statement in statements {
+            let position = self.position;
+            self.current = position;
+            self.position += 1;
+
+            match statement {
+                IrStatement::ScoreOperation(x) => {
+                    self.read(&x.right);
+                    if x.op != IrScoreOperationType::Assign {
+                        self.read(&x.left);
+                    }
+                    self.write(&x.left);
+                }
+                IrStatement::If(x) => {
+                    self.read_cond(&x.cond);
+                    self.collect(std::slice::from_ref(&x.body));
+                }
+                IrStatement::Block(x) => {
+                    self.collect(&x.statements);
+                    // a loop calls itself last, so everything in it also comes after itself
+                    if matches!(x.statements.last(), Some(IrStatement::FnCall(call)) if call.fn_name == x.get_fn_name())
+                    {
+                        self.loops.push((position, self.position));
+                    }
+                }
+                IrStatement::PushFrame(_) => pushed.push(position),
+                IrStatement::PopFrame(frame) => {
+                    let push = pushed.pop().unwrap();
+                    self.frames.push((push, position, frame.clone()));
+                }
+                IrStatement::ArrayInit(address, _) | IrStatement::StorageSet(address, _) => {
+                    self.write(address)
+                }
+                IrStatement::ArrayGet(element, address) => {
+                    self.read_element(element);
+                    self.write(address);
+                }
+                IrStatement::ArraySet(element, address) => {
+                    self.read(address);
+                    self.read_element(element);
+                    self.write(&element.array);
+                }
+                IrStatement::StorageCopy(address, source) => {
+                    self.read(source);
+                    self.write(address);
+                }
+                IrStatement::FieldGet(field, address) => {
+                    self.read(&field.compound);
+                    self.write(address);
+                }
+                IrStatement::FieldSet(field, address) => {
+                    self.read(address);
+                    self.read(&field.compound);
+                    self.write(&field.compound);
+                }
+                IrStatement::Print(addresses) => addresses.iter().for_each(|a| self.read(a)),
+                IrStatement::FnCall(_) | IrStatement::Return => {}
+            }
+        }
+    }
+
+    /*
+    A temporary only has to be saved if it is written before the frame is pushed and read after it is popped.
+    A loop around the call runs again, so the rest of the loop counts as both before and after the call.
+     */
+    fn is_live(&self, address: &Address, push: usize, pop: usize) -> bool {
+        let loops: Vec<&(usize, usize)> = self
+            .loops
+            .iter()
+            .filter(|(start, end)| *start <= push && pop < *end)
+            .collect();
+        let empty = vec![];
+        let writes = self.writes.get(&slot(address)).unwrap_or(&empty);
+        let reads = self.reads.get(&slot(address)).unwrap_or(&empty);
+
+        let written = writes
+            .iter()
+            .any(|w| *w < push || loops.iter().any(|(_, end)| pop < *w && *w < *end));
+        let read = reads
+            .iter()
+            .any(|r| *r > pop || loops.iter().any(|(start, _)| *start <= *r && *r < push));
+        written && read
+    }
+}
+
+fn replace_frames(
+    statements: &mut [IrStatement],
+    position: &mut usize,
+    frames: &HashMap<usize, Vec<Address>>,
+) {
+    for statement in statements {
+        if let Some(frame) = frames.get(position) {
+            match statement {
+                IrStatement::PushFrame(x) | IrStatement::PopFrame(x) => *x = frame.clone(),
+                _ => {}
+            }
+        }
+        *position += 1;
+
+        match statement {
+            IrStatement::If(x) => {
+                replace_frames(std::slice::from_mut(x.body.as_mut()), position, frames)
+            }
+            IrStatement::Block(x) => replace_frames(&mut x.statements, position, frames),
+            _ => {}
+        }
+    }
+}
+
+/*
+The frames are made while the function is converted, when it is not known yet which of the temporaries in use
+are read after the call. Once it is converted, the temporaries that are not live at the call are left out.
+ */
+pub fn prune_frames(statements: &mut [IrStatement]) {
+    let mut accesses = Accesses::default();
+    accesses.collect(statements);
+
+    let mut frames = HashMap::new();
+    for (push, pop, frame) in &accesses.frames {
+        let frame: Vec<Address> = frame
+            .iter()
+            .filter(|address| {
+                !matches!(address.name, AddressOrigin::CtxGenerated(_, _))
+                    || accesses.is_live(address, *push, *pop)
+            })
+            .cloned()
+            .collect();
+        frames.insert(*push, frame.clone());
+        frames.insert(*pop, frame);
+    }
+
+    replace_frames(statements, &mut 0, &frames);
+}
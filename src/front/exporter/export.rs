@@ -1,19 +1,37 @@
-use crate::front::ast_types::GlobalResolvedName;
+use crate::front::ast_types::{
+    AtomicExpression, Expression, ExpressionEnum, FnMod, GlobalResolvedName, VarDecl,
+};
 use crate::front::exporter::convert::context::ConstGenerator;
-use crate::front::exporter::convert::{convert_fn, global_name_updater};
+use crate::front::exporter::convert::{
+    convert_fn, convert_global_initializers, global_name_updater,
+};
 use crate::front::mergers::definition_table::DefinitionTable;
-use crate::middle::format::types::Program;
+use crate::middle::format::ir_types::IrStatement;
+use crate::middle::format::types::{GlobalName, Program};
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
+// floats are held in scores as fixed-point numbers, 1.0 is stored as the scale
+pub static DEFAULT_FLOAT_SCALE: i32 = 1000;
+
 #[derive(Debug)]
 pub struct FrontProgram {
     pub public_functions: HashSet<Rc<GlobalResolvedName>>,
     pub definitions: DefinitionTable<Rc<GlobalResolvedName>>,
+    pub float_scale: i32,
 }
 
 impl FrontProgram {
     pub fn export_program(&self) -> Program {
+        let mut const_generator = ConstGenerator::new();
+
+        let load_function = convert_global_initializers(
+            &self.global_initialization_order(),
+            &self.definitions,
+            &mut const_generator,
+            self.float_scale,
+        );
+
         let mut program = Program {
             public_functions: self
                 .public_functions
@@ -21,19 +39,255 @@ impl FrontProgram {
                 .map(|x| global_name_updater(x))
                 .collect(),
             function_definitions: HashMap::new(),
+            constants: HashSet::new(),
+            load_function,
         };
 
-        let mut const_generator = ConstGenerator::new();
+        // functions are only referred to by their global name in the IR
+        let function_names: HashMap<GlobalName, &Rc<GlobalResolvedName>> = self
+            .definitions
+            .function_definitions
+            .keys()
+            .map(|x| (global_name_updater(x), x))
+            .collect();
+
+        // convert every function that can be reached from the public functions or the global initialisers
+        let mut called = vec![];
+        collect_fn_calls(&program.load_function.statements, &mut called);
+
+        let mut queue: Vec<&Rc<GlobalResolvedName>> = self.public_functions.iter().collect();
+        queue.extend(called.iter().filter_map(|x| function_names.get(x).copied()));
+        while let Some(function) = queue.pop() {
+            let global_name = global_name_updater(function);
+            if program.function_definitions.contains_key(&global_name) {
+                continue;
+            }
+
+            if let Some(fn_) = self.definitions.function_definitions.get(function) {
+                let fn_def = convert_fn(
+                    fn_,
+                    &self.definitions,
+                    HashSet::new(),
+                    &mut const_generator,
+                    self.float_scale,
+                );
+
+                let mut called = vec![];
+                collect_fn_calls(&fn_def.statements, &mut called);
+                queue.extend(called.iter().filter_map(|x| function_names.get(x).copied()));
+
+                program.function_definitions.insert(global_name, fn_def);
+            }
+        }
 
-        for public_function in &self.public_functions {
-            if let Some(fn_) = self.definitions.function_definitions.get(public_function) {
+        /*
+        Methods are only resolved once the functions are converted, so the calls are taken from the IR. A
+        function that can reach itself is converted again, saving its frame around the calls that can reenter it.
+         */
+        let call_graph: HashMap<&Rc<GlobalResolvedName>, Vec<&Rc<GlobalResolvedName>>> = program
+            .function_definitions
+            .iter()
+            .map(|(name, fn_def)| {
+                let mut called = vec![];
+                collect_fn_calls(&fn_def.statements, &mut called);
+                (
+                    function_names[name],
+                    called
+                        .iter()
+                        .filter_map(|x| function_names.get(x).copied())
+                        .collect(),
+                )
+            })
+            .collect();
+
+        for (function, callees) in &call_graph {
+            let fn_ = &self.definitions.function_definitions[*function];
+            let frame_calls: HashSet<Rc<GlobalResolvedName>> = if fn_.mods.contains(&FnMod::Rec) {
+                callees.iter().map(|x| Rc::clone(x)).collect()
+            } else {
+                callees
+                    .iter()
+                    .filter(|x| reaches(&call_graph, x, function))
+                    .map(|x| Rc::clone(x))
+                    .collect()
+            };
+
+            if !frame_calls.is_empty() {
                 program.function_definitions.insert(
-                    global_name_updater(&public_function),
-                    convert_fn(fn_, &self.definitions, &mut const_generator),
+                    global_name_updater(function),
+                    convert_fn(
+                        fn_,
+                        &self.definitions,
+                        frame_calls,
+                        &mut const_generator,
+                        self.float_scale,
+                    ),
                 );
             }
         }
 
+        program.constants = const_generator.into_consts();
+
         program
     }
+
+    /*
+    Globals are initialised in the order of their names, except that a global is always initialised after
+    the globals its initialiser reads.
+     */
+    fn global_initialization_order(&self) -> Vec<&VarDecl> {
+        let mut names: Vec<&Rc<GlobalResolvedName>> =
+            self.definitions.global_var_definitions.keys().collect();
+        names.sort_by_key(|x| global_name_updater(x));
+
+        let mut visited = HashSet::new();
+        let mut order = vec![];
+        for name in names {
+            self.rec_global_initialization_order(name, &mut visited, &mut order);
+        }
+        order
+    }
+
+    fn rec_global_initialization_order<'a>(
+        &'a self,
+        name: &Rc<GlobalResolvedName>,
+        visited: &mut HashSet<Rc<GlobalResolvedName>>,
+        order: &mut Vec<&'a VarDecl>,
+    ) {
+        if !visited.insert(Rc::clone(name)) {
+            return;
+        }
+
+        let var_decl = self.definitions.global_var_definitions.get(name).unwrap();
+        if let Some(expr) = &var_decl.expr {
+            let mut read = vec![];
+            collect_variables(expr, &mut read);
+            for name in read {
+                if self.definitions.global_var_definitions.contains_key(name) {
+                    self.rec_global_initialization_order(name, visited, order);
+                }
+            }
+        }
+        order.push(var_decl);
+    }
+}
+
+fn collect_variables<'a>(expr: &'a Expression, read: &mut Vec<&'a Rc<GlobalResolvedName>>) {
+    match &expr.expr {
+        ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x)) => {
+            read.push(x.name.global_resolved.as_ref().unwrap());
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(x)) => {
+            if let Some(receiver) = &x.receiver {
+                read.push(receiver.name.global_resolved.as_ref().unwrap());
+            }
+            for arg in &x.args {
+                collect_variables(arg, read);
+            }
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::StructLiteral(x)) => {
+            for field in x.fields.values() {
+                collect_variables(field, read);
+            }
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Index(x)) => {
+            read.push(x.array.name.global_resolved.as_ref().unwrap());
+            collect_variables(&x.index, read);
+        }
+        ExpressionEnum::AtomicExpression(AtomicExpression::Literal(_)) => {}
+        ExpressionEnum::Unary(_, e) => collect_variables(e, read),
+        ExpressionEnum::Binary(e0, _, e1) => {
+            collect_variables(e0, read);
+            collect_variables(e1, read);
+        }
+    }
+}
+
+// whether `from` can end up calling `to`
+fn reaches(
+    call_graph: &HashMap<&Rc<GlobalResolvedName>, Vec<&Rc<GlobalResolvedName>>>,
+    from: &Rc<GlobalResolvedName>,
+    to: &Rc<GlobalResolvedName>,
+) -> bool {
+    let mut visited = HashSet::new();
+    let mut queue = vec![from];
+    while let Some(function) = queue.pop() {
+        if function == to {
+            return true;
+        }
+        if visited.insert(function) {
+            if let Some(called) = call_graph.get(function) {
+                queue.extend(called.iter().copied());
+            }
+        }
+    }
+    false
+}
+
+fn collect_fn_calls(statements: &[IrStatement], called: &mut Vec<GlobalName>) {
+    for statement in statements {
+        match statement {
+            IrStatement::FnCall(x) => called.push(x.fn_name.clone()),
+            IrStatement::If(x) => collect_fn_calls(std::slice::from_ref(&x.body), called),
+            IrStatement::Block(x) => collect_fn_calls(&x.statements, called),
+            IrStatement::ScoreOperation(_)
+            | IrStatement::Return
+            | IrStatement::PushFrame(_)
+            | IrStatement::PopFrame(_)
+            | IrStatement::ArrayInit(_, _)
+            | IrStatement::ArrayGet(_, _)
+            | IrStatement::ArraySet(_, _)
+            | IrStatement::StorageSet(_, _)
+            | IrStatement::StorageCopy(_, _)
+            | IrStatement::FieldGet(_, _)
+            | IrStatement::FieldSet(_, _)
+            | IrStatement::Print(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::front::file_system::fs::FileSystem;
+    use crate::front::file_system::mock_fs::MockFileSystem;
+    use crate::front::mergers::program::ProgramMerger;
+    use camino::Utf8PathBuf;
+
+    #[test]
+    fn test_export_reachable_functions() {
+        let mut mock_fs_0 = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs_0.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "mod test; use root::test::example::a; use library::libfunc; pub fn main() { helper(); a(); libfunc(); } fn helper() {} fn unused() {}",
+        );
+        mock_fs_0.insert_file(Utf8PathBuf::from("test.ing"), "pub mod example;");
+        mock_fs_0.insert_dir(Utf8PathBuf::from("test"));
+        mock_fs_0.insert_file(
+            Utf8PathBuf::from("test/example.ing"),
+            "pub fn a() { b(); } fn b() {}",
+        );
+
+        let mut mock_fs_1 = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs_1.insert_file(Utf8PathBuf::from("main.ing"), "pub fn libfunc() {}");
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs_0).unwrap();
+        program_merger.read_package("library", mock_fs_1).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let program = front_program.export_program();
+
+        let mut exported: Vec<&String> = program.function_definitions.keys().collect();
+        exported.sort();
+        assert_eq!(
+            exported,
+            vec![
+                "library/root/0_libfunc",
+                "test/root/0_helper",
+                "test/root/0_main",
+                "test/root/test/example/0_a",
+                "test/root/test/example/0_b",
+            ]
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use crate::front::ast_retriever::retriever::FileRetriever;
 use crate::front::mergers::package::module_resolution::merged_module::MergedModule;
-use crate::front::mergers::package::module_resolution::module_merger::ModuleMerger;
+use crate::front::mergers::package::module_resolution::module_merger::{
+    ModuleMergeResult, ModuleMerger,
+};
 
 mod module_resolution;
 
@@ -23,29 +25,45 @@ impl<T> Packager<T> {
         }
     }
 
-    pub fn merge_modules(&mut self) -> Package {
+    pub fn merge_modules(&mut self) -> ModuleMergeResult<Package> {
         let mut module_merger = ModuleMerger::new(&self.package_name);
-        let merged_module = module_merger
-            .merge_modules(self.retriever.take().unwrap().modules) //TODO: don't use unwrap
-            .unwrap();
+        let merged_module = module_merger.merge_modules(self.retriever.take().unwrap().modules)?;
 
-        Package { merged_module }
+        Ok(Package { merged_module })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::front::ast_types::visitor::{ClearSpans, Visitable};
     use crate::front::ast_types::Type::Void;
     use crate::front::ast_types::{
         AtomicExpression, Block, Expression, ExpressionEnum, FnCall, FnDef, GlobalResolvedName,
-        Reference, Statement,
+        Reference, Span, Statement,
     };
+    use crate::front::diagnostics::Diagnostic;
     use crate::front::file_system::fs::FileSystem;
     use crate::front::file_system::mock_fs::MockFileSystem;
+    use crate::front::mergers::definition_table::DefinitionTable;
     use camino::Utf8PathBuf;
     use std::rc::Rc;
 
+    // the functions of the merged package, compared without their spans
+    fn private_definitions<T>(
+        program: &mut Packager<T>,
+    ) -> DefinitionTable<Rc<GlobalResolvedName>> {
+        let mut definition_table = program
+            .merge_modules()
+            .unwrap()
+            .merged_module
+            .private_definitions;
+        for fn_def in definition_table.function_definitions.values_mut() {
+            fn_def.visit(&mut ClearSpans).unwrap();
+        }
+        definition_table
+    }
+
     #[test]
     fn test_parse_files() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
@@ -54,9 +72,9 @@ mod tests {
         mock_file_system.insert_dir(Utf8PathBuf::from("test"));
         mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
 
-        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system));
+        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system).unwrap());
 
-        let definition_table = program.merge_modules().merged_module.private_definitions;
+        let definition_table = private_definitions(&mut program);
 
         assert_eq!(definition_table.function_definitions.len(), 2);
         assert_eq!(definition_table.struct_definitions.len(), 0);
@@ -83,6 +101,7 @@ mod tests {
                 }),
                 mods: Rc::new(vec![]),
                 args: vec![],
+                span: Span::default(),
             })
         );
 
@@ -107,6 +126,7 @@ mod tests {
                 }),
                 mods: Rc::new(vec![]),
                 args: vec![],
+                span: Span::default(),
             })
         );
     }
@@ -122,9 +142,9 @@ mod tests {
         mock_file_system.insert_dir(Utf8PathBuf::from("test"));
         mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
 
-        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system));
+        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system).unwrap());
 
-        let definition_table = program.merge_modules().merged_module.private_definitions;
+        let definition_table = private_definitions(&mut program);
 
         assert_eq!(definition_table.function_definitions.len(), 2);
         assert_eq!(definition_table.struct_definitions.len(), 0);
@@ -157,18 +177,21 @@ mod tests {
                                         module_resolved: Some(Rc::from("0_a".to_string())),
                                         global_resolved: Some(Rc::from(GlobalResolvedName {
                                             package: Rc::from("pkg"),
-                                            module: Rc::from("/test/example"),
+                                            module: Rc::from("/root/test/example"),
                                             name: "0_a".to_string(),
                                         })),
                                     },
+                                    receiver: None,
                                     args: vec![],
                                 })
                             )),
+                            span: Span::default(),
                         },)))
                     ],
                 }),
                 mods: Rc::new(vec![]),
                 args: vec![],
+                span: Span::default(),
             })
         );
 
@@ -193,10 +216,74 @@ mod tests {
                 }),
                 mods: Rc::new(vec![]),
                 args: vec![],
+                span: Span::default(),
             })
         );
     }
 
+    #[test]
+    fn test_import_resolves_to_definition() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "mod test; use root::test::example::a; fn main() { a(); }",
+        );
+        mock_file_system.insert_file(Utf8PathBuf::from("test.ing"), "pub mod example;");
+        mock_file_system.insert_dir(Utf8PathBuf::from("test"));
+        mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
+
+        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system).unwrap());
+        let definition_table = private_definitions(&mut program);
+
+        // the imported name is the one `a` is defined under, so the call can be exported
+        let main = definition_table
+            .function_definitions
+            .values()
+            .find(|fn_def| fn_def.name.raw == "main")
+            .unwrap();
+        let Statement::Expression(call) = &main.body.statements[0] else {
+            panic!("expected a call");
+        };
+        let ExpressionEnum::AtomicExpression(AtomicExpression::FnCall(call)) = &call.expr else {
+            panic!("expected a call");
+        };
+        assert!(definition_table
+            .function_definitions
+            .contains_key(call.name.global_resolved.as_ref().unwrap()));
+    }
+
+    #[test]
+    fn test_import_private_module() {
+        // example is private to test, so only test and the modules in it can import from it
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "mod test; use root::test::example::a; fn main() { a(); }",
+        );
+        mock_file_system.insert_file(Utf8PathBuf::from("test.ing"), "mod example;");
+        mock_file_system.insert_dir(Utf8PathBuf::from("test"));
+        mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
+
+        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system).unwrap());
+        let error = Diagnostic::from(program.merge_modules().err().unwrap());
+        assert_eq!(
+            error.message,
+            "Cannot call module from this path: /root/test/example"
+        );
+
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), "mod test; fn main() {}");
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("test.ing"),
+            "mod example; use root::test::example::a; pub fn b() { a(); }",
+        );
+        mock_file_system.insert_dir(Utf8PathBuf::from("test"));
+        mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
+
+        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system).unwrap());
+        assert!(program.merge_modules().is_ok());
+    }
+
     #[test]
     fn test_import_cross_package() {
         let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
@@ -208,9 +295,9 @@ mod tests {
         mock_file_system.insert_dir(Utf8PathBuf::from("test/"));
         mock_file_system.insert_file(Utf8PathBuf::from("test/example.ing"), "pub fn a() {};");
 
-        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system));
+        let mut program = Packager::new("pkg", FileRetriever::new(mock_file_system).unwrap());
 
-        let definition_table = program.merge_modules().merged_module.private_definitions;
+        let definition_table = private_definitions(&mut program);
 
         assert_eq!(definition_table.function_definitions.len(), 2);
         assert_eq!(definition_table.struct_definitions.len(), 0);
@@ -238,14 +325,16 @@ mod tests {
                             raw: "a".to_string(),
                             module_resolved: Some(Rc::from("0_a".to_string())),
                             global_resolved: Some(Rc::from(GlobalResolvedName {
-                                package: Rc::from("pkg"),
-                                module: Rc::from("std/test/example"),
+                                package: Rc::from("std"),
+                                module: Rc::from("/root/test/example"),
                                 name: "0_a".to_string(),
                             })),
                         },
+                        receiver: None,
                         args: vec![],
                     }
                 ))),
+                span: Span::default(),
             })))
         );
     }
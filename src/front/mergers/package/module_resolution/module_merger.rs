@@ -1,13 +1,35 @@
 use crate::front::ast_retriever::retriever::{ModuleNode, ModuleSource};
 use crate::front::ast_types::visitor::Visitable;
 use crate::front::ast_types::{FnDef, GlobalResolvedName, ResolvedName, StructDef, VarDecl};
+use crate::front::diagnostics::Diagnostic;
 use crate::front::mergers::package::module_resolution::merged_module::MergedModule;
+use crate::front::mergers::package::module_resolution::resolver::ResolverError;
 use std::collections::{HashMap, LinkedList};
 use std::rc::Rc;
 
 #[derive(Debug)]
 pub enum ModuleMergeError {
     ModuleNotAttached(ModuleSource, String),
+    Resolve(ResolverError),
+}
+
+impl From<ModuleMergeError> for Diagnostic {
+    fn from(error: ModuleMergeError) -> Diagnostic {
+        match error {
+            ModuleMergeError::ModuleNotAttached(module_source, message) => {
+                Diagnostic::error(format!("{message} ({module_source})"))
+                    .help("declare it with `mod` in the module it is in")
+            }
+            ModuleMergeError::Resolve(ResolverError::ImportVisibilityError(
+                target,
+                from,
+                message,
+                span,
+            )) => Diagnostic::error(format!("{message}: {target}"))
+                .at(&span)
+                .note(format!("it is imported in {from}")),
+        }
+    }
 }
 
 pub type ModuleMergeResult<T> = Result<T, ModuleMergeError>;
@@ -16,7 +38,7 @@ pub type ModuleMergeResult<T> = Result<T, ModuleMergeError>;
 pub struct ModuleMerger {
     pub package_name: Rc<str>,
     module_source: ModuleSource,
-    global_name_table: HashMap<(String, String), Rc<GlobalResolvedName>>,
+    global_name_table: HashMap<(Rc<str>, String, String), Rc<GlobalResolvedName>>,
     global_name_map: HashMap<Rc<ResolvedName>, Rc<GlobalResolvedName>>,
     merged_module: Option<MergedModule>,
     visibility_rules: HashMap<ModuleSource, ModuleSource>, // to call public methods in module of path (key), module_path needs prefix (value)
@@ -48,21 +70,29 @@ impl ModuleMerger {
         module_name: String,
         name: String,
     ) -> Rc<GlobalResolvedName> {
-        if let Some(g) = self
-            .global_name_table
-            .get(&(module_name.clone(), name.clone()))
-        {
+        let package_name = Rc::clone(&self.package_name);
+        self.create_or_get_package_global_name(package_name, module_name, name)
+    }
+
+    // names imported from another package are resolved in that package
+    pub fn create_or_get_package_global_name(
+        &mut self,
+        package_name: Rc<str>,
+        module_name: String,
+        name: String,
+    ) -> Rc<GlobalResolvedName> {
+        let key = (package_name, module_name, name);
+        if let Some(g) = self.global_name_table.get(&key) {
             return Rc::clone(g);
         }
 
         let g = Rc::from(GlobalResolvedName {
-            package: Rc::clone(&self.package_name),
-            module: Rc::from(module_name.clone()),
-            name: name.clone(),
+            package: Rc::clone(&key.0),
+            module: Rc::from(key.1.clone()),
+            name: key.2.clone(),
         });
 
-        self.global_name_table
-            .insert((module_name, name), Rc::clone(&g));
+        self.global_name_table.insert(key, Rc::clone(&g));
         g
     }
 
@@ -153,7 +183,7 @@ impl ModuleMerger {
                 .module
                 .unwrap()
                 .visit(self)
-                .expect("Expected Module, got None");
+                .map_err(ModuleMergeError::Resolve)?;
         }
 
         Ok(self.merged_module.take().unwrap())
@@ -1,11 +1,12 @@
 use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
-use crate::front::ast_types::{Definition, ResolvedName};
+use crate::front::ast_types::{Definition, ResolvedName, Span, Type};
 use crate::front::mergers::package::module_resolution::module_merger::ModuleMerger;
 use std::rc::Rc;
 
 #[derive(Debug, PartialEq)]
 pub enum ResolverError {
-    ImportVisibilityError(String, String, String),
+    // the module imported from, the module importing and why it can not
+    ImportVisibilityError(String, String, String, Span),
 }
 
 pub type ResolveResult<T> = GenericResolveResult<T, ResolverError>;
@@ -44,26 +45,33 @@ impl Visitor<(), ResolverError> for ModuleMerger {
                         .unwrap()
                         .clone();
 
-                    let module_name = if use_.path[0] == "root" {
-                        let mut s = "/".to_string();
-                        s.push_str(&use_.path[1..].join("/"));
-                        s
-                    } else {
-                        let s = use_.path.join("/");
-
-                        if !self.can_call(&s) {
+                    // `root::a::b` is the module /root/a/b of this package, `other::a::b` is the
+                    // module /root/a/b of the package other
+                    let mut module_name = "/root".to_string();
+                    for segment in &use_.path[1..] {
+                        module_name.push('/');
+                        module_name.push_str(segment);
+                    }
+
+                    // only the modules of this package have visibility rules to check against
+                    let global_resolved_name = if use_.path[0] == "root" {
+                        if !self.can_call(&module_name) {
                             return Err(ResolverError::ImportVisibilityError(
-                                s.clone(),
+                                module_name.clone(),
                                 self.get_path().clone(),
                                 "Cannot call module from this path".to_string(),
+                                use_.span.clone(),
                             ));
                         }
-                        s
+                        self.create_or_get_global_name(module_name, format!("0_{original_name}"))
+                    } else {
+                        self.create_or_get_package_global_name(
+                            Rc::from(use_.path[0].as_str()),
+                            module_name,
+                            format!("0_{original_name}"),
+                        )
                     };
 
-                    let global_resolved_name =
-                        self.create_or_get_global_name(module_name, format!("0_{original_name}"));
-
                     self.register_global_name(local_name, Rc::from(global_resolved_name), false);
                     element.imported_name.visit(self)?;
                 }
@@ -77,8 +85,7 @@ impl Visitor<(), ResolverError> for ModuleMerger {
                 name_path.name.visit(self)?;
             }
 
-            ASTNodeEnum::Else(_)
-            | ASTNodeEnum::If(_)
+            ASTNodeEnum::If(_)
             | ASTNodeEnum::While(_)
             | ASTNodeEnum::For(_)
             | ASTNodeEnum::Statement(_)
@@ -86,11 +93,13 @@ impl Visitor<(), ResolverError> for ModuleMerger {
             | ASTNodeEnum::VarAssign(_)
             | ASTNodeEnum::VarDef(_)
             | ASTNodeEnum::FnCall(_)
-            | ASTNodeEnum::LiteralValue(_)
+            | ASTNodeEnum::StructLiteral(_)
+            | ASTNodeEnum::Index(_)
+            | ASTNodeEnum::IndexAssign(_)
             | ASTNodeEnum::VarDecl(_)
             | ASTNodeEnum::AtomicExpression(_) => return Ok((true, None)),
 
-            ASTNodeEnum::FnDef(_) | ASTNodeEnum::StructDef(_) | ASTNodeEnum::Definition(_) => {
+            ASTNodeEnum::FnDef(_) | ASTNodeEnum::StructDef(_) | ASTNodeEnum::Impl(_) => {
                 panic!("Should not be called directly")
             }
         };
@@ -106,6 +115,12 @@ fn resolve_definition(
     match definition {
         Definition::FnDef(mut fn_def) => {
             fn_def.name.visit(module_merger)?;
+            if let Type::Struct(struct_name) = &mut fn_def.return_type {
+                struct_name.visit(module_merger)?;
+            }
+            for arg in &mut fn_def.args {
+                arg.visit(module_merger)?;
+            }
             fn_def.body.visit(module_merger)?;
 
             module_merger.insert_fn_definition(
@@ -114,8 +129,40 @@ fn resolve_definition(
                 is_public,
             );
         }
+        Definition::Impl(mut impl_) => {
+            impl_.type_name.visit(module_merger)?;
+            let type_name = impl_.type_name.global_resolved.clone().unwrap();
+
+            // methods live in the module of their struct, so they are found from its global name
+            for mut method in impl_.methods {
+                method.name.global_resolved =
+                    Some(module_merger.create_or_get_package_global_name(
+                        Rc::clone(&type_name.package),
+                        type_name.module.to_string(),
+                        format!("{}.{}", type_name.name, method.name.raw),
+                    ));
+                if let Type::Struct(struct_name) = &mut method.return_type {
+                    struct_name.visit(module_merger)?;
+                }
+                for arg in &mut method.args {
+                    arg.visit(module_merger)?;
+                }
+                method.body.visit(module_merger)?;
+
+                module_merger.insert_fn_definition(
+                    method.name.global_resolved.clone().unwrap(),
+                    method,
+                    false,
+                );
+            }
+        }
         Definition::StructDef(mut struct_def) => {
             struct_def.type_name.visit(module_merger)?;
+            for type_ in struct_def.map.values_mut() {
+                if let Type::Struct(struct_name) = type_ {
+                    struct_name.visit(module_merger)?;
+                }
+            }
 
             module_merger.insert_struct_definition(
                 struct_def.type_name.global_resolved.clone().unwrap(),
@@ -124,7 +171,7 @@ fn resolve_definition(
             );
         }
         Definition::VarDecl(mut var_decl) => {
-            var_decl.var_def.name.visit(module_merger)?;
+            var_decl.var_def.visit(module_merger)?;
             if let Some(expr) = &mut var_decl.expr {
                 expr.visit(module_merger)?;
             }
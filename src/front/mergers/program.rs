@@ -1,5 +1,6 @@
 use crate::front::ast_retriever::retriever::FileRetriever;
-use crate::front::exporter::export::FrontProgram;
+use crate::front::diagnostics::Diagnostic;
+use crate::front::exporter::export::{FrontProgram, DEFAULT_FLOAT_SCALE};
 use crate::front::file_system::fs::FileSystem;
 use crate::front::mergers::definition_table::DefinitionTable;
 use crate::front::mergers::package::{Package, Packager};
@@ -22,11 +23,15 @@ impl<R: FileSystem> ProgramMerger<R> {
         }
     }
 
-    pub fn read_package(&mut self, package_name: &str, file_system: R) -> &mut Package {
-        let mut packager = Packager::new(package_name, FileRetriever::new(file_system));
+    pub fn read_package(
+        &mut self,
+        package_name: &str,
+        file_system: R,
+    ) -> Result<&mut Package, Diagnostic> {
+        let mut packager = Packager::new(package_name, FileRetriever::new(file_system)?);
         self.packages
-            .insert(package_name.to_string(), packager.merge_modules());
-        self.packages.get_mut(package_name).unwrap()
+            .insert(package_name.to_string(), packager.merge_modules()?);
+        Ok(self.packages.get_mut(package_name).unwrap())
     }
 
     pub fn return_merged(&mut self) -> FrontProgram {
@@ -89,6 +94,7 @@ impl<R: FileSystem> ProgramMerger<R> {
         FrontProgram {
             public_functions,
             definitions: def_table,
+            float_scale: DEFAULT_FLOAT_SCALE,
         }
     }
 }
@@ -1,13 +1,61 @@
-mod check_assignment;
-mod types;
+pub mod check_assignment;
+pub mod check_loops;
+pub mod fold_constants;
+pub mod types;
 
+use crate::front::ast_types::Span;
+use crate::front::diagnostics::Diagnostic;
 use crate::front::exporter::export::FrontProgram;
 use crate::front::passes::types::TypeError;
+use std::fmt::Display;
 
 pub enum PassError {
     Unimplemented,
-    Generic(String),
-    Types(TypeError),
+    Generic(String, Span),
+    Types(Box<TypeError>, Span),
+}
+
+impl PassError {
+    // an error without a span is reported at the node it was found in
+    pub fn at(self, span: &Span) -> PassError {
+        match self {
+            PassError::Generic(message, found) if found.is_default() => {
+                PassError::Generic(message, span.clone())
+            }
+            PassError::Types(type_error, found) if found.is_default() => {
+                PassError::Types(type_error, span.clone())
+            }
+            error => error,
+        }
+    }
+}
+
+impl From<TypeError> for PassError {
+    fn from(type_error: TypeError) -> PassError {
+        PassError::Types(Box::new(type_error), Span::default())
+    }
+}
+
+impl Display for PassError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PassError::Unimplemented => write!(f, "Not implemented"),
+            PassError::Generic(message, _) => write!(f, "{}", message),
+            PassError::Types(type_error, _) => write!(f, "{}", type_error),
+        }
+    }
+}
+
+impl From<PassError> for Diagnostic {
+    fn from(error: PassError) -> Diagnostic {
+        let message = error.to_string();
+        match error {
+            PassError::Unimplemented => Diagnostic::error(message),
+            PassError::Generic(_, span) | PassError::Types(_, span) => {
+                Diagnostic::error(message).at(&span)
+            }
+        }
+    }
 }
 
 pub type PassResult = Result<(), PassError>;
@@ -1,11 +1,12 @@
 mod null_check;
 
 use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
-use crate::front::ast_types::{AtomicExpression, Else, ExpressionEnum, Statement};
+use crate::front::ast_types::{AtomicExpression, Else, ExpressionEnum, Span, Statement, Type};
 use crate::front::exporter::export::FrontProgram;
 use crate::front::passes::check_assignment::null_check::NullCheck;
 use crate::front::passes::{Pass, PassError, PassResult};
 use std::collections::HashSet;
+use std::rc::Rc;
 
 use std::ops::DerefMut;
 
@@ -35,8 +36,19 @@ impl Visitor<bool, ResolverError> for Option<NullCheck> {
                         }
                         Ok((false, Some(false)))
                     }
+                    AtomicExpression::Index(x) => {
+                        // arrays are filled with zeros when they are declared
+                        x.index.visit(self)?;
+                        Ok((false, Some(false)))
+                    }
+                    AtomicExpression::StructLiteral(x) => {
+                        for field in x.fields.values_mut() {
+                            field.visit(self)?;
+                        }
+                        Ok((false, Some(false)))
+                    }
                     AtomicExpression::Literal(_) => Ok((true, Some(false))),
-                }
+                };
             }
             ASTNodeEnum::If(if_) => {
                 if_.cond.visit(self)?;
@@ -52,10 +64,13 @@ impl Visitor<bool, ResolverError> for Option<NullCheck> {
 
                 let mut cur = if_.deref_mut();
                 while let Some(ref mut else_) = &mut cur.else_ {
+                    // the condition is checked before the body takes over the null check
+                    if let Else::If(ref mut x) = else_ {
+                        x.cond.visit(self)?;
+                    }
                     let mut nc = Some(NullCheck::new_with_parent(self.take().unwrap()));
                     match else_ {
                         Else::If(ref mut x) => {
-                            x.cond.visit(self)?;
                             x.body.visit(&mut nc)?;
                             ifs.push(nc.as_mut().unwrap().take_not_null());
                             *self = Some(nc.unwrap().take_parent());
@@ -126,18 +141,21 @@ impl Visitor<bool, ResolverError> for Option<NullCheck> {
 
             ASTNodeEnum::VarDecl(var_decl) => {
                 var_decl.var_def.visit(self)?;
-                if let Some(expr) = &mut var_decl.expr {
-                    if !self.apply(&mut ASTNodeEnum::Expression(expr))?.1.unwrap() {
-                        self.as_mut().unwrap().confirm_not_null(
-                            var_decl
-                                .var_def
-                                .name
-                                .global_resolved
-                                .as_ref()
-                                .unwrap()
-                                .clone(),
-                        );
-                    }
+                let not_null = match &mut var_decl.expr {
+                    Some(expr) => !self.apply(&mut ASTNodeEnum::Expression(expr))?.1.unwrap(),
+                    // arrays are filled with zeros when they are declared
+                    None => matches!(var_decl.var_def.type_, Some(Type::Array(_, _))),
+                };
+                if not_null {
+                    self.as_mut().unwrap().confirm_not_null(
+                        var_decl
+                            .var_def
+                            .name
+                            .global_resolved
+                            .as_ref()
+                            .unwrap()
+                            .clone(),
+                    );
                 }
             }
             ASTNodeEnum::Expression(expr) => {
@@ -158,6 +176,9 @@ impl Visitor<bool, ResolverError> for Option<NullCheck> {
             }
 
             ASTNodeEnum::FnCall(_)
+            | ASTNodeEnum::StructLiteral(_)
+            | ASTNodeEnum::Index(_)
+            | ASTNodeEnum::IndexAssign(_)
             | ASTNodeEnum::VarDef(_)
             | ASTNodeEnum::Statement(_)
             | ASTNodeEnum::Block(_)
@@ -165,9 +186,7 @@ impl Visitor<bool, ResolverError> for Option<NullCheck> {
             | ASTNodeEnum::Reference(_)
             | ASTNodeEnum::FnDef(_)
             | ASTNodeEnum::StructDef(_)
-            | ASTNodeEnum::LiteralValue(_)
-            | ASTNodeEnum::Else(_)
-            | ASTNodeEnum::Definition(_)
+            | ASTNodeEnum::Impl(_)
             | ASTNodeEnum::Module(_)
             | ASTNodeEnum::Use(_) => return Ok((true, None)),
         };
@@ -185,6 +204,19 @@ impl Pass for DisallowNullAssignment {
         for (_, v) in &mut program.definitions.function_definitions {
             let mut null_check = Some(NullCheck::new());
 
+            // parameters and globals always hold a value
+            for name in v
+                .args
+                .iter()
+                .map(|arg| arg.name.global_resolved.as_ref().unwrap())
+                .chain(program.definitions.global_var_definitions.keys())
+            {
+                null_check
+                    .as_mut()
+                    .unwrap()
+                    .confirm_not_null(Rc::clone(name));
+            }
+
             let mut statements = v.body.statements.drain(..).collect::<Vec<Statement>>();
             statements.iter_mut().for_each(|s| {
                 s.visit(&mut null_check).expect("Null check failed");
@@ -196,10 +228,10 @@ impl Pass for DisallowNullAssignment {
         }
 
         if !all_null_accesses.is_empty() {
-            return Err(PassError::Generic(format!(
-                "Null access detected: {:?}",
-                all_null_accesses
-            )));
+            return Err(PassError::Generic(
+                format!("Null access detected: {:?}", all_null_accesses),
+                Span::default(),
+            ));
         }
         Ok(())
     }
@@ -224,7 +256,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -245,7 +279,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -266,7 +302,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -287,7 +325,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -308,7 +348,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -329,7 +371,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -339,4 +383,28 @@ mod tests {
         )
         .is_err());
     }
+
+    #[test]
+    fn test_parameters_and_globals() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "let g: int = 1; fn f(a: int) -> int { let xs: [int; 2]; return a + g + xs[0]; } \
+            pub fn main() { let b: int = f(1); if (b == 0) { b = 1; } else if (b == 1) { b = 2; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(pass(
+            &mut front_program,
+            &mut vec![Box::new(DisallowNullAssignment)],
+        )
+        .is_ok());
+    }
 }
@@ -0,0 +1,109 @@
+use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
+use crate::front::ast_types::Statement;
+use crate::front::exporter::export::FrontProgram;
+use crate::front::passes::{Pass, PassError, PassResult};
+
+// how many loops the statement being checked is in
+struct LoopDepth {
+    depth: u32,
+}
+
+impl Visitor<(), PassError> for LoopDepth {
+    fn apply(&mut self, ast_node: &mut ASTNodeEnum) -> GenericResolveResult<(), PassError> {
+        match ast_node {
+            ASTNodeEnum::Statement(Statement::Break(span)) if self.depth == 0 => {
+                return Err(PassError::Generic(
+                    "`break` outside of a loop".to_string(),
+                    span.clone(),
+                ));
+            }
+            ASTNodeEnum::Statement(Statement::Continue(span)) if self.depth == 0 => {
+                return Err(PassError::Generic(
+                    "`continue` outside of a loop".to_string(),
+                    span.clone(),
+                ));
+            }
+
+            ASTNodeEnum::While(while_) => {
+                self.depth += 1;
+                while_.body.visit(self)?;
+                self.depth -= 1;
+            }
+            ASTNodeEnum::For(for_) => {
+                // the step runs as part of the loop, but it is a single statement that can not exit it
+                self.depth += 1;
+                for_.body.visit(self)?;
+                self.depth -= 1;
+            }
+
+            // functions defined in a block are checked on their own
+            ASTNodeEnum::FnDef(_) | ASTNodeEnum::Impl(_) => {}
+
+            _ => return Ok((true, None)),
+        }
+        Ok((false, None))
+    }
+}
+
+// rejects `break` and `continue` that are not in a loop of their function
+#[derive(Debug)]
+pub struct CheckLoopExits;
+
+impl Pass for CheckLoopExits {
+    fn pass(&mut self, program: &mut FrontProgram) -> PassResult {
+        for fn_def in program.definitions.function_definitions.values_mut() {
+            fn_def.body.visit(&mut LoopDepth { depth: 0 })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::front::diagnostics::Diagnostic;
+    use crate::front::exporter::export::FrontProgram;
+    use crate::front::file_system::fs::FileSystem;
+    use crate::front::file_system::mock_fs::MockFileSystem;
+    use crate::front::mergers::program::ProgramMerger;
+    use crate::front::passes::check_loops::CheckLoopExits;
+    use crate::front::passes::pass;
+    use camino::Utf8PathBuf;
+
+    fn merge(source: &str) -> FrontProgram {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), source);
+
+        let mut program_merger = ProgramMerger::new("pkg");
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+        program_merger.return_merged()
+    }
+
+    #[test]
+    fn test_loop_exits() {
+        let source =
+            "pub fn main() {\n    let a: int = 0;\n    if (a < 1) {\n        break;\n    }\n}";
+        let mut front_program = merge(source);
+        let error = pass(&mut front_program, &mut vec![Box::new(CheckLoopExits)])
+            .err()
+            .unwrap();
+        assert_eq!(
+            Diagnostic::from(error).render(Some(source)),
+            "error: `break` outside of a loop\n \
+             --> main.ing:4:9\n  \
+               |\n\
+             4 |         break;\n  \
+               |         ^^^^^"
+        );
+
+        let mut front_program = merge("pub fn main() { continue; }");
+        assert!(pass(&mut front_program, &mut vec![Box::new(CheckLoopExits)]).is_err());
+
+        let mut front_program = merge(
+            "pub fn main() { let a: int = 0; while (a < 5) { a++; if (a == 2) { continue; } \
+            for (let b: int = 0; b < a; b++) { break; } if (a == 3) { break; } } }",
+        );
+        assert!(pass(&mut front_program, &mut vec![Box::new(CheckLoopExits)]).is_ok());
+    }
+}
@@ -0,0 +1,361 @@
+use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
+use crate::front::ast_types::{
+    AtomicExpression, BinOp, Expression, ExpressionEnum, GlobalResolvedName, LiteralValue,
+    NamePath, Span, Statement, Type, UnOp, VarDecl, VarDef, VarMod,
+};
+use crate::front::exporter::export::FrontProgram;
+use crate::front::passes::{Pass, PassError, PassResult};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+pub enum ResolverError {
+    ConstAssignment(String, Span),
+}
+
+pub type ResolveResult<T> = GenericResolveResult<T, ResolverError>;
+
+/*
+Every const binding can not be assigned to. The ones with a value that is known when the program is compiled are
+also replaced by that value wherever they are used, so they are constants instead of variables.
+ */
+struct ConstTable {
+    consts: HashSet<Rc<GlobalResolvedName>>,
+    values: HashMap<Rc<GlobalResolvedName>, LiteralValue>,
+}
+
+fn is_const(var_def: &VarDef) -> bool {
+    var_def.mods.contains(&VarMod::Const)
+}
+
+// `/` and `%` round towards negative infinity, like they do on a scoreboard
+fn floor_div(a: i32, b: i32) -> Option<i32> {
+    let q = a.checked_div(b)?;
+    Some(if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    })
+}
+
+impl ConstTable {
+    fn new() -> ConstTable {
+        ConstTable {
+            consts: HashSet::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    fn check_assignment(&self, name_path: &NamePath, span: &Span) -> Result<(), ResolverError> {
+        if self
+            .consts
+            .contains(name_path.name.global_resolved.as_ref().unwrap())
+        {
+            return Err(ResolverError::ConstAssignment(
+                name_path.name.raw.clone(),
+                span.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    // the value of the expression if it only uses literals and folded consts
+    fn fold(&self, expr: &Expression) -> Option<LiteralValue> {
+        match &expr.expr {
+            ExpressionEnum::AtomicExpression(AtomicExpression::Literal(x)) => match x {
+                LiteralValue::Null | LiteralValue::Compound(_) => None,
+                _ => Some(x.clone()),
+            },
+            ExpressionEnum::AtomicExpression(AtomicExpression::Variable(x))
+                if x.path.is_empty() =>
+            {
+                self.values
+                    .get(x.name.global_resolved.as_ref().unwrap())
+                    .cloned()
+            }
+            ExpressionEnum::Unary(unop, e) => match (unop, self.fold(e)?) {
+                (UnOp::Neg, LiteralValue::Int(x)) => Some(LiteralValue::Int(x.checked_neg()?)),
+                (UnOp::Neg, LiteralValue::Float(x)) => Some(LiteralValue::Float(-x)),
+                (UnOp::Neg, LiteralValue::Double(x)) => Some(LiteralValue::Double(-x)),
+                (UnOp::Not, LiteralValue::Bool(x)) => Some(LiteralValue::Bool(!x)),
+                _ => None,
+            },
+            ExpressionEnum::Binary(e0, binop, e1) => match (self.fold(e0)?, self.fold(e1)?) {
+                (LiteralValue::Int(a), LiteralValue::Int(b)) => Some(match binop {
+                    BinOp::Add => LiteralValue::Int(a.checked_add(b)?),
+                    BinOp::Sub => LiteralValue::Int(a.checked_sub(b)?),
+                    BinOp::Mul => LiteralValue::Int(a.checked_mul(b)?),
+                    BinOp::Div => LiteralValue::Int(floor_div(a, b)?),
+                    BinOp::Mod => LiteralValue::Int(a.checked_sub(floor_div(a, b)? * b)?),
+                    BinOp::Eq => LiteralValue::Bool(a == b),
+                    BinOp::Neq => LiteralValue::Bool(a != b),
+                    BinOp::Lt => LiteralValue::Bool(a < b),
+                    BinOp::Gt => LiteralValue::Bool(a > b),
+                    BinOp::Leq => LiteralValue::Bool(a <= b),
+                    BinOp::Geq => LiteralValue::Bool(a >= b),
+                    BinOp::And | BinOp::Or => return None,
+                }),
+                (LiteralValue::Bool(a), LiteralValue::Bool(b)) => {
+                    Some(LiteralValue::Bool(match binop {
+                        BinOp::Eq => a == b,
+                        BinOp::Neq => a != b,
+                        BinOp::And => a && b,
+                        BinOp::Or => a || b,
+                        _ => return None,
+                    }))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /*
+    Folds the value of the const declaration, which is only possible once the consts it uses are folded. A value
+    that does not have the type of the annotation is left to the conversion, which reports it.
+     */
+    fn fold_decl(&mut self, var_decl: &mut VarDecl) -> Result<bool, ResolverError> {
+        let name = Rc::clone(var_decl.var_def.name.global_resolved.as_ref().unwrap());
+        self.consts.insert(Rc::clone(&name));
+
+        let Some(expr) = &mut var_decl.expr else {
+            return Ok(false);
+        };
+        expr.visit(self)?;
+        let Some(value) = self.fold(expr) else {
+            return Ok(false);
+        };
+
+        let type_ = match &value {
+            LiteralValue::Int(_) => Type::Int,
+            LiteralValue::Bool(_) => Type::Bool,
+            LiteralValue::Float(_) => Type::Float,
+            LiteralValue::Double(_) => Type::Double,
+            _ => Type::String,
+        };
+        if var_decl
+            .var_def
+            .type_
+            .as_ref()
+            .is_some_and(|annotation| annotation != &type_)
+        {
+            return Ok(false);
+        }
+
+        self.values.insert(name, value);
+        Ok(true)
+    }
+}
+
+impl Visitor<(), ResolverError> for ConstTable {
+    fn apply(&mut self, ast_node: &mut ASTNodeEnum) -> ResolveResult<()> {
+        match ast_node {
+            ASTNodeEnum::VarDecl(x) if is_const(&x.var_def) => {
+                self.fold_decl(x)?;
+            }
+            ASTNodeEnum::VarAssign(x) => {
+                self.check_assignment(&x.name_path, &x.span)?;
+                x.expr.visit(self)?;
+            }
+            ASTNodeEnum::IndexAssign(x) => {
+                self.check_assignment(&x.element.array, &x.span)?;
+                x.element.index.visit(self)?;
+                x.expr.visit(self)?;
+            }
+            ASTNodeEnum::Expression(x) => {
+                if let ExpressionEnum::Unary(
+                    UnOp::PreInc | UnOp::PreDec | UnOp::PostInc | UnOp::PostDec,
+                    e,
+                ) = &x.expr
+                {
+                    if let ExpressionEnum::AtomicExpression(AtomicExpression::Variable(name_path)) =
+                        &e.expr
+                    {
+                        self.check_assignment(name_path, &x.span)?;
+                    }
+                }
+                if !matches!(
+                    x.expr,
+                    ExpressionEnum::AtomicExpression(AtomicExpression::Variable(_))
+                ) {
+                    return Ok((true, None));
+                }
+                if let Some(value) = self.fold(x) {
+                    x.expr = ExpressionEnum::AtomicExpression(AtomicExpression::Literal(value));
+                }
+            }
+            ASTNodeEnum::Block(x) => {
+                for definition in &mut x.definitions {
+                    definition.visit(self)?;
+                }
+                for statement in &mut x.statements {
+                    statement.visit(self)?;
+                }
+                // a folded const is not used anymore, so it does not need a score
+                x.statements.retain(|statement| match statement {
+                    Statement::VarDecl(var_decl) => !self
+                        .values
+                        .contains_key(var_decl.var_def.name.global_resolved.as_ref().unwrap()),
+                    _ => true,
+                });
+            }
+            _ => return Ok((true, None)),
+        };
+        Ok((false, None))
+    }
+}
+
+#[derive(Debug)]
+pub struct FoldConstants;
+
+impl Pass for FoldConstants {
+    fn pass(&mut self, program: &mut FrontProgram) -> PassResult {
+        let mut table = ConstTable::new();
+        let to_pass_error = |error: ResolverError| match error {
+            ResolverError::ConstAssignment(name, span) => {
+                PassError::Generic(format!("Can not assign to the const {name}"), span)
+            }
+        };
+
+        // global consts can use each other in any order, so they are folded until no more of them can be
+        let mut pending: Vec<Rc<GlobalResolvedName>> = program
+            .definitions
+            .global_var_definitions
+            .iter()
+            .filter(|(_, var_decl)| is_const(&var_decl.var_def))
+            .map(|(name, _)| Rc::clone(name))
+            .collect();
+        loop {
+            let mut folded = false;
+            for name in &pending {
+                let var_decl = program
+                    .definitions
+                    .global_var_definitions
+                    .get_mut(name)
+                    .unwrap();
+                folded |= table.fold_decl(var_decl).map_err(to_pass_error)?;
+            }
+            pending.retain(|name| !table.values.contains_key(name));
+            if !folded {
+                break;
+            }
+        }
+        for var_decl in program.definitions.global_var_definitions.values_mut() {
+            if !is_const(&var_decl.var_def) {
+                var_decl.visit(&mut table).map_err(to_pass_error)?;
+            }
+        }
+        program
+            .definitions
+            .global_var_definitions
+            .retain(|name, _| !table.values.contains_key(name));
+
+        for fn_def in program.definitions.function_definitions.values_mut() {
+            for arg in &fn_def.args {
+                if is_const(arg) {
+                    table
+                        .consts
+                        .insert(Rc::clone(arg.name.global_resolved.as_ref().unwrap()));
+                }
+            }
+            fn_def.body.visit(&mut table).map_err(to_pass_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::front::ast_types::visitor::{ClearSpans, Visitable};
+    use crate::front::ast_types::{
+        AtomicExpression, BinOp, Expression, ExpressionEnum, LiteralValue, Span, Statement, VarDecl,
+    };
+    use crate::front::exporter::export::FrontProgram;
+    use crate::front::file_system::fs::FileSystem;
+    use crate::front::file_system::mock_fs::MockFileSystem;
+    use crate::front::mergers::program::ProgramMerger;
+    use crate::front::passes::fold_constants::FoldConstants;
+    use crate::front::passes::pass;
+    use camino::Utf8PathBuf;
+
+    fn literal(value: LiteralValue) -> Expression {
+        Expression {
+            expr: ExpressionEnum::AtomicExpression(AtomicExpression::Literal(value)),
+            type_: None,
+            span: Span::default(),
+        }
+    }
+
+    fn merge(source: &str) -> FrontProgram {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), source);
+
+        let mut program_merger = ProgramMerger::new("pkg");
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+        program_merger.return_merged()
+    }
+
+    #[test]
+    fn test_const_assign() {
+        for source in [
+            "pub fn main() { const a: int = 1; a = 2; }",
+            "pub fn main() { const a: int = 1; a++; }",
+            "const A: int = 1; pub fn main() { let b: int = 0; while (b < 5) { A = b; b++; } }",
+            "pub fn main() { let a: int = 0; const b = a + 1; b += 1; }",
+        ] {
+            let mut front_program = merge(source);
+            assert!(
+                pass(&mut front_program, &mut vec![Box::new(FoldConstants)]).is_err(),
+                "{source}"
+            );
+        }
+
+        let mut front_program = merge("pub fn main() { let a: int = 1; a = 2; a++; }");
+        assert!(pass(&mut front_program, &mut vec![Box::new(FoldConstants)]).is_ok());
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        let mut front_program = merge(
+            "const A: int = 2; const B: int = A * 3; \
+            pub fn main() { const c = B + 1; const d = c > 6; let e: int = c * 2; let f: bool = d; }",
+        );
+        assert!(pass(&mut front_program, &mut vec![Box::new(FoldConstants)]).is_ok());
+
+        assert!(front_program.definitions.global_var_definitions.is_empty());
+        let main = front_program
+            .definitions
+            .function_definitions
+            .values_mut()
+            .next()
+            .unwrap();
+        main.body.visit(&mut ClearSpans).unwrap();
+        let values: Vec<&Expression> = main
+            .body
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::VarDecl(VarDecl { expr: Some(e), .. }) => e.as_ref(),
+                _ => panic!("Expected only the declarations of e and f"),
+            })
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                &Expression {
+                    expr: ExpressionEnum::Binary(
+                        Box::new(literal(LiteralValue::Int(7))),
+                        BinOp::Mul,
+                        Box::new(literal(LiteralValue::Int(2))),
+                    ),
+                    type_: None,
+                    span: Span::default(),
+                },
+                &literal(LiteralValue::Bool(true)),
+            ]
+        );
+    }
+}
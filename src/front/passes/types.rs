@@ -1,21 +1,197 @@
+mod check_types;
+mod declared_types;
 mod first_assignment_graph;
 mod insert_types;
 mod topological_sort;
 mod type_expression;
 mod var_def_table;
 
+use crate::front::ast_types::visitor::Visitable;
+use crate::front::ast_types::{BinOp, Type, UnOp};
 use crate::front::exporter::export::FrontProgram;
-use crate::front::passes::{Pass, PassError, PassResult};
+use crate::front::passes::{Pass, PassResult};
 use either::Either;
 use std::collections::HashMap;
+use std::fmt::Display;
 
+use crate::front::passes::types::check_types::TypeChecker;
+use crate::front::passes::types::declared_types::DeclaredTypes;
 use crate::front::passes::types::first_assignment_graph::create_first_assignment_graph;
 use crate::front::passes::types::insert_types::{insert_types, ResolvedVarDefTable};
 use crate::front::passes::types::topological_sort::topological_sort;
 use crate::front::passes::types::var_def_table::VarTypeNode;
 
+#[derive(Debug, PartialEq)]
 pub enum TypeError {
     NotEnoughInformation,
+    // `target` is a variable, a field or an element of an array
+    Assignment {
+        target: String,
+        expected: Type,
+        found: Type,
+    },
+    ArgumentCount {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+    ArgumentType {
+        function: String,
+        index: usize,
+        expected: Type,
+        found: Type,
+    },
+    ReturnType {
+        function: String,
+        expected: Type,
+        found: Type,
+    },
+    // a function that returns a value can reach its end
+    MissingReturn {
+        function: String,
+    },
+    BinaryOperands {
+        op: BinOp,
+        left: Type,
+        right: Type,
+    },
+    UnaryOperand {
+        op: UnOp,
+        operand: Type,
+    },
+    FieldType {
+        struct_: String,
+        field: String,
+        expected: Type,
+        found: Type,
+    },
+    MissingField {
+        struct_: String,
+        field: String,
+    },
+    UnknownField {
+        struct_: String,
+        field: String,
+    },
+    Condition {
+        found: Type,
+    },
+    // a field or method of something that has none
+    NotAStruct {
+        name: String,
+    },
+    NotAnArray {
+        name: String,
+    },
+    IndexType {
+        array: String,
+        found: Type,
+    },
+    // a constant index that is not inside the array
+    IndexOutOfBounds {
+        array: String,
+        index: i32,
+        len: u32,
+    },
+    ArrayElement {
+        element: Type,
+    },
+    // a string, array or compound field of a struct
+    StorageField {
+        struct_: String,
+        field: String,
+        type_: Type,
+    },
+    // only scores, strings and compounds have a text to show
+    Print {
+        found: Type,
+    },
+}
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeError::NotEnoughInformation => write!(f, "Could not infer the type of a variable"),
+            TypeError::Assignment {
+                target,
+                expected,
+                found,
+            } => write!(f, "Can not assign {found} to {target}, which is {expected}"),
+            TypeError::ArgumentCount {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{function} takes {expected} arguments, but {found} were given"
+            ),
+            TypeError::ArgumentType {
+                function,
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Argument {} of {function} should be {expected}, found {found}",
+                index + 1
+            ),
+            TypeError::ReturnType {
+                function,
+                expected,
+                found,
+            } => write!(f, "{function} should return {expected}, found {found}"),
+            TypeError::MissingReturn { function } => {
+                write!(f, "{function} does not return a value on every path")
+            }
+            TypeError::BinaryOperands { op, left, right } => {
+                write!(f, "Can not apply {op:?} to {left} and {right}")
+            }
+            TypeError::UnaryOperand { op, operand } => {
+                write!(f, "Can not apply {op:?} to {operand}")
+            }
+            TypeError::FieldType {
+                struct_,
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "The field {field} of {struct_} should be {expected}, found {found}"
+            ),
+            TypeError::MissingField { struct_, field } => {
+                write!(f, "{struct_} is missing the field {field}")
+            }
+            TypeError::UnknownField { struct_, field } => {
+                write!(f, "{struct_} has no field {field}")
+            }
+            TypeError::Condition { found } => {
+                write!(f, "Expected a condition of type bool, found {found}")
+            }
+            TypeError::NotAStruct { name } => write!(f, "{name} has no fields"),
+            TypeError::NotAnArray { name } => write!(f, "{name} is not an array"),
+            TypeError::IndexType { array, found } => {
+                write!(f, "{array} must be indexed with an int, found {found}")
+            }
+            TypeError::IndexOutOfBounds { array, index, len } => {
+                write!(
+                    f,
+                    "Index {index} is out of bounds for {array} of length {len}"
+                )
+            }
+            TypeError::ArrayElement { element } => {
+                write!(f, "Arrays of {element} are not supported")
+            }
+            TypeError::StorageField {
+                struct_,
+                field,
+                type_,
+            } => write!(
+                f,
+                "The field {field} of {struct_} is {type_}, which a struct can not hold"
+            ),
+            TypeError::Print { found } => write!(f, "A value of type {found} can not be printed"),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -29,19 +205,31 @@ impl Pass for AnnotateTypes {
         let sorted = topological_sort(&table);
 
         let mut new_table = ResolvedVarDefTable {
+            declared: DeclaredTypes::new(program),
             var_types: HashMap::new(),
         };
 
-        // evaluate types
+        /*
+        evaluate types, a variable that is never assigned or whose first assignment can not be typed is left
+        untyped, so CheckTypes reports why where it is used
+         */
         for value in sorted {
-            let types = &table.var_types.get(&value).unwrap().types_;
-            let type_ = match &types {
+            let Some(node) = table.var_types.get(&value) else {
+                continue;
+            };
+            let type_ = match &node.types_ {
                 Either::Left(l) => l.clone(),
                 Either::Right(r) => {
-                    if let Ok(type_) = r.tree.resolve_type(program, &table.var_types) {
-                        type_
-                    } else {
-                        return Err(PassError::Types(TypeError::NotEnoughInformation));
+                    if !r
+                        .deps
+                        .iter()
+                        .all(|dep| new_table.var_types.contains_key(dep))
+                    {
+                        continue;
+                    }
+                    match r.tree.resolve_type(&new_table.declared, &table.var_types) {
+                        Ok(type_) => type_,
+                        Err(_) => continue,
                     }
                 }
             };
@@ -61,16 +249,64 @@ impl Pass for AnnotateTypes {
     }
 }
 
+// checks the types of calls, returns, operators and struct literals once every variable is typed
+#[derive(Debug)]
+pub struct CheckTypes;
+
+impl Pass for CheckTypes {
+    fn pass(&mut self, program: &mut FrontProgram) -> PassResult {
+        let mut checker = TypeChecker::new(program);
+
+        for struct_def in program.definitions.struct_definitions.values() {
+            checker
+                .check_struct(struct_def)
+                .map_err(|error| error.at(&struct_def.span))?;
+        }
+
+        for (name, var_decl) in &program.definitions.global_var_definitions {
+            if let Some(type_) = &var_decl.var_def.type_ {
+                checker.register_variable(name, type_.clone());
+            }
+        }
+        for var_decl in program.definitions.global_var_definitions.values_mut() {
+            var_decl
+                .visit(&mut checker)
+                .map_err(|error| error.at(&var_decl.span))?;
+        }
+
+        for fn_def in program.definitions.function_definitions.values_mut() {
+            checker.enter_function(&fn_def.name.raw, &fn_def.return_type);
+            // errors that are not in an expression are reported at the function
+            let span = fn_def.span.clone();
+            for arg in &mut fn_def.args {
+                arg.visit(&mut checker).map_err(|error| error.at(&span))?;
+            }
+            fn_def
+                .body
+                .visit(&mut checker)
+                .map_err(|error| error.at(&span))?;
+            checker
+                .check_returns(&fn_def.body)
+                .map_err(|error| error.at(&span))?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::front::ast_types::Type;
+    use crate::front::ast_types::{BinOp, Type, UnOp};
     use crate::front::ast_types::{GlobalResolvedName, Statement};
+    use crate::front::diagnostics::Diagnostic;
     use crate::front::file_system::fs::FileSystem;
     use crate::front::file_system::mock_fs::MockFileSystem;
     use crate::front::mergers::program::ProgramMerger;
     use crate::front::passes::pass;
-    use crate::front::passes::types::AnnotateTypes;
+    use crate::front::passes::types::{AnnotateTypes, CheckTypes, TypeError};
+    use crate::front::passes::PassError;
     use camino::Utf8PathBuf;
+    use std::collections::HashMap;
     use std::rc::Rc;
 
     #[test]
@@ -83,7 +319,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -117,7 +355,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let mut front_program = program_merger.return_merged();
 
@@ -140,4 +380,488 @@ mod tests {
             panic!("Expected VarDecl");
         }
     }
+
+    #[test]
+    fn test_type_annotation_conversion() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a = 1.5; let b = a * 2.0; let c = b as int; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(pass(&mut front_program, &mut vec![Box::new(AnnotateTypes)]).is_ok());
+
+        let statements = &front_program
+            .definitions
+            .function_definitions
+            .get(&Rc::from(GlobalResolvedName {
+                package: Rc::from("pkg"),
+                module: Rc::from("/root"),
+                name: "0_main".to_string(),
+            }))
+            .unwrap()
+            .body
+            .statements;
+        for (statement, type_) in statements.iter().zip([Type::Float, Type::Float, Type::Int]) {
+            if let Statement::VarDecl(x) = statement {
+                assert_eq!(x.var_def.type_.as_ref().unwrap(), &type_);
+            } else {
+                panic!("Expected VarDecl");
+            }
+        }
+
+        // ints and floats are only mixed through `as`
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a = 1.5; let b = a * 2; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(matches!(
+            pass(
+                &mut front_program,
+                &mut vec![Box::new(AnnotateTypes), Box::new(CheckTypes)]
+            ),
+            Err(PassError::Types(error, _)) if *error == TypeError::BinaryOperands {
+                op: BinOp::Mul,
+                left: Type::Float,
+                right: Type::Int,
+            }
+        ));
+
+        // comparisons are bools, and only bools are conditions
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a = 1; let b = a < 2 && !false; if (b) { a = 2; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(pass(&mut front_program, &mut vec![Box::new(AnnotateTypes)]).is_ok());
+
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a = 1; if (a) { a = 2; } }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(matches!(
+            pass(
+                &mut front_program,
+                &mut vec![Box::new(AnnotateTypes), Box::new(CheckTypes)]
+            ),
+            Err(PassError::Types(error, _)) if *error == TypeError::Condition { found: Type::Int }
+        ));
+    }
+
+    #[test]
+    fn test_compound_type_annotation() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { let a = 2; let c = { hp: a, pos: { y: 1.5 } }; }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(pass(&mut front_program, &mut vec![Box::new(AnnotateTypes)]).is_ok());
+
+        let statements = &front_program
+            .definitions
+            .function_definitions
+            .get(&Rc::from(GlobalResolvedName {
+                package: Rc::from("pkg"),
+                module: Rc::from("/root"),
+                name: "0_main".to_string(),
+            }))
+            .unwrap()
+            .body
+            .statements;
+        let Statement::VarDecl(x) = &statements[1] else {
+            panic!("Expected VarDecl");
+        };
+        assert_eq!(
+            x.var_def.type_.as_ref().unwrap(),
+            &Type::Compound(HashMap::from([
+                ("hp".to_string(), Type::Int),
+                (
+                    "pos".to_string(),
+                    Type::Compound(HashMap::from([("y".to_string(), Type::Float)]))
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_check_types() {
+        let check = |source: &str| {
+            let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+            mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), source);
+
+            let mut program_merger = ProgramMerger::new("pkg");
+
+            program_merger
+                .read_package("pkg", mock_file_system)
+                .unwrap();
+
+            let mut front_program = program_merger.return_merged();
+
+            match pass(
+                &mut front_program,
+                &mut vec![Box::new(AnnotateTypes), Box::new(CheckTypes)],
+            ) {
+                Ok(()) => None,
+                Err(PassError::Types(type_error, _)) => Some(*type_error),
+                Err(_) => panic!("Expected a type error"),
+            }
+        };
+
+        assert_eq!(
+            check(
+                "struct Point { x: int, y: int } \
+                impl Point { fn len(self) -> int { return self.x + self.y; } } \
+                fn max(a: int, b: int) -> int { if (a > b) { return a; } else { return b; } } \
+                pub fn main() { let p: Point = Point { x: 1, y: 2 }; let r: int = max(p.len(), 3); }"
+            ),
+            None
+        );
+
+        assert_eq!(
+            check("fn f(a: int, b: int) -> int { return a; } pub fn main() { let r: int = f(1); }"),
+            Some(TypeError::ArgumentCount {
+                function: "f".to_string(),
+                expected: 2,
+                found: 1,
+            })
+        );
+        assert_eq!(
+            check("fn f(a: int, b: bool) {} pub fn main() { f(1, 2); }"),
+            Some(TypeError::ArgumentType {
+                function: "f".to_string(),
+                index: 1,
+                expected: Type::Bool,
+                found: Type::Int,
+            })
+        );
+        assert_eq!(
+            check(
+                "struct Point { x: int } impl Point { fn scale(self, s: int) {} } \
+                pub fn main() { let p: Point = Point { x: 1 }; p.scale(1.5); }"
+            ),
+            Some(TypeError::ArgumentType {
+                function: "scale".to_string(),
+                index: 0,
+                expected: Type::Int,
+                found: Type::Float,
+            })
+        );
+
+        assert_eq!(
+            check("fn f() -> int { return true; } pub fn main() {}"),
+            Some(TypeError::ReturnType {
+                function: "f".to_string(),
+                expected: Type::Int,
+                found: Type::Bool,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { return 1; }"),
+            Some(TypeError::ReturnType {
+                function: "main".to_string(),
+                expected: Type::Void,
+                found: Type::Int,
+            })
+        );
+        assert_eq!(
+            check("fn f(a: int) -> int { if (a > 0) { return a; } } pub fn main() {}"),
+            Some(TypeError::MissingReturn {
+                function: "f".to_string(),
+            })
+        );
+
+        assert_eq!(
+            check("pub fn main() { let a: int = 1; let b: float = 2.0; let c: bool = a < b; }"),
+            Some(TypeError::BinaryOperands {
+                op: BinOp::Lt,
+                left: Type::Int,
+                right: Type::Float,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let a: int = 1.5; }"),
+            Some(TypeError::Assignment {
+                target: "a".to_string(),
+                expected: Type::Int,
+                found: Type::Float,
+            })
+        );
+        assert_eq!(
+            check("fn f() {} pub fn main() { let a: int = f(); }"),
+            Some(TypeError::Assignment {
+                target: "a".to_string(),
+                expected: Type::Int,
+                found: Type::Void,
+            })
+        );
+        assert_eq!(
+            check("struct Point { x: int } pub fn main() { let p = Point { x: 1 }; p.x = true; }"),
+            Some(TypeError::Assignment {
+                target: "p.x".to_string(),
+                expected: Type::Int,
+                found: Type::Bool,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let xs: [float; 2]; xs[0] = 1; }"),
+            Some(TypeError::Assignment {
+                target: "an element of xs".to_string(),
+                expected: Type::Float,
+                found: Type::Int,
+            })
+        );
+        assert_eq!(
+            check("struct Point { x: int } pub fn main() { let p = Point { x: 1 }; p.z = 1; }"),
+            Some(TypeError::UnknownField {
+                struct_: "p".to_string(),
+                field: "z".to_string(),
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let a: int = 1; a.foo(); }"),
+            Some(TypeError::NotAStruct {
+                name: "a".to_string(),
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let a = 1 + 1.5; }"),
+            Some(TypeError::BinaryOperands {
+                op: BinOp::Add,
+                left: Type::Int,
+                right: Type::Float,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let s = \"a\"; let t = s + s; }"),
+            Some(TypeError::BinaryOperands {
+                op: BinOp::Add,
+                left: Type::String,
+                right: Type::String,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let a; }"),
+            Some(TypeError::NotEnoughInformation)
+        );
+
+        // only numbers and bools are compared, the other types are not scores
+        assert_eq!(
+            check("pub fn main() { let s: string = \"a\"; let b: bool = s == s; }"),
+            Some(TypeError::BinaryOperands {
+                op: BinOp::Eq,
+                left: Type::String,
+                right: Type::String,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let a: int = 1; while (a) { a = 0; } }"),
+            Some(TypeError::Condition { found: Type::Int })
+        );
+        assert_eq!(
+            check("pub fn main() { let a: bool = -true; }"),
+            Some(TypeError::UnaryOperand {
+                op: UnOp::Neg,
+                operand: Type::Bool,
+            })
+        );
+
+        assert_eq!(
+            check("struct Point { x: int, y: int } pub fn main() { let p: Point = Point { x: 1, y: false }; }"),
+            Some(TypeError::FieldType {
+                struct_: "Point".to_string(),
+                field: "y".to_string(),
+                expected: Type::Int,
+                found: Type::Bool,
+            })
+        );
+        assert_eq!(
+            check(
+                "struct Point { x: int, y: int } pub fn main() { let p: Point = Point { x: 1 }; }"
+            ),
+            Some(TypeError::MissingField {
+                struct_: "Point".to_string(),
+                field: "y".to_string(),
+            })
+        );
+        assert_eq!(
+            check("struct Point { x: int } pub fn main() { let p: Point = Point { x: 1 }; let a: int = p.z; }"),
+            Some(TypeError::UnknownField {
+                struct_: "p".to_string(),
+                field: "z".to_string(),
+            })
+        );
+
+        assert_eq!(
+            check("pub fn main() { let xs: [int; 3]; xs[3] = 1; }"),
+            Some(TypeError::IndexOutOfBounds {
+                array: "xs".to_string(),
+                index: 3,
+                len: 3,
+            })
+        );
+        assert_eq!(
+            check("pub fn main() { let xs: [int; 3]; let a: int = xs[1] + xs[true]; }"),
+            Some(TypeError::IndexType {
+                array: "xs".to_string(),
+                found: Type::Bool,
+            })
+        );
+        assert_eq!(
+            check("struct Named { name: string, id: int } pub fn main() {}"),
+            Some(TypeError::StorageField {
+                struct_: "Named".to_string(),
+                field: "name".to_string(),
+                type_: Type::String,
+            })
+        );
+        assert!(matches!(
+            check("struct Point { x: int } pub fn main() { let ps: [Point; 2]; }"),
+            Some(TypeError::ArrayElement {
+                element: Type::Struct(_),
+            })
+        ));
+        assert!(matches!(
+            check("struct Point { x: int } pub fn main() { let p: Point; p.x = 1; println!(p.x, p); }"),
+            Some(TypeError::Print {
+                found: Type::Struct(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn test_type_diagnostics() {
+        let render = |source: &str| {
+            let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+            mock_file_system.insert_file(Utf8PathBuf::from("main.ing"), source);
+
+            let mut program_merger = ProgramMerger::new("pkg");
+
+            program_merger
+                .read_package("pkg", mock_file_system)
+                .unwrap();
+
+            let mut front_program = program_merger.return_merged();
+
+            let error = pass(
+                &mut front_program,
+                &mut vec![Box::new(AnnotateTypes), Box::new(CheckTypes)],
+            )
+            .err()
+            .unwrap();
+            Diagnostic::from(error).render(Some(source))
+        };
+
+        // an assignment is pointed at as a whole, anything else at the expression that has the wrong type
+        assert_eq!(
+            render("pub fn main() {\n    let a: int = 1;\n    a = 1.5;\n}"),
+            "error: Can not assign float to a, which is int\n \
+             --> main.ing:3:5\n  \
+               |\n\
+             3 |     a = 1.5;\n  \
+               |     ^^^^^^^"
+        );
+        assert_eq!(
+            render("pub fn main() {\n    let s = \"a\";\n    while (s == s) {}\n}"),
+            "error: Can not apply Eq to string and string\n \
+             --> main.ing:3:12\n  \
+               |\n\
+             3 |     while (s == s) {}\n  \
+               |            ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_type_annotation_calls() {
+        let mut mock_file_system = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_file_system.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "struct Point { x: int } impl Point { fn half(self) -> float { return self.x as float / 2.0; } } \
+            fn f(a: int) -> bool { return a > 0; } \
+            pub fn main() { let p = Point { x: 1 }; let a = f(p.x); let b = p.half(); let c = p.x; \
+            let xs: [int; 3]; let n = xs.len(); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("pkg");
+
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
+
+        let mut front_program = program_merger.return_merged();
+
+        assert!(pass(
+            &mut front_program,
+            &mut vec![Box::new(AnnotateTypes), Box::new(CheckTypes)]
+        )
+        .is_ok());
+
+        let statements = &front_program
+            .definitions
+            .function_definitions
+            .get(&Rc::from(GlobalResolvedName {
+                package: Rc::from("pkg"),
+                module: Rc::from("/root"),
+                name: "0_main".to_string(),
+            }))
+            .unwrap()
+            .body
+            .statements;
+        for (i, type_) in [
+            (1, Type::Bool),
+            (2, Type::Float),
+            (3, Type::Int),
+            (5, Type::Int),
+        ] {
+            let Statement::VarDecl(x) = &statements[i] else {
+                panic!("Expected VarDecl");
+            };
+            assert_eq!(x.var_def.type_.as_ref().unwrap(), &type_);
+        }
+    }
 }
@@ -0,0 +1,426 @@
+use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
+use crate::front::ast_types::{
+    AtomicExpression, Block, Else, Expression, ExpressionEnum, FnCall, GlobalResolvedName, Index,
+    LiteralValue, NamePath, Statement, StructDef, StructLiteral, Type,
+};
+use crate::front::exporter::export::FrontProgram;
+use crate::front::passes::types::declared_types::{is_array_len, DeclaredTypes};
+use crate::front::passes::types::type_expression::{
+    binop_type_resolver, literal_types, same_type, unop_type_resolver,
+};
+use crate::front::passes::types::TypeError;
+use crate::front::passes::PassError;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub type ResolveResult<T> = GenericResolveResult<T, PassError>;
+
+// checks the types of a program whose variables are already typed
+pub struct TypeChecker {
+    declared: DeclaredTypes,
+    var_types: HashMap<Rc<GlobalResolvedName>, Type>,
+    function: String,
+    return_type: Type,
+}
+
+// whether every path through the block ends in a return
+fn always_returns(block: &Block) -> bool {
+    block.statements.iter().any(|statement| match statement {
+        Statement::Return(_) => true,
+        Statement::Block(x) => always_returns(x),
+        Statement::If(x) => {
+            let mut if_ = x;
+            loop {
+                if !always_returns(&if_.body) {
+                    break false;
+                }
+                match &if_.else_ {
+                    Some(Else::If(x)) => if_ = x,
+                    Some(Else::Block(x)) => break always_returns(x),
+                    None => break false,
+                }
+            }
+        }
+        _ => false,
+    })
+}
+
+// `target` names the variable, field or element the value is assigned to
+fn check_assignment(target: String, expected: &Type, found: Type) -> Result<(), PassError> {
+    if !same_type(expected, &found) {
+        return Err(TypeError::Assignment {
+            target,
+            expected: expected.clone(),
+            found,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+impl TypeChecker {
+    pub fn new(program: &FrontProgram) -> TypeChecker {
+        TypeChecker {
+            declared: DeclaredTypes::new(program),
+            var_types: HashMap::new(),
+            function: String::new(),
+            return_type: Type::Void,
+        }
+    }
+
+    pub fn register_variable(&mut self, name: &Rc<GlobalResolvedName>, type_: Type) {
+        self.var_types.insert(Rc::clone(name), type_);
+    }
+
+    pub fn enter_function(&mut self, name: &str, return_type: &Type) {
+        self.function = name.to_string();
+        self.return_type = return_type.clone();
+    }
+
+    pub fn check_returns(&self, body: &Block) -> Result<(), PassError> {
+        if self.return_type != Type::Void && !always_returns(body) {
+            return Err(TypeError::MissingReturn {
+                function: self.function.clone(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    // the fields of a struct are laid out over scores, so they can not be held in storage
+    pub fn check_struct(&self, struct_def: &StructDef) -> Result<(), PassError> {
+        let mut fields: Vec<_> = struct_def.map.iter().collect();
+        fields.sort_by_key(|(name, _)| *name);
+        for (field, type_) in fields {
+            if matches!(type_, Type::String | Type::Array(_, _) | Type::Compound(_)) {
+                return Err(TypeError::StorageField {
+                    struct_: struct_def.type_name.raw.clone(),
+                    field: field.clone(),
+                    type_: type_.clone(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    fn name_path_type(&self, name_path: &NamePath) -> Result<Type, PassError> {
+        let mut type_ = self
+            .var_types
+            .get(name_path.name.global_resolved.as_ref().unwrap())
+            .cloned()
+            .ok_or(TypeError::NotEnoughInformation)?;
+
+        let mut owner = name_path.name.raw.clone();
+        for field in &name_path.path {
+            if !matches!(type_, Type::Struct(_) | Type::Compound(_)) {
+                return Err(TypeError::NotAStruct { name: owner }.into());
+            }
+            type_ =
+                self.declared
+                    .field_type(&type_, field)
+                    .ok_or_else(|| TypeError::UnknownField {
+                        struct_: owner.clone(),
+                        field: field.clone(),
+                    })?;
+            owner = field.clone();
+        }
+        Ok(type_)
+    }
+
+    // a constant index is checked against the length of the array
+    fn element_type(&mut self, index: &mut Index) -> Result<Type, PassError> {
+        let found = index.index.visit(self)?.unwrap();
+        let name = index
+            .array
+            .path
+            .last()
+            .unwrap_or(&index.array.name.raw)
+            .clone();
+        let Type::Array(element, len) = self.name_path_type(&index.array)? else {
+            return Err(TypeError::NotAnArray { name }.into());
+        };
+        if found != Type::Int {
+            return Err(TypeError::IndexType { array: name, found }.into());
+        }
+        if let ExpressionEnum::AtomicExpression(AtomicExpression::Literal(LiteralValue::Int(i))) =
+            index.index.expr
+        {
+            if i < 0 || i as u32 >= len {
+                return Err(TypeError::IndexOutOfBounds {
+                    array: name,
+                    index: i,
+                    len,
+                }
+                .into());
+            }
+        }
+        Ok(*element)
+    }
+
+    // a condition has to be a bool, an int is only one through a comparison
+    fn check_condition(&mut self, cond: &mut Expression) -> Result<(), PassError> {
+        let found = cond.visit(self)?.unwrap();
+        if found != Type::Bool {
+            return Err(PassError::from(TypeError::Condition { found }).at(&cond.span));
+        }
+        Ok(())
+    }
+
+    fn check_fn_call(&mut self, fn_call: &mut FnCall) -> Result<Type, PassError> {
+        // a method is found through the type of its receiver, which is its first argument
+        let (name, skipped) = match &fn_call.receiver {
+            Some(receiver) => {
+                let receiver_type = self.name_path_type(receiver)?;
+                if is_array_len(&receiver_type, &fn_call.name.raw) {
+                    if !fn_call.args.is_empty() {
+                        return Err(TypeError::ArgumentCount {
+                            function: fn_call.name.raw.clone(),
+                            expected: 0,
+                            found: fn_call.args.len(),
+                        }
+                        .into());
+                    }
+                    return Ok(Type::Int);
+                }
+                let Type::Struct(struct_name) = &receiver_type else {
+                    return Err(TypeError::NotAStruct {
+                        name: receiver.name.raw.clone(),
+                    }
+                    .into());
+                };
+                let name = self
+                    .declared
+                    .method(&receiver_type, &fn_call.name.raw)
+                    .ok_or_else(|| TypeError::UnknownField {
+                        struct_: struct_name.raw.clone(),
+                        field: fn_call.name.raw.clone(),
+                    })?;
+                (name, 1)
+            }
+            None => (Rc::clone(fn_call.name.global_resolved.as_ref().unwrap()), 0),
+        };
+
+        let mut found = vec![];
+        for arg in &mut fn_call.args {
+            found.push(arg.visit(self)?.unwrap());
+        }
+
+        let signature = self.declared.functions.get(&name).unwrap();
+        let expected = &signature.args[skipped..];
+        if expected.len() != found.len() {
+            return Err(TypeError::ArgumentCount {
+                function: signature.name.clone(),
+                expected: expected.len(),
+                found: found.len(),
+            }
+            .into());
+        }
+        for (index, (expected, found)) in expected.iter().zip(found).enumerate() {
+            if !same_type(expected, &found) {
+                return Err(TypeError::ArgumentType {
+                    function: signature.name.clone(),
+                    index,
+                    expected: expected.clone(),
+                    found,
+                }
+                .into());
+            }
+        }
+        Ok(signature.return_type.clone())
+    }
+
+    fn check_struct_literal(
+        &mut self,
+        struct_literal: &mut StructLiteral,
+    ) -> Result<Type, PassError> {
+        let type_ = Type::Struct(struct_literal.type_name.clone());
+        let mut found = HashMap::new();
+        for (name, field) in &mut struct_literal.fields {
+            found.insert(name.clone(), field.visit(self)?.unwrap());
+        }
+
+        let struct_name = &struct_literal.type_name.raw;
+        let fields = self.declared.struct_fields(&type_).unwrap();
+        for (field, expected) in fields {
+            match found.get(field) {
+                Some(found) if !same_type(expected, found) => {
+                    return Err(TypeError::FieldType {
+                        struct_: struct_name.clone(),
+                        field: field.clone(),
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    }
+                    .into())
+                }
+                Some(_) => {}
+                None => {
+                    return Err(TypeError::MissingField {
+                        struct_: struct_name.clone(),
+                        field: field.clone(),
+                    }
+                    .into())
+                }
+            }
+        }
+        if let Some(field) = found.keys().find(|field| !fields.contains_key(*field)) {
+            return Err(TypeError::UnknownField {
+                struct_: struct_name.clone(),
+                field: field.clone(),
+            }
+            .into());
+        }
+        Ok(type_)
+    }
+
+    fn expression_type(&mut self, x: &mut Expression) -> Result<Type, PassError> {
+        Ok(match &mut x.expr {
+            ExpressionEnum::AtomicExpression(atomic) => match atomic {
+                AtomicExpression::Literal(literal) => {
+                    // the fields of a compound are typed first
+                    literal.visit(self)?;
+                    literal_types(literal)
+                }
+                AtomicExpression::Variable(name_path) => self.name_path_type(name_path)?,
+                AtomicExpression::FnCall(fn_call) => self.check_fn_call(fn_call)?,
+                AtomicExpression::StructLiteral(struct_literal) => {
+                    self.check_struct_literal(struct_literal)?
+                }
+                AtomicExpression::Index(index) => self.element_type(index)?,
+            },
+            ExpressionEnum::Unary(unop, e) => {
+                let operand = e.visit(self)?.unwrap();
+                unop_type_resolver(unop, &operand).map_err(|_| TypeError::UnaryOperand {
+                    op: unop.clone(),
+                    operand,
+                })?
+            }
+            ExpressionEnum::Binary(e0, binop, e1) => {
+                let left = e0.visit(self)?.unwrap();
+                let right = e1.visit(self)?.unwrap();
+                binop_type_resolver(binop, &left, &right).map_err(|_| {
+                    TypeError::BinaryOperands {
+                        op: binop.clone(),
+                        left,
+                        right,
+                    }
+                })?
+            }
+        })
+    }
+}
+
+impl Visitor<Type, PassError> for TypeChecker {
+    fn apply(&mut self, ast_node: &mut ASTNodeEnum) -> ResolveResult<Type> {
+        match ast_node {
+            ASTNodeEnum::VarDef(x) => {
+                let type_ = x.type_.clone().ok_or(TypeError::NotEnoughInformation)?;
+                // arrays are lists of scores in storage, so their elements are numbers or bools
+                if let Type::Array(element, _) = &type_ {
+                    if !matches!(
+                        **element,
+                        Type::Int | Type::Float | Type::Double | Type::Bool
+                    ) {
+                        return Err(TypeError::ArrayElement {
+                            element: *element.clone(),
+                        }
+                        .into());
+                    }
+                }
+                self.register_variable(x.name.global_resolved.as_ref().unwrap(), type_);
+            }
+
+            ASTNodeEnum::VarDecl(x) => {
+                let found = match &mut x.expr {
+                    Some(expr) => expr.visit(self)?,
+                    None => None,
+                };
+                x.var_def.visit(self).map_err(|error| error.at(&x.span))?;
+                if let Some(found) = found {
+                    let expected = x.var_def.type_.as_ref().unwrap();
+                    check_assignment(x.var_def.name.raw.clone(), expected, found)
+                        .map_err(|error| error.at(&x.span))?;
+                }
+            }
+
+            ASTNodeEnum::VarAssign(x) => {
+                let expected = self
+                    .name_path_type(&x.name_path)
+                    .map_err(|error| error.at(&x.span))?;
+                let found = x.expr.visit(self)?.unwrap();
+                let mut target = vec![x.name_path.name.raw.clone()];
+                target.extend(x.name_path.path.iter().cloned());
+                check_assignment(target.join("."), &expected, found)
+                    .map_err(|error| error.at(&x.span))?;
+            }
+
+            ASTNodeEnum::IndexAssign(x) => {
+                let expected = self
+                    .element_type(&mut x.element)
+                    .map_err(|error| error.at(&x.span))?;
+                let found = x.expr.visit(self)?.unwrap();
+                let target = format!("an element of {}", x.element.array.name.raw);
+                check_assignment(target, &expected, found).map_err(|error| error.at(&x.span))?;
+            }
+
+            ASTNodeEnum::If(x) => {
+                self.check_condition(&mut x.cond)?;
+                x.body.visit(self)?;
+                if let Some(else_) = &mut x.else_ {
+                    else_.visit(self)?;
+                }
+            }
+
+            ASTNodeEnum::While(x) => {
+                self.check_condition(&mut x.cond)?;
+                x.body.visit(self)?;
+            }
+
+            ASTNodeEnum::For(x) => {
+                if let Some(init) = &mut x.init {
+                    init.visit(self)?;
+                }
+                if let Some(cond) = &mut x.cond {
+                    self.check_condition(cond)?;
+                }
+                if let Some(step) = &mut x.step {
+                    step.visit(self)?;
+                }
+                x.body.visit(self)?;
+            }
+
+            ASTNodeEnum::Statement(Statement::Return(x)) => {
+                let found = x.visit(self)?.unwrap();
+                if !same_type(&found, &self.return_type) {
+                    return Err(PassError::from(TypeError::ReturnType {
+                        function: self.function.clone(),
+                        expected: self.return_type.clone(),
+                        found,
+                    })
+                    .at(&x.span));
+                }
+            }
+
+            ASTNodeEnum::Statement(Statement::Print(args)) => {
+                for arg in args {
+                    let found = arg.visit(self)?.unwrap();
+                    if matches!(found, Type::Struct(_) | Type::Array(_, _)) {
+                        return Err(PassError::from(TypeError::Print { found }).at(&arg.span));
+                    }
+                }
+            }
+
+            ASTNodeEnum::Expression(x) => {
+                let type_ = self.expression_type(x).map_err(|error| error.at(&x.span))?;
+                x.type_ = Some(type_.clone());
+                return Ok((false, Some(type_)));
+            }
+
+            // functions defined in a block are checked on their own
+            ASTNodeEnum::FnDef(_) | ASTNodeEnum::Impl(_) => return Ok((false, None)),
+
+            _ => return Ok((true, None)),
+        };
+        Ok((false, None))
+    }
+}
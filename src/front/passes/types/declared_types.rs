@@ -0,0 +1,87 @@
+use crate::front::ast_types::{GlobalResolvedName, Type};
+use crate::front::exporter::export::FrontProgram;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// `xs.len()`, the only method of an array
+pub fn is_array_len(type_: &Type, method: &str) -> bool {
+    matches!(type_, Type::Array(_, _)) && method == "len"
+}
+
+pub struct Signature {
+    pub name: String,
+    pub args: Vec<Type>,
+    pub return_type: Type,
+}
+
+/*
+The types of the functions and structs of a program. They are copied out of it, so that the bodies of the functions
+can be changed while they are looked up.
+ */
+pub struct DeclaredTypes {
+    pub functions: HashMap<Rc<GlobalResolvedName>, Signature>,
+    structs: HashMap<Rc<GlobalResolvedName>, HashMap<String, Type>>,
+}
+
+impl DeclaredTypes {
+    pub fn new(program: &FrontProgram) -> DeclaredTypes {
+        DeclaredTypes {
+            functions: program
+                .definitions
+                .function_definitions
+                .iter()
+                .map(|(name, fn_def)| {
+                    (
+                        Rc::clone(name),
+                        Signature {
+                            name: fn_def.name.raw.clone(),
+                            args: fn_def
+                                .args
+                                .iter()
+                                .map(|arg| arg.type_.clone().unwrap_or(Type::Void))
+                                .collect(),
+                            return_type: fn_def.return_type.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            structs: program
+                .definitions
+                .struct_definitions
+                .iter()
+                .map(|(name, struct_def)| (Rc::clone(name), struct_def.map.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn struct_fields(&self, type_: &Type) -> Option<&HashMap<String, Type>> {
+        let Type::Struct(struct_name) = type_ else {
+            return None;
+        };
+        self.structs.get(struct_name.global_resolved.as_ref()?)
+    }
+
+    // the type of a field of a struct or a compound
+    pub fn field_type(&self, type_: &Type, field: &str) -> Option<Type> {
+        match type_ {
+            Type::Compound(fields) => fields.get(field).cloned(),
+            _ => self.struct_fields(type_)?.get(field).cloned(),
+        }
+    }
+
+    // the methods of a struct are defined next to it as `<struct>.<method>`
+    pub fn method(&self, type_: &Type, method: &str) -> Option<Rc<GlobalResolvedName>> {
+        let Type::Struct(struct_name) = type_ else {
+            return None;
+        };
+        let struct_global_name = struct_name.global_resolved.as_ref()?;
+        let method_name = GlobalResolvedName {
+            package: Rc::clone(&struct_global_name.package),
+            module: Rc::clone(&struct_global_name.module),
+            name: format!("{}.{method}", struct_global_name.name),
+        };
+        self.functions
+            .get_key_value(&method_name)
+            .map(|(name, _)| Rc::clone(name))
+    }
+}
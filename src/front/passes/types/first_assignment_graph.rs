@@ -1,15 +1,11 @@
 use crate::front::ast_types::visitor::{ASTNodeEnum, GenericResolveResult, Visitable, Visitor};
 use crate::front::exporter::export::FrontProgram;
 use crate::front::passes::types::var_def_table::VarDefTable;
+use std::convert::Infallible;
 
-#[derive(Debug, PartialEq)]
-pub enum ResolverError {
-    Unknown,
-}
-
-pub type ResolveResult<T> = GenericResolveResult<T, ResolverError>;
+pub type ResolveResult<T> = GenericResolveResult<T, Infallible>;
 
-impl Visitor<(), ResolverError> for VarDefTable {
+impl Visitor<(), Infallible> for VarDefTable {
     fn apply(&mut self, ast_node: &mut ASTNodeEnum) -> ResolveResult<()> {
         match ast_node {
             ASTNodeEnum::VarDef(x) => {
@@ -34,21 +30,22 @@ impl Visitor<(), ResolverError> for VarDefTable {
             }
 
             ASTNodeEnum::If(_)
-            | ASTNodeEnum::Else(_)
             | ASTNodeEnum::While(_)
             | ASTNodeEnum::For(_)
             | ASTNodeEnum::Statement(_)
             | ASTNodeEnum::Block(_)
-            | ASTNodeEnum::FnDef(_)
-            | ASTNodeEnum::FnCall(_) => return Ok((true, None)),
+            | ASTNodeEnum::FnCall(_)
+            | ASTNodeEnum::StructLiteral(_)
+            | ASTNodeEnum::IndexAssign(_)
+            | ASTNodeEnum::Index(_) => return Ok((true, None)),
 
             ASTNodeEnum::AtomicExpression(_)
             | ASTNodeEnum::Expression(_)
             | ASTNodeEnum::NamePath(_)
             | ASTNodeEnum::Reference(_)
             | ASTNodeEnum::StructDef(_)
-            | ASTNodeEnum::LiteralValue(_)
-            | ASTNodeEnum::Definition(_)
+            | ASTNodeEnum::FnDef(_)
+            | ASTNodeEnum::Impl(_)
             | ASTNodeEnum::Module(_)
             | ASTNodeEnum::Use(_) => return Ok((false, None)),
         };
@@ -60,6 +57,9 @@ pub fn create_first_assignment_graph(program: &mut FrontProgram) -> VarDefTable
     let mut table = VarDefTable::new(program);
 
     for v in program.definitions.function_definitions.values_mut() {
+        for arg in &mut v.args {
+            arg.visit(&mut table).unwrap();
+        }
         for statement in &mut v.body.statements {
             statement.visit(&mut table).unwrap();
         }
@@ -1,8 +1,8 @@
 use crate::front::ast_types::{
-    AtomicExpression, BinOp, Expression, ExpressionEnum, GlobalResolvedName, LiteralValue, Type,
-    UnOp,
+    AtomicExpression, BinOp, Compound, CompoundValue, Expression, ExpressionEnum,
+    GlobalResolvedName, LiteralValue, Type, UnOp,
 };
-use crate::front::exporter::export::FrontProgram;
+use crate::front::passes::types::declared_types::{is_array_len, DeclaredTypes};
 use crate::front::passes::types::var_def_table::VarTypeNode;
 use either::Left;
 use std::collections::HashMap;
@@ -30,15 +30,36 @@ impl TypeDependency {
 
         let tree = match &expr.expr {
             ExpressionEnum::AtomicExpression(x) => match x {
+                AtomicExpression::Literal(LiteralValue::Compound(x)) => {
+                    let child = TypeDependency::from_compound(x);
+                    deps.extend(child.deps);
+                    child.tree
+                }
                 AtomicExpression::Literal(x) => TypeTree::Type(literal_types(x)),
+                AtomicExpression::Index(x) => {
+                    let var_name = x.array.name.global_resolved.as_ref().unwrap().clone();
+                    deps.push(var_name.clone());
+                    TypeTree::Element(var_name)
+                }
+                AtomicExpression::StructLiteral(x) => {
+                    TypeTree::Type(Type::Struct(x.type_name.clone()))
+                }
                 AtomicExpression::Variable(x) => {
                     let var_name = x.name.global_resolved.as_ref().unwrap().clone();
                     deps.push(var_name.clone());
-                    TypeTree::Var(var_name)
-                }
-                AtomicExpression::FnCall(x) => {
-                    TypeTree::FnCall(x.name.global_resolved.as_ref().unwrap().clone())
+                    TypeTree::from_name_path(var_name, &x.path)
                 }
+                AtomicExpression::FnCall(x) => match &x.receiver {
+                    Some(receiver) => {
+                        let var_name = receiver.name.global_resolved.as_ref().unwrap().clone();
+                        deps.push(var_name.clone());
+                        TypeTree::Method(
+                            Box::new(TypeTree::from_name_path(var_name, &receiver.path)),
+                            x.name.raw.clone(),
+                        )
+                    }
+                    None => TypeTree::FnCall(x.name.global_resolved.as_ref().unwrap().clone()),
+                },
             },
             ExpressionEnum::Unary(unop, x) => {
                 let child = TypeDependency::new(x);
@@ -63,6 +84,25 @@ impl TypeDependency {
 
         TypeDependency { deps, tree }
     }
+
+    // the type of a compound literal is made of the types of its fields
+    fn from_compound(compound: &Compound) -> TypeDependency {
+        let mut deps = Vec::new();
+        let mut fields = HashMap::new();
+        for (name, value) in compound {
+            let child = match value {
+                CompoundValue::Expression(x) => TypeDependency::new(x),
+                CompoundValue::Compound(x) => TypeDependency::from_compound(x),
+            };
+            deps.extend(child.deps);
+            fields.insert(name.clone(), child.tree);
+        }
+
+        TypeDependency {
+            deps,
+            tree: TypeTree::Compound(fields),
+        }
+    }
 }
 
 pub enum TypeTree {
@@ -70,23 +110,38 @@ pub enum TypeTree {
     UnOp(UnOpNode),
     Type(Type),
     Var(Rc<GlobalResolvedName>),
+    // an element of the array held by the variable
+    Element(Rc<GlobalResolvedName>),
     FnCall(Rc<GlobalResolvedName>),
+    // `p.len()`, the method of the type of the receiver
+    Method(Box<TypeTree>, String),
+    Compound(HashMap<String, TypeTree>),
+    // `p.pos.x`, a field of a struct or compound
+    Field(Box<TypeTree>, Vec<String>),
 }
 
 impl TypeTree {
+    fn from_name_path(name: Rc<GlobalResolvedName>, path: &[String]) -> TypeTree {
+        if path.is_empty() {
+            TypeTree::Var(name)
+        } else {
+            TypeTree::Field(Box::new(TypeTree::Var(name)), path.to_vec())
+        }
+    }
+
     pub fn resolve_type(
         &self,
-        front_program: &FrontProgram,
+        declared: &DeclaredTypes,
         vars: &HashMap<Rc<GlobalResolvedName>, VarTypeNode>,
     ) -> TypeResult<Type> {
         match self {
             TypeTree::BinOp(node) => {
-                let left = node.left.resolve_type(front_program, vars)?;
-                let right = node.right.resolve_type(front_program, vars)?;
+                let left = node.left.resolve_type(declared, vars)?;
+                let right = node.right.resolve_type(declared, vars)?;
                 binop_type_resolver(&node.op, &left, &right)
             }
             TypeTree::UnOp(node) => {
-                let operand = node.operand.resolve_type(front_program, vars)?;
+                let operand = node.operand.resolve_type(declared, vars)?;
                 unop_type_resolver(&node.op, &operand)
             }
             TypeTree::Type(type_) => Ok(type_.clone()),
@@ -101,13 +156,45 @@ impl TypeTree {
                     panic!("Dependency Variable Type not in table!")
                 }
             }
+            TypeTree::Element(name) => {
+                match TypeTree::Var(Rc::clone(name)).resolve_type(declared, vars)? {
+                    Type::Array(element, _) => Ok(*element),
+                    _ => Err(TypeError::TypeMismatch),
+                }
+            }
+            TypeTree::Compound(fields) => {
+                let mut types = HashMap::new();
+                for (name, field) in fields {
+                    types.insert(name.clone(), field.resolve_type(declared, vars)?);
+                }
+                Ok(Type::Compound(types))
+            }
             TypeTree::FnCall(name) => {
-                if let Some(fn_def) = front_program.definitions.function_definitions.get(name) {
-                    Ok(fn_def.return_type.clone())
+                if let Some(signature) = declared.functions.get(name) {
+                    Ok(signature.return_type.clone())
                 } else {
                     panic!("Dependency Function not in table!")
                 }
             }
+            TypeTree::Method(receiver, method) => {
+                let receiver = receiver.resolve_type(declared, vars)?;
+                if is_array_len(&receiver, method) {
+                    return Ok(Type::Int);
+                }
+                let name = declared
+                    .method(&receiver, method)
+                    .ok_or(TypeError::TypeMismatch)?;
+                Ok(declared.functions[&name].return_type.clone())
+            }
+            TypeTree::Field(tree, path) => {
+                let mut type_ = tree.resolve_type(declared, vars)?;
+                for field in path {
+                    type_ = declared
+                        .field_type(&type_, field)
+                        .ok_or(TypeError::TypeMismatch)?;
+                }
+                Ok(type_)
+            }
         }
     }
 }
@@ -115,19 +202,58 @@ impl TypeTree {
 #[derive(Debug, PartialEq)]
 pub enum TypeError {
     TypeMismatch,
-    MultipleTypes,
 }
 
 pub type TypeResult<T> = Result<T, TypeError>;
 
+// the fields of a compound literal have to be typed already
 pub fn literal_types(literal: &LiteralValue) -> Type {
+    fn compound_type(compound: &Compound) -> Type {
+        Type::Compound(
+            compound
+                .iter()
+                .map(|(name, value)| {
+                    let type_ = match value {
+                        CompoundValue::Expression(x) => x.type_.clone().unwrap_or_else(|| {
+                            panic!("The field {name} has to be typed before its compound")
+                        }),
+                        CompoundValue::Compound(x) => compound_type(x),
+                    };
+                    (name.clone(), type_)
+                })
+                .collect(),
+        )
+    }
+
     match literal {
         LiteralValue::Null => Type::Void,
         LiteralValue::Int(_) => Type::Int,
+        LiteralValue::Bool(_) => Type::Bool,
         LiteralValue::Float(_) => Type::Float,
         LiteralValue::Double(_) => Type::Double,
         LiteralValue::String(_) => Type::String,
-        LiteralValue::Compound(_) => Type::Void,
+        LiteralValue::Compound(x) => compound_type(x),
+    }
+}
+
+// structs are the same when they are the same definition, whatever name they were referred to by
+pub fn same_type(type_0: &Type, type_1: &Type) -> bool {
+    match (type_0, type_1) {
+        (Type::Struct(struct_0), Type::Struct(struct_1)) => {
+            struct_0.global_resolved == struct_1.global_resolved
+        }
+        (Type::Array(element_0, len_0), Type::Array(element_1, len_1)) => {
+            len_0 == len_1 && same_type(element_0, element_1)
+        }
+        (Type::Compound(fields_0), Type::Compound(fields_1)) => {
+            fields_0.len() == fields_1.len()
+                && fields_0.iter().all(|(name, field_0)| {
+                    fields_1
+                        .get(name)
+                        .is_some_and(|field_1| same_type(field_0, field_1))
+                })
+        }
+        _ => type_0 == type_1,
     }
 }
 
@@ -144,16 +270,25 @@ pub fn binop_type_resolver(op: &BinOp, left: &Type, right: &Type) -> TypeResult<
                 Err(TypeError::TypeMismatch)
             }
         }
-        BinOp::Eq | BinOp::Neq | BinOp::Lt | BinOp::Leq | BinOp::Gt | BinOp::Geq => {
-            if left == right {
-                Ok(Type::Int)
+        // strings, structs, arrays and compounds are not scores, so they can not be compared
+        BinOp::Eq | BinOp::Neq => {
+            if left == right && matches!(left, Type::Int | Type::Float | Type::Double | Type::Bool)
+            {
+                Ok(Type::Bool)
+            } else {
+                Err(TypeError::TypeMismatch)
+            }
+        }
+        BinOp::Lt | BinOp::Leq | BinOp::Gt | BinOp::Geq => {
+            if left == right && matches!(left, Type::Int | Type::Float | Type::Double) {
+                Ok(Type::Bool)
             } else {
                 Err(TypeError::TypeMismatch)
             }
         }
         BinOp::And | BinOp::Or => {
-            if left == &Type::Int && right == &Type::Int {
-                Ok(Type::Int)
+            if left == &Type::Bool && right == &Type::Bool {
+                Ok(Type::Bool)
             } else {
                 Err(TypeError::TypeMismatch)
             }
@@ -174,9 +309,23 @@ pub fn unop_type_resolver(op: &UnOp, operand: &Type) -> TypeResult<Type> {
                 Err(TypeError::TypeMismatch)
             }
         }
+        UnOp::Cast(type_) => {
+            // only numbers can be converted, and only explicitly, a bool only to and from an int
+            let is_number = |x: &Type| matches!(x, Type::Int | Type::Float | Type::Double);
+            if (is_number(operand) && is_number(type_))
+                || matches!(
+                    (operand, type_),
+                    (Type::Bool, Type::Int) | (Type::Int, Type::Bool) | (Type::Bool, Type::Bool)
+                )
+            {
+                Ok(type_.clone())
+            } else {
+                Err(TypeError::TypeMismatch)
+            }
+        }
         UnOp::Not => {
-            if operand == &Type::Int {
-                Ok(Type::Int)
+            if operand == &Type::Bool {
+                Ok(Type::Bool)
             } else {
                 Err(TypeError::TypeMismatch)
             }
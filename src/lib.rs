@@ -21,7 +21,9 @@ mod tests {
 
         let mut program_merger = ProgramMerger::new("pkg");
 
-        program_merger.read_package("pkg", mock_file_system);
+        program_merger
+            .read_package("pkg", mock_file_system)
+            .unwrap();
 
         let front_program = program_merger.return_merged();
         let mut program = front_program.export_program();
@@ -2,15 +2,43 @@
 pub enum AddressOrigin {
     User(String),
     CtxGenerated(String, u32),
-    If,
     Return,
     Const(i32),
 }
 
+// where the value of an address is held
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
+pub enum AddressKind {
+    // a score of the blst objective, for numbers
+    Score,
+    // a path in the data storage that holds the variables, for strings and arrays
+    Storage,
+}
+
 #[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub struct Address {
     pub name: AddressOrigin,
     pub offset: i32,
+    pub kind: AddressKind,
+}
+
+impl Address {
+    // the score at the given offset from this one, used for the fields of structs
+    pub fn with_offset(&self, offset: i32) -> Address {
+        Address {
+            name: self.name.clone(),
+            offset: self.offset + offset,
+            kind: self.kind.clone(),
+        }
+    }
+
+    pub fn with_kind(&self, kind: AddressKind) -> Address {
+        Address {
+            name: self.name.clone(),
+            offset: self.offset,
+            kind,
+        }
+    }
 }
 
 pub type FunctionName = String;
@@ -30,9 +58,6 @@ pub enum IrScoreOperationType {
     Gt,
     Eq,
     Neq,
-
-    And,
-    Or,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -82,6 +107,9 @@ pub struct CompareVal {
 pub enum Cond {
     CheckVal(CheckVal),
     CompareVal(CompareVal),
+    Not(Box<Cond>),
+    // checked as a chain of `execute if ... if ...`, so it can not be inverted
+    And(Vec<Cond>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -91,6 +119,26 @@ pub struct IrIf {
     pub body: Box<IrStatement>,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrayIndex {
+    Const(i32),
+    Score(Address),
+}
+
+// an element of an array held in storage
+#[derive(Debug, PartialEq, Clone)]
+pub struct IrArrayElement {
+    pub array: Address,
+    pub index: ArrayIndex,
+}
+
+// a field of a compound held in storage, `c.pos.x` is the path [pos, x] of `c`
+#[derive(Debug, PartialEq, Clone)]
+pub struct IrCompoundField {
+    pub compound: Address,
+    pub path: Vec<String>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum IrStatement {
     ScoreOperation(IrScoreOperation),
@@ -98,6 +146,26 @@ pub enum IrStatement {
     FnCall(IrFnCall),
     Return,
     Block(IrBlock),
+    // saves the scores of a recursive function on the call stack before a call that may reenter it
+    PushFrame(Vec<Address>),
+    // restores the scores saved by the matching PushFrame
+    PopFrame(Vec<Address>),
+    // sets the array to a list of the given number of zeros
+    ArrayInit(Address, u32),
+    // copies an element of an array into a score
+    ArrayGet(IrArrayElement, Address),
+    // copies a score into an element of an array
+    ArraySet(IrArrayElement, Address),
+    // sets the storage address to a value written as SNBT
+    StorageSet(Address, String),
+    // copies the second storage address into the first
+    StorageCopy(Address, Address),
+    // copies a field of a compound into a score or storage address
+    FieldGet(IrCompoundField, Address),
+    // copies a score or storage address into a field of a compound
+    FieldSet(IrCompoundField, Address),
+    // shows the values to every player on one line, separated by spaces
+    Print(Vec<Address>),
 }
 
 #[derive(Debug, PartialEq, Clone)]
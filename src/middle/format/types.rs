@@ -3,8 +3,14 @@ use std::collections::{HashMap, HashSet};
 
 pub type GlobalName = String;
 
+/// The function that runs when the datapack is loaded, it also initialises the global variables. Its namespace `blst-`
+/// is one that no package name is sanitized to, see the linker.
+pub static LOAD_FN_NAME: &str = "blst-/load";
+
 #[derive(Debug, PartialEq)]
 pub struct Program {
     pub public_functions: HashSet<GlobalName>,
     pub function_definitions: HashMap<GlobalName, IrFnDef>,
+    pub constants: HashSet<i32>,
+    pub load_function: IrFnDef,
 }
@@ -17,8 +17,14 @@ impl CheckUsed for IrStatement {
     fn add_used(&self, used: &mut Used, program: &mut Program) {
         match self {
             IrStatement::If(x) => x.add_used(used, program),
-            IrStatement::FnCall(x) => {
-                used.functions.insert(x.fn_name.clone());
+            // the called function can call other functions as well
+            IrStatement::FnCall(x) if used.functions.insert(x.fn_name.clone()) => {
+                if let Some(fn_def) = program.function_definitions.remove(&x.fn_name) {
+                    fn_def.add_used(used, program);
+                    program
+                        .function_definitions
+                        .insert(x.fn_name.clone(), fn_def);
+                }
             }
             IrStatement::Block(x) => x.add_used(used, program),
             _ => {}
@@ -60,6 +66,12 @@ impl Pass for DeleteUnused {
             variables: HashSet::new(),
         };
 
+        // the global initialisers can call functions as well
+        let load_function = program.load_function.clone();
+        for statement in &load_function.statements {
+            statement.add_used(&mut used, program);
+        }
+
         for global_name in program.public_functions.clone() {
             if let Some(fn_def) = program.function_definitions.remove(&global_name) {
                 fn_def.add_used(&mut used, program);
@@ -98,8 +110,8 @@ mod tests {
         mock_fs_1.insert_file(Utf8PathBuf::from("main.ing"), "pub fn libfunc() {}");
 
         let mut program_merger = ProgramMerger::new("test");
-        program_merger.read_package("test", mock_fs_0);
-        program_merger.read_package("library", mock_fs_1);
+        program_merger.read_package("test", mock_fs_0).unwrap();
+        program_merger.read_package("library", mock_fs_1).unwrap();
 
         let front_program = program_merger.return_merged();
         let mut program = front_program.export_program();
@@ -114,4 +126,24 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_keep_indirectly_called() {
+        let mut mock_fs = MockFileSystem::new(Utf8PathBuf::new()).unwrap();
+        mock_fs.insert_file(
+            Utf8PathBuf::from("main.ing"),
+            "pub fn main() { a(); } fn a() { b(); } fn b() { a(); }",
+        );
+
+        let mut program_merger = ProgramMerger::new("test");
+        program_merger.read_package("test", mock_fs).unwrap();
+
+        let front_program = program_merger.return_merged();
+        let mut program = front_program.export_program();
+
+        optimize(&mut program, &mut vec![Box::new(DeleteUnused)]);
+
+        assert_eq!(program.function_definitions.len(), 3);
+        assert!(program.function_definitions.contains_key("test/root/0_b"));
+    }
 }